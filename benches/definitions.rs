@@ -0,0 +1,87 @@
+use bevy::{
+    prelude::*,
+    render::render_resource::{Extent3d, TextureDimension, TextureFormat},
+    utils::HashMap,
+};
+use bevy_atlas_loader::{
+    test_support::minimal_app, AtlasDefinition, AtlasTexturePlugin, DynamicAtlasKey,
+    DynamicAtlasTextures, GridAtlasDefinition, TypedAtlasDefinition,
+};
+use criterion::{black_box, criterion_group, criterion_main, BatchSize, BenchmarkId, Criterion};
+
+fn grid_rects(c: &mut Criterion) {
+    let grid =
+        GridAtlasDefinition::grid("sheet.png", 64, 64, UVec2::new(16, 16)).with_padding(UVec2::ONE);
+    let image_size = grid.size();
+
+    c.bench_function("grid_rects 64x64", |b| {
+        b.iter(|| grid.rects(black_box(image_size)).unwrap())
+    });
+}
+
+fn definitions_until_done(c: &mut Criterion) {
+    let mut group = c.benchmark_group("definitions_until_done");
+    group.sample_size(10);
+    for count in [1, 10, 100] {
+        group.bench_with_input(BenchmarkId::from_parameter(count), &count, |b, &count| {
+            b.iter_batched(
+                || app_with_definitions(count),
+                |mut app| update_until_done(&mut app),
+                BatchSize::LargeInput,
+            )
+        });
+    }
+    group.finish();
+}
+
+/// Creates an app with `count` grid definitions over the same generated image.
+fn app_with_definitions(count: usize) -> App {
+    let mut app = minimal_app();
+    app.add_plugin(AtlasTexturePlugin::<DynamicAtlasKey>::default());
+
+    let image = app
+        .world
+        .get_resource_mut::<Assets<Image>>()
+        .unwrap()
+        .add(Image::new_fill(
+            Extent3d {
+                width: 256,
+                height: 256,
+                depth_or_array_layers: 1,
+            },
+            TextureDimension::D2,
+            &[255, 255, 255, 255],
+            TextureFormat::Rgba8UnormSrgb,
+        ));
+    app.world
+        .insert_resource(TypedAtlasDefinition::<DynamicAtlasKey>::from(
+            (0..count)
+                .map(|index| {
+                    (
+                        format!("atlas-{}", index),
+                        AtlasDefinition::from(GridAtlasDefinition::grid_from_image(
+                            image.clone(),
+                            16,
+                            16,
+                            UVec2::new(16, 16),
+                        )),
+                    )
+                })
+                .collect::<HashMap<String, AtlasDefinition>>(),
+        ));
+    app
+}
+
+/// Updates the app until the atlases are created, returning the number of updates needed.
+fn update_until_done(app: &mut App) -> usize {
+    let mut updates = 0;
+    while !app.world.contains_resource::<DynamicAtlasTextures>() {
+        assert!(updates < 1000, "atlases should be created");
+        app.update();
+        updates += 1;
+    }
+    updates
+}
+
+criterion_group!(benches, grid_rects, definitions_until_done);
+criterion_main!(benches);
@@ -1,7 +1,7 @@
 use bevy::prelude::*;
 use bevy_atlas_loader::{
-    atlas_textures_created, AtlasTexturePlugin, AtlasTextures, AtlasTexturesEvent,
-    GenericAtlasDefinitions, ResourceStatus, TypedAtlasDefinition,
+    atlas_textures_created, sync_atlas_sprite_handles, AtlasTexturePlugin, AtlasTextures,
+    GenericAtlasDefinitions, TypedAtlasDefinition, UsesAtlasTexture,
 };
 use bevy_common_assets::ron::RonAssetPlugin;
 use iyes_loopless::prelude::*;
@@ -17,12 +17,12 @@ enum AtlasTextureIndex {
     Pacman,
 }
 
-#[derive(Debug, Component, Clone, Copy)]
-struct UsesAtlasTexture<T>(T);
-
 #[derive(Debug, Component, Deref, DerefMut)]
 struct AtlasAnimationTimer(Timer);
 
+#[derive(Debug, Component, Deref, DerefMut)]
+struct AtlasAnimationFrames(Vec<usize>);
+
 fn main() {
     let mut app = App::new();
     app.insert_resource(WindowDescriptor {
@@ -59,7 +59,7 @@ fn main() {
             ConditionSet::new()
                 .run_in_state(GameState::Running)
                 .with_system(animate_textures)
-                .with_system(update_reloaded_textures::<AtlasTextureIndex>)
+                .with_system(sync_atlas_sprite_handles::<AtlasTextureIndex>)
                 .into(),
         );
 
@@ -86,52 +86,36 @@ fn setup_game(mut commands: Commands, atlas_textures: Res<AtlasTextures<AtlasTex
     let camera = OrthographicCameraBundle::new_2d();
     commands.spawn_bundle(camera);
 
+    let animation = atlas_textures
+        .animation(AtlasTextureIndex::Pacman, "chomp")
+        .expect("chomp animation should be defined");
+
     commands
-        .spawn_bundle(SpriteSheetBundle {
-            sprite: TextureAtlasSprite {
-                index: 0,
-                custom_size: Some(Vec2::new(128.0, 128.0)),
-                ..Default::default()
-            },
-            texture_atlas: atlas_textures.handle(AtlasTextureIndex::Pacman),
-            ..Default::default()
-        })
+        .spawn_bundle(
+            atlas_textures
+                .default_sprite_bundle(AtlasTextureIndex::Pacman, Some(Vec2::new(128.0, 128.0)))
+                .expect("Pacman atlas should be created"),
+        )
         .insert(UsesAtlasTexture(AtlasTextureIndex::Pacman))
-        .insert(AtlasAnimationTimer(Timer::from_seconds(1.0 / 5.0, true)));
-}
-
-fn update_reloaded_textures<T: Send + Sync + Eq + core::hash::Hash + 'static>(
-    mut asset_events: EventReader<AtlasTexturesEvent<T>>,
-    atlas_texture_index: Query<(Entity, &UsesAtlasTexture<T>)>,
-    mut commands: Commands,
-    atlas_textures: Res<AtlasTextures<T>>,
-) {
-    for _ in asset_events
-        .iter()
-        .filter(|ev| ev.state() == ResourceStatus::Created)
-    {
-        if let Ok((entity, index)) = atlas_texture_index.get_single() {
-            commands
-                .entity(entity)
-                .insert(atlas_textures.handle(&index.0));
-        }
-    }
+        .insert(AtlasAnimationFrames(animation.frames.clone()))
+        .insert(AtlasAnimationTimer(Timer::from_seconds(
+            animation.frame_duration(),
+            true,
+        )));
 }
 
 fn animate_textures(
     mut query: Query<(
         &mut AtlasAnimationTimer,
+        &mut AtlasAnimationFrames,
         &mut TextureAtlasSprite,
-        &Handle<TextureAtlas>,
     )>,
-    texture_assets: Res<Assets<TextureAtlas>>,
     time: Res<Time>,
 ) {
-    for (mut timer, mut sprite_texture, texture_handle) in query.iter_mut() {
-        if timer.tick(time.delta()).just_finished() {
-            if let Some(texture_atlas) = texture_assets.get(texture_handle.id) {
-                sprite_texture.index = (sprite_texture.index + 1) % texture_atlas.len();
-            }
+    for (mut timer, mut frames, mut sprite_texture) in query.iter_mut() {
+        if timer.tick(time.delta()).just_finished() && !frames.is_empty() {
+            frames.rotate_left(1);
+            sprite_texture.index = frames[0];
         }
     }
 }
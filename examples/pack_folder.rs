@@ -0,0 +1,134 @@
+//! Packs a folder of images into a single atlas image, along with an `.atlasmap` describing the
+//! regions - turning the folder atlas into an offline packer, e.g. for build scripts.
+//!
+//! Usage: `cargo run --example pack_folder -- <folder> <output>`
+//!
+//! The folder is relative to the asset folder, and the results are written as `<output>.png`
+//! and `<output>.atlasmap`. Place both in the asset folder to load them as a regular grid
+//! definition.
+use bevy::{
+    asset::AssetPlugin,
+    core_pipeline::CorePipelinePlugin,
+    prelude::*,
+    render::{settings::WgpuSettings, RenderPlugin},
+    sprite::SpritePlugin,
+    utils::HashMap,
+    window::WindowPlugin,
+};
+use bevy_atlas_loader::{
+    AtlasDefinition, AtlasTexturePlugin, AtlasTextures, AtlasTexturesEvent, FolderAtlasDefinition,
+    TypedAtlasDefinition,
+};
+use std::{
+    fmt::Write,
+    path::{Path, PathBuf},
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, strum::EnumVariantNames, strum::EnumString)]
+enum PackedAtlas {
+    Packed,
+}
+
+fn main() {
+    let mut args = std::env::args().skip(1);
+    let folder = args.next().unwrap_or_else(|| String::from("shapes"));
+    let output = PathBuf::from(args.next().unwrap_or_else(|| String::from("packed")));
+
+    // a headless app, running the same folder processing as the plugin does at runtime
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins)
+        .insert_resource(WgpuSettings {
+            backends: None,
+            ..Default::default()
+        })
+        .add_plugin(WindowPlugin::default())
+        .add_plugin(AssetPlugin::default())
+        .add_plugin(RenderPlugin::default())
+        .add_plugin(CorePipelinePlugin::default())
+        .add_plugin(SpritePlugin::default())
+        .add_plugin(AtlasTexturePlugin::<PackedAtlas>::default())
+        .add_system(exit_on_failure);
+
+    app.insert_resource(TypedAtlasDefinition::<PackedAtlas>::from(
+        [(
+            String::from("Packed"),
+            AtlasDefinition::from(FolderAtlasDefinition::folder(folder)),
+        )]
+        .into_iter()
+        .collect::<HashMap<String, AtlasDefinition>>(),
+    ));
+
+    let handle = loop {
+        app.update();
+        if let Some(atlas_textures) = app.world.get_resource::<AtlasTextures<PackedAtlas>>() {
+            break atlas_textures.handle(PackedAtlas::Packed);
+        }
+    };
+
+    let atlas = app
+        .world
+        .get_resource::<Assets<TextureAtlas>>()
+        .unwrap()
+        .get(handle)
+        .unwrap();
+    let image = app
+        .world
+        .get_resource::<Assets<Image>>()
+        .unwrap()
+        .get(&atlas.texture)
+        .unwrap();
+
+    let image_path = output.with_extension("png");
+    image::save_buffer(
+        &image_path,
+        &image.data,
+        atlas.size.x as u32,
+        atlas.size.y as u32,
+        image::ColorType::Rgba8,
+    )
+    .expect("unable to write the atlas image");
+
+    // a grid without tiles, holding the packed regions as extra regions
+    let mut atlasmap = String::new();
+    writeln!(atlasmap, "({{").unwrap();
+    writeln!(atlasmap, "    {:?}: (", output_name(&output)).unwrap();
+    writeln!(atlasmap, "        texture: {:?},", file_name(&image_path)).unwrap();
+    writeln!(atlasmap, "        columns: 0,").unwrap();
+    writeln!(atlasmap, "        rows: 0,").unwrap();
+    writeln!(atlasmap, "        tile_size: (0, 0),").unwrap();
+    writeln!(atlasmap, "        extra_regions: [").unwrap();
+    for rect in &atlas.textures {
+        let (position, size) = (rect.min.as_uvec2(), (rect.max - rect.min).as_uvec2());
+        writeln!(
+            atlasmap,
+            "            (position: ({}, {}), size: ({}, {})),",
+            position.x, position.y, size.x, size.y
+        )
+        .unwrap();
+    }
+    writeln!(atlasmap, "        ],").unwrap();
+    writeln!(atlasmap, "    ),").unwrap();
+    writeln!(atlasmap, "}})").unwrap();
+    std::fs::write(output.with_extension("atlasmap"), atlasmap)
+        .expect("unable to write the atlasmap");
+}
+
+fn exit_on_failure(mut events: EventReader<AtlasTexturesEvent<PackedAtlas>>) {
+    for event in events.iter().filter(|event| event.status().is_failed()) {
+        eprintln!("Packing failed: {}", event.error().unwrap());
+        std::process::exit(1);
+    }
+}
+
+fn output_name(output: &Path) -> String {
+    output
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().into_owned())
+        .unwrap_or_default()
+}
+
+fn file_name(path: &Path) -> String {
+    path.file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default()
+}
@@ -0,0 +1,91 @@
+//! Background color of packed atlases, filling the space not covered by any region.
+
+use bevy::{prelude::*, render::render_resource::TextureFormat, sprite::Rect};
+
+use crate::ResourceError;
+
+/// Fills all pixels of `image` outside of `rects` with `color`.
+pub(crate) fn fill_background(
+    image: &mut Image,
+    rects: &[Rect],
+    color: Color,
+) -> Result<(), ResourceError> {
+    let [r, g, b, a] = color
+        .as_rgba_f32()
+        .map(|channel| (channel * 255.).round() as u8);
+    let fill = match image.texture_descriptor.format {
+        TextureFormat::Rgba8Unorm | TextureFormat::Rgba8UnormSrgb => [r, g, b, a],
+        TextureFormat::Bgra8Unorm | TextureFormat::Bgra8UnormSrgb => [b, g, r, a],
+        format => return Err(ResourceError::UnsupportedTextureFormat(format)),
+    };
+    let width = image.size().x as usize;
+    // only the first mip level is filled
+    let mut covered = vec![false; width * image.size().y as usize];
+    for rect in rects {
+        for y in rect.min.y as usize..rect.max.y as usize {
+            covered[y * width + rect.min.x as usize..y * width + rect.max.x as usize]
+                .iter_mut()
+                .for_each(|pixel| *pixel = true);
+        }
+    }
+    image
+        .data
+        .chunks_exact_mut(4)
+        .zip(covered)
+        .filter(|(_, covered)| !covered)
+        .for_each(|(pixel, _)| pixel.copy_from_slice(&fill));
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy::render::render_resource::{Extent3d, TextureDimension};
+
+    fn image(width: u32, height: u32, format: TextureFormat) -> Image {
+        Image::new(
+            Extent3d {
+                width,
+                height,
+                depth_or_array_layers: 1,
+            },
+            TextureDimension::D2,
+            vec![7; (width * height * 4) as usize],
+            format,
+        )
+    }
+
+    #[test]
+    fn space_outside_the_regions_is_filled() {
+        let mut image = image(3, 1, TextureFormat::Rgba8UnormSrgb);
+        let rects = [Rect {
+            min: Vec2::new(1., 0.),
+            max: Vec2::new(2., 1.),
+        }];
+
+        fill_background(&mut image, &rects, Color::rgba(1., 0., 0., 1.)).unwrap();
+
+        assert_eq!(image.data, vec![255, 0, 0, 255, 7, 7, 7, 7, 255, 0, 0, 255]);
+    }
+
+    #[test]
+    fn color_is_written_in_bgra_order() {
+        let mut image = image(1, 1, TextureFormat::Bgra8Unorm);
+
+        fill_background(&mut image, &[], Color::rgba(1., 0., 0., 1.)).unwrap();
+
+        assert_eq!(image.data, vec![0, 0, 255, 255]);
+    }
+
+    #[test]
+    fn other_formats_are_unsupported() {
+        let mut image = image(1, 1, TextureFormat::Rg16Uint);
+
+        assert_eq!(
+            fill_background(&mut image, &[], Color::BLACK),
+            Err(ResourceError::UnsupportedTextureFormat(
+                TextureFormat::Rg16Uint
+            ))
+        );
+    }
+}
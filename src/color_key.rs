@@ -0,0 +1,76 @@
+//! Color-key transparency for images lacking an alpha channel.
+
+use bevy::{prelude::*, render::render_resource::TextureFormat};
+
+use crate::ResourceError;
+
+/// Makes all pixels of `image` matching the `color_key` fully transparent.
+pub(crate) fn apply_color_key(image: &mut Image, color_key: [u8; 3]) -> Result<(), ResourceError> {
+    let [r, g, b] = color_key;
+    let key = match image.texture_descriptor.format {
+        TextureFormat::Rgba8Unorm | TextureFormat::Rgba8UnormSrgb => [r, g, b],
+        TextureFormat::Bgra8Unorm | TextureFormat::Bgra8UnormSrgb => [b, g, r],
+        format => return Err(ResourceError::UnsupportedTextureFormat(format)),
+    };
+    image
+        .data
+        .chunks_exact_mut(4)
+        .filter(|pixel| pixel[..3] == key)
+        .for_each(|pixel| pixel[3] = 0);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy::render::render_resource::{Extent3d, TextureDimension};
+
+    fn image(data: Vec<u8>, format: TextureFormat) -> Image {
+        Image::new(
+            Extent3d {
+                width: data.len() as u32 / 4,
+                height: 1,
+                depth_or_array_layers: 1,
+            },
+            TextureDimension::D2,
+            data,
+            format,
+        )
+    }
+
+    #[test]
+    fn matching_pixels_become_transparent() {
+        let mut image = image(
+            vec![255, 0, 255, 255, 255, 0, 254, 255, 0, 0, 0, 255],
+            TextureFormat::Rgba8UnormSrgb,
+        );
+
+        apply_color_key(&mut image, [255, 0, 255]).unwrap();
+
+        assert_eq!(
+            image.data,
+            vec![255, 0, 255, 0, 255, 0, 254, 255, 0, 0, 0, 255]
+        );
+    }
+
+    #[test]
+    fn key_is_matched_in_bgra_order() {
+        let mut image = image(vec![3, 2, 1, 255, 1, 2, 3, 255], TextureFormat::Bgra8Unorm);
+
+        apply_color_key(&mut image, [1, 2, 3]).unwrap();
+
+        assert_eq!(image.data, vec![3, 2, 1, 0, 1, 2, 3, 255]);
+    }
+
+    #[test]
+    fn other_formats_are_unsupported() {
+        let mut image = image(vec![0; 8], TextureFormat::Rg16Uint);
+
+        assert_eq!(
+            apply_color_key(&mut image, [0, 0, 0]),
+            Err(ResourceError::UnsupportedTextureFormat(
+                TextureFormat::Rg16Uint
+            ))
+        );
+    }
+}
@@ -0,0 +1,43 @@
+//! Selection of the color space used when sampling the atlas images.
+
+use bevy::render::render_resource::TextureFormat;
+
+/// Returns the linear counterpart of an sRGB `format`.
+///
+/// Formats without an sRGB counterpart are returned as is.
+pub(crate) fn linear_format(format: TextureFormat) -> TextureFormat {
+    match format {
+        TextureFormat::Rgba8UnormSrgb => TextureFormat::Rgba8Unorm,
+        TextureFormat::Bgra8UnormSrgb => TextureFormat::Bgra8Unorm,
+        format => format,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn srgb_formats_become_linear() {
+        assert_eq!(
+            linear_format(TextureFormat::Rgba8UnormSrgb),
+            TextureFormat::Rgba8Unorm
+        );
+        assert_eq!(
+            linear_format(TextureFormat::Bgra8UnormSrgb),
+            TextureFormat::Bgra8Unorm
+        );
+    }
+
+    #[test]
+    fn other_formats_are_kept() {
+        assert_eq!(
+            linear_format(TextureFormat::Rgba8Unorm),
+            TextureFormat::Rgba8Unorm
+        );
+        assert_eq!(
+            linear_format(TextureFormat::R32Float),
+            TextureFormat::R32Float
+        );
+    }
+}
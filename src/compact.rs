@@ -0,0 +1,229 @@
+//! Compact notation for grid and folder definitions, for quick prototyping in tests or tooling
+//! where a full RON file is overkill.
+
+use bevy::prelude::*;
+use derive_more::Display;
+use std::str::FromStr;
+
+use crate::{AtlasDefinition, FolderAtlasDefinition, GridAtlasDefinition};
+
+/// Reason for failing to parse the compact notation of a definition.
+///
+/// The notation is `grid:<texture>:<columns>x<rows>:<width>x<height>` for grids, and
+/// `folder:<path>` for folders.
+#[derive(Debug, PartialEq, Eq, Clone, Display)]
+pub enum ParseDefinitionError {
+    /// The notation doesn't start with a known kind of definition.
+    #[display(fmt = "unknown kind {:?}, expected \"grid\" or \"folder\"", _0)]
+    UnknownKind(String),
+    /// The notation is missing the path of the texture or folder.
+    #[display(fmt = "missing path")]
+    MissingPath,
+    /// The notation doesn't have the parts expected for its kind.
+    #[display(fmt = "expected {} parts separated by ':', found {}", expected, actual)]
+    PartCount { expected: usize, actual: usize },
+    /// A size isn't given as two numbers separated by `x`, e.g. `8x4`.
+    #[display(fmt = "invalid size {:?}, expected e.g. \"8x4\"", _0)]
+    InvalidSize(String),
+}
+
+impl std::error::Error for ParseDefinitionError {}
+
+/// Parses `grid:<texture>:<columns>x<rows>:<width>x<height>`.
+///
+/// # Example:
+/// ```rust
+/// # use bevy::prelude::*;
+/// # use bevy_atlas_loader::*;
+/// let grid: GridAtlasDefinition = "grid:Pac-Man.png:8x4:20x20".parse().unwrap();
+/// assert_eq!((grid.columns, grid.rows), (8, 4));
+/// assert_eq!(grid.tile_size, UVec2::new(20, 20));
+/// ```
+impl FromStr for GridAtlasDefinition {
+    type Err = ParseDefinitionError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let rest = strip_kind(s, "grid")?;
+        // the path itself may contain ':', so the sizes are split off from the end
+        let parts = rest.rsplitn(3, ':').collect::<Vec<_>>();
+        let (tile_size, grid, texture) = match parts[..] {
+            [tile_size, grid, texture] => (tile_size, grid, texture),
+            _ => {
+                return Err(ParseDefinitionError::PartCount {
+                    expected: 4,
+                    actual: parts.len() + 1,
+                })
+            }
+        };
+        if texture.is_empty() {
+            return Err(ParseDefinitionError::MissingPath);
+        }
+        let (columns, rows) = parse_size(grid)?;
+        let (width, height) = parse_size(tile_size)?;
+        Ok(GridAtlasDefinition::grid(
+            texture,
+            columns,
+            rows,
+            UVec2::new(width, height),
+        ))
+    }
+}
+
+/// Parses `folder:<path>`.
+///
+/// # Example:
+/// ```rust
+/// # use std::path::Path;
+/// # use bevy_atlas_loader::*;
+/// let folder: FolderAtlasDefinition = "folder:shapes".parse().unwrap();
+/// assert_eq!(folder.path, Path::new("shapes"));
+/// ```
+impl FromStr for FolderAtlasDefinition {
+    type Err = ParseDefinitionError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let path = strip_kind(s, "folder")?;
+        if path.is_empty() {
+            return Err(ParseDefinitionError::MissingPath);
+        }
+        Ok(FolderAtlasDefinition::folder(path))
+    }
+}
+
+/// Parses the compact notation of either a grid or a folder definition.
+///
+/// # Example:
+/// ```rust
+/// # use bevy_atlas_loader::*;
+/// let definition: AtlasDefinition = "folder:shapes".parse().unwrap();
+/// assert_eq!(definition.kind(), AtlasDefinitionKind::Folder);
+/// ```
+impl FromStr for AtlasDefinition {
+    type Err = ParseDefinitionError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.split(':').next().unwrap_or_default() {
+            "grid" => s.parse::<GridAtlasDefinition>().map(Into::into),
+            "folder" => s.parse::<FolderAtlasDefinition>().map(Into::into),
+            kind => Err(ParseDefinitionError::UnknownKind(kind.to_string())),
+        }
+    }
+}
+
+/// Returns the remainder of `s` after the `kind` prefix.
+fn strip_kind<'a>(s: &'a str, kind: &str) -> Result<&'a str, ParseDefinitionError> {
+    match s.split_once(':') {
+        Some((prefix, rest)) if prefix == kind => Ok(rest),
+        Some((prefix, _)) => Err(ParseDefinitionError::UnknownKind(prefix.to_string())),
+        None if s == kind => Err(ParseDefinitionError::MissingPath),
+        None => Err(ParseDefinitionError::UnknownKind(s.to_string())),
+    }
+}
+
+/// Parses a size such as `8x4`.
+fn parse_size<N: FromStr>(size: &str) -> Result<(N, N), ParseDefinitionError> {
+    let invalid = || ParseDefinitionError::InvalidSize(size.to_string());
+    let (x, y) = size.split_once('x').ok_or_else(invalid)?;
+    Ok((
+        x.parse().map_err(|_| invalid())?,
+        y.parse().map_err(|_| invalid())?,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::Path;
+
+    mod grid {
+        use super::*;
+
+        #[test]
+        fn parses_texture_and_sizes() {
+            let grid: GridAtlasDefinition = "grid:sheets/Pac-Man.png:8x4:20x19".parse().unwrap();
+            assert_eq!(grid.texture, Path::new("sheets/Pac-Man.png"));
+            assert_eq!((grid.columns, grid.rows), (8, 4));
+            assert_eq!(grid.tile_size, UVec2::new(20, 19));
+        }
+
+        #[test]
+        fn allows_colons_in_the_path() {
+            let grid: GridAtlasDefinition = "grid:C:/Pac-Man.png:8x4:20x20".parse().unwrap();
+            assert_eq!(grid.texture, Path::new("C:/Pac-Man.png"));
+        }
+
+        #[test]
+        fn rejects_missing_parts() {
+            assert_eq!(
+                "grid:Pac-Man.png:8x4".parse::<GridAtlasDefinition>().err(),
+                Some(ParseDefinitionError::PartCount {
+                    expected: 4,
+                    actual: 3
+                })
+            );
+            assert_eq!(
+                "grid::8x4:20x20".parse::<GridAtlasDefinition>().err(),
+                Some(ParseDefinitionError::MissingPath)
+            );
+        }
+
+        #[test]
+        fn rejects_invalid_sizes() {
+            assert_eq!(
+                "grid:Pac-Man.png:8by4:20x20"
+                    .parse::<GridAtlasDefinition>()
+                    .err(),
+                Some(ParseDefinitionError::InvalidSize(String::from("8by4")))
+            );
+            assert_eq!(
+                "grid:Pac-Man.png:8x4:20x-1"
+                    .parse::<GridAtlasDefinition>()
+                    .err(),
+                Some(ParseDefinitionError::InvalidSize(String::from("20x-1")))
+            );
+        }
+    }
+
+    mod folder {
+        use super::*;
+
+        #[test]
+        fn parses_path() {
+            let folder: FolderAtlasDefinition = "folder:textures/ui".parse().unwrap();
+            assert_eq!(folder.path, Path::new("textures/ui"));
+        }
+
+        #[test]
+        fn rejects_missing_path() {
+            assert_eq!(
+                "folder:".parse::<FolderAtlasDefinition>().err(),
+                Some(ParseDefinitionError::MissingPath)
+            );
+            assert_eq!(
+                "folder".parse::<FolderAtlasDefinition>().err(),
+                Some(ParseDefinitionError::MissingPath)
+            );
+        }
+    }
+
+    mod definition {
+        use super::*;
+        use crate::AtlasDefinitionKind;
+
+        #[test]
+        fn dispatches_on_kind() {
+            let grid: AtlasDefinition = "grid:Pac-Man.png:8x4:20x20".parse().unwrap();
+            assert_eq!(grid.kind(), AtlasDefinitionKind::Grid);
+            let folder: AtlasDefinition = "folder:shapes".parse().unwrap();
+            assert_eq!(folder.kind(), AtlasDefinitionKind::Folder);
+        }
+
+        #[test]
+        fn rejects_unknown_kind() {
+            assert_eq!(
+                "tiles:Pac-Man.png".parse::<AtlasDefinition>().err(),
+                Some(ParseDefinitionError::UnknownKind(String::from("tiles")))
+            );
+        }
+    }
+}
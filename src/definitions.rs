@@ -1,8 +1,20 @@
-use bevy::{prelude::*, reflect::TypeUuid, sprite::TextureAtlas, utils::HashMap};
-use derive_more::{Constructor, Deref, DerefMut, From, IsVariant};
+use bevy::{
+    asset::AssetServerError,
+    prelude::*,
+    reflect::{FromReflect, TypeUuid},
+    sprite::{Rect, TextureAtlas},
+    utils::{HashMap, HashSet},
+};
+use derive_more::{Constructor, Deref, DerefMut, Display, From, IsVariant};
 use enum_default::EnumDefault;
-use serde::Deserialize;
-use std::{marker::PhantomData, path::PathBuf};
+use serde::{Deserialize, Serialize};
+use smart_default::SmartDefault;
+use std::{
+    marker::PhantomData,
+    path::{Path, PathBuf},
+};
+
+use crate::{AtlasKey, FolderManifest, ResourceError, TexturePackerSheet};
 
 /// Trait for getting the created [TextureAtlas] Handle from any definition
 pub(crate) trait GetTextureAtlas {
@@ -64,32 +76,204 @@ pub(crate) trait GetTextureAtlas {
 ///     ));
 /// });
 /// ```
-#[derive(Debug, Deserialize, TypeUuid, Deref, DerefMut, Constructor, Default, From)]
+#[derive(Debug, Clone, Deserialize, TypeUuid, Deref, DerefMut, Constructor, Default, From)]
 #[uuid = "ef608653-e978-4a71-98e5-05c55911cfc0"]
-pub struct GenericAtlasDefinitions(HashMap<String, AtlasDefinition>);
+pub struct GenericAtlasDefinitions(AtlasDefinitionMap);
+
+/// Map of the [AtlasDefinition]s of [GenericAtlasDefinitions], by key.
+///
+/// With the `ordered` feature, the definitions keep the order they're inserted or listed in a
+/// file, and are processed in that order.
+#[cfg(not(feature = "ordered"))]
+pub type AtlasDefinitionMap = HashMap<String, AtlasDefinition>;
+
+/// Map of the [AtlasDefinition]s of [GenericAtlasDefinitions], by key.
+///
+/// With the `ordered` feature, the definitions keep the order they're inserted or listed in a
+/// file, and are processed in that order.
+#[cfg(feature = "ordered")]
+pub type AtlasDefinitionMap = indexmap::IndexMap<String, AtlasDefinition>;
+
+#[cfg(feature = "ordered")]
+impl From<HashMap<String, AtlasDefinition>> for GenericAtlasDefinitions {
+    fn from(definitions: HashMap<String, AtlasDefinition>) -> Self {
+        Self(definitions.into_iter().collect())
+    }
+}
+
+impl FromIterator<(String, AtlasDefinition)> for GenericAtlasDefinitions {
+    fn from_iter<I: IntoIterator<Item = (String, AtlasDefinition)>>(iter: I) -> Self {
+        Self(iter.into_iter().collect())
+    }
+}
+
+impl GenericAtlasDefinitions {
+    /// Merges the definitions of several named sources, in order.
+    ///
+    /// For duplicate keys, the definition of the first source is kept.
+    pub(crate) fn merge<'a>(
+        sources: impl IntoIterator<Item = (String, &'a GenericAtlasDefinitions)>,
+    ) -> Self {
+        let mut merged = AtlasDefinitionMap::default();
+        for (source, definitions) in sources {
+            for (key, definition) in definitions.iter() {
+                if merged.contains_key(key) {
+                    warn!(
+                        key = key.as_str(),
+                        source = source.as_str(),
+                        "Duplicate AtlasDefinition - it will be ignored."
+                    );
+                } else {
+                    merged.insert(key.clone(), definition.clone());
+                }
+            }
+        }
+        Self(merged)
+    }
+
+    /// Whether the definition `key` should be created for `profile`, when only the `requested`
+    /// variants are.
+    ///
+    /// Definitions of the profile replace those of the same variant without a profile.
+    pub(crate) fn is_selected(
+        &self,
+        key: &str,
+        profile: Option<&str>,
+        requested: Option<&HashSet<String>>,
+    ) -> bool {
+        let (variant, key_profile) = split_profile(key);
+        let active = match (key_profile, profile) {
+            (Some(key_profile), Some(profile)) => key_profile == profile,
+            (Some(_), None) => false,
+            (None, Some(profile)) => {
+                !self.contains_key(&format!("{}{}{}", variant, PROFILE_SEPARATOR, profile))
+            }
+            (None, None) => true,
+        };
+        active && is_requested(requested, variant)
+    }
+
+    /// Keys of the definitions to create for `profile`, see [is_selected](Self::is_selected).
+    pub(crate) fn selected_keys(
+        &self,
+        profile: Option<&str>,
+        requested: Option<&HashSet<String>>,
+    ) -> HashSet<String> {
+        self.keys()
+            .filter(|key| self.is_selected(key, profile, requested))
+            .cloned()
+            .collect()
+    }
+
+    /// Parses definitions in the RON format.
+    pub fn from_ron_bytes(bytes: &[u8]) -> anyhow::Result<Self> {
+        Ok(ron::de::from_bytes(bytes)?)
+    }
+
+    /// Parses definitions in the JSON format, e.g. as emitted by other tools.
+    pub fn from_json_bytes(bytes: &[u8]) -> anyhow::Result<Self> {
+        Ok(serde_json::from_slice(bytes)?)
+    }
+
+    /// Parses definitions in the TOML format, with a table per key.
+    #[cfg(feature = "toml")]
+    pub fn from_toml_bytes(bytes: &[u8]) -> anyhow::Result<Self> {
+        Ok(toml::from_slice(bytes)?)
+    }
+
+    /// Parses definitions in the TOML format, with a table per key.
+    #[cfg(feature = "toml")]
+    pub fn from_toml_str(toml: &str) -> anyhow::Result<Self> {
+        Ok(toml::from_str(toml)?)
+    }
+}
 
 /// Defines how a [TextureAtlas] is to be created from 1 or more textures.
 ///
 /// See [GenericAtlasDefinitions].
 ///
+/// The definitions implement `Reflect`, though their paths (e.g. `texture`), frames, color keys
+/// and process state are ignored by it - `PathBuf` doesn't implement `Reflect`. Inspectors thus
+/// show the layout of a definition, but not the images it is created from.
+///
 /// # Example:
 /// ```rust
 /// # use std::path::Path;
+/// # use bevy::prelude::*;
 /// # use bevy_atlas_loader::*;
-/// let _: AtlasDefinition = GridAtlasDefinition {
-///     texture: Path::new("image.png").into(),
-///     columns: 4,
-///     rows: 3,
-///     tile_size: (16, 16),
-///     ..Default::default()
-/// }.into();
+/// let _: AtlasDefinition =
+///     GridAtlasDefinition::grid(Path::new("image.png"), 4, 3, UVec2::new(16, 16)).into();
 /// ```
-#[derive(Debug, Deserialize, From)]
+#[derive(Debug, Clone, PartialEq, Deserialize, From, Reflect)]
+#[reflect_value]
 #[serde(untagged)]
+#[non_exhaustive]
 pub enum AtlasDefinition {
     Grid(GridAtlasDefinition),
+    GridByBounds(GridByBoundsAtlasDefinition),
     Manual(PatchAtlasDefinition),
+    Frames(FramesAtlasDefinition),
     Folder(FolderAtlasDefinition),
+    FolderManifest(FolderManifestAtlasDefinition),
+    TexturePacker(TexturePackerAtlasDefinition),
+    Array(ArrayAtlasDefinition),
+    Paged(PagedAtlasDefinition),
+    AutoDetect(AutoDetectAtlasDefinition),
+    Glob(GlobAtlasDefinition),
+    Mosaic(MosaicAtlasDefinition),
+    FractionalGrid(FractionalGridAtlasDefinition),
+}
+
+/// Kind of an [AtlasDefinition], for branching without matching on the definition itself.
+///
+/// # Example:
+/// ```rust
+/// # use std::path::Path;
+/// # use bevy_atlas_loader::*;
+/// let definition: AtlasDefinition = FolderAtlasDefinition::folder(Path::new("images")).into();
+/// assert_eq!(definition.kind(), AtlasDefinitionKind::Folder);
+/// ```
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy, Display, IsVariant)]
+#[non_exhaustive]
+pub enum AtlasDefinitionKind {
+    Grid,
+    GridByBounds,
+    Manual,
+    Frames,
+    Folder,
+    FolderManifest,
+    TexturePacker,
+    Array,
+    Paged,
+    AutoDetect,
+    Glob,
+    Mosaic,
+    FractionalGrid,
+}
+
+impl AtlasDefinitionKind {
+    /// Every kind of definition, e.g. for an editor offering to add a definition of some kind.
+    ///
+    /// # Example:
+    /// ```rust
+    /// # use bevy_atlas_loader::*;
+    /// assert!(AtlasDefinitionKind::KINDS.contains(&AtlasDefinitionKind::Folder));
+    /// ```
+    pub const KINDS: &'static [AtlasDefinitionKind] = &[
+        AtlasDefinitionKind::Grid,
+        AtlasDefinitionKind::GridByBounds,
+        AtlasDefinitionKind::Manual,
+        AtlasDefinitionKind::Frames,
+        AtlasDefinitionKind::Folder,
+        AtlasDefinitionKind::FolderManifest,
+        AtlasDefinitionKind::TexturePacker,
+        AtlasDefinitionKind::Array,
+        AtlasDefinitionKind::Paged,
+        AtlasDefinitionKind::AutoDetect,
+        AtlasDefinitionKind::Glob,
+        AtlasDefinitionKind::Mosaic,
+        AtlasDefinitionKind::FractionalGrid,
+    ];
 }
 
 /// Defines a [TextureAtlas] composed from a grid of an image.
@@ -97,285 +281,2580 @@ pub enum AtlasDefinition {
 /// # Example:
 /// ```rust
 /// # use std::path::Path;
+/// # use bevy::prelude::*;
 /// # use bevy_atlas_loader::*;
-/// let _ = GridAtlasDefinition {
-///     texture: Path::new("image.png").into(),
-///     columns: 4,
-///     rows: 3,
-///     tile_size: (16, 16),
-///     ..Default::default()
-/// };
+/// let _ = GridAtlasDefinition::grid(Path::new("image.png"), 4, 3, UVec2::new(16, 16))
+///     .with_padding(UVec2::new(1, 1));
 /// ```
-#[derive(Debug, Default, Deserialize, Constructor)]
+#[derive(Debug, Clone, PartialEq, SmartDefault, Deserialize, Reflect)]
 pub struct GridAtlasDefinition {
+    #[reflect(ignore)]
     pub texture: PathBuf,
+    /// Further sheets sharing the layout of `texture`, e.g. an animation split across several
+    /// files. Their tiles follow those of `texture`, indexed continuously.
+    #[serde(default)]
+    #[reflect(ignore)]
+    pub textures: Vec<PathBuf>,
+    /// Number of columns, or `0` for as many as fit the width of the image. With a tile width of
+    /// `0` as well, the grid has no tiles - e.g. holding only `extra_regions`.
     pub columns: usize,
+    /// Number of rows, or `0` for as many as fit the height of the image. With a tile height of
+    /// `0` as well, the grid has no tiles.
     pub rows: usize,
-    pub tile_size: (usize, usize),
-    pub padding: Option<(usize, usize)>,
+    pub tile_size: UVec2,
+    /// Gap between the tiles.
+    pub padding: Option<UVec2>,
+    /// Order in which the tiles are indexed.
+    #[serde(default)]
+    #[reflect(ignore)]
+    pub order: GridOrder,
+    /// Inset of the region used inside each tile, e.g. for skipping an extruded border.
+    ///
+    /// Unlike `padding`, the margin is part of the tile itself.
+    #[serde(default)]
+    pub inner_margin: Option<UVec2>,
+    /// Color of the image to treat as transparent, for images lacking an alpha channel.
+    #[serde(default)]
+    #[reflect(ignore)]
+    pub color_key: Option<[u8; 3]>,
+    /// Nine-slice borders of every region, for use when rendering e.g. UI elements.
+    #[serde(default)]
+    pub nine_patch: Option<NineSliceBorders>,
+    /// Factor to resample the image by before creating the atlas, e.g. `0.5` for art made at
+    /// twice the in-game resolution. Regions are given in pixels of the original image.
+    ///
+    /// Resampling uses nearest-neighbour, which is exact for integer factors such as `0.5` or
+    /// `2.0`, but drops details when downscaling as pixels aren't averaged.
+    #[serde(default)]
+    pub scale: Option<f32>,
+    /// Group of grids packed into a single, shared [TextureAtlas] - e.g. for batching the draw
+    /// calls of several small sheets. The sheets are stacked in the order of their keys, see
+    /// [AtlasTextures::index_offset](crate::AtlasTextures::index_offset).
+    ///
+    /// As the grids of a group share a single `Handle<TextureAtlas>`,
+    /// [AtlasTextures::variant_of](crate::AtlasTextures::variant_of) can't tell them apart.
+    #[serde(default)]
+    pub combine: Option<String>,
+    /// Irregular regions appended after the tiles of the grid.
+    #[serde(default)]
+    pub extra_regions: Vec<AtlasRegion>,
+    #[serde(flatten)]
+    pub image: ImageOptions,
+    #[serde(flatten)]
+    pub options: DefinitionOptions,
     #[doc(hidden)]
     #[serde(skip)]
-    pub state: SingleTextureProcessState,
+    #[reflect(ignore)]
+    pub state: GridProcessState,
 }
 
-/// Defines a [TextureAtlas] composed as similar sized, mahually placed, regions inside an image.
+/// Order in which the tiles of a [GridAtlasDefinition] are indexed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, EnumDefault, Deserialize)]
+pub enum GridOrder {
+    /// Row by row, left to right.
+    #[default]
+    RowMajor,
+    /// Column by column, top to bottom - as some exporters lay out animations.
+    ColumnMajor,
+}
+
+/// Insets, in pixels, of the borders of a nine-slice region.
+///
+/// The corners keep their size, while the edges and center are stretched.
+#[derive(
+    Debug,
+    Default,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    Deserialize,
+    Serialize,
+    Constructor,
+    Reflect,
+    FromReflect,
+)]
+pub struct NineSliceBorders {
+    pub left: u32,
+    pub right: u32,
+    pub top: u32,
+    pub bottom: u32,
+}
+
+/// Region of an image, in pixels.
+#[derive(
+    Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize, Constructor, Reflect, FromReflect,
+)]
+pub struct AtlasRegion {
+    pub position: UVec2,
+    pub size: UVec2,
+}
+
+/// Defines a [TextureAtlas] composed from a grid of an image, given by the top-left corner of its
+/// first tile and the bottom-right corner of its last tile rather than by its number of tiles.
+///
+/// The bounds must fit a whole number of tiles, including the padding between them.
 ///
 /// # Example:
-/// ```
+/// ```rust
 /// # use std::path::Path;
+/// # use bevy::prelude::*;
 /// # use bevy_atlas_loader::*;
-/// let _ = PatchAtlasDefinition {
-///     texture: Path::new("image.png").into(),
-///     width: 16,
-///     height: 16,
-///     positions: vec![(0, 0)],
-///     ..Default::default()
-/// };
+/// let _ = GridByBoundsAtlasDefinition::bounds(
+///     Path::new("image.png"),
+///     UVec2::new(8, 8),
+///     UVec2::new(72, 56),
+///     UVec2::new(16, 16),
+/// );
 /// ```
-#[derive(Debug, Default, Deserialize)]
-pub struct PatchAtlasDefinition {
+#[derive(Debug, Clone, PartialEq, SmartDefault, Deserialize, Reflect)]
+pub struct GridByBoundsAtlasDefinition {
+    #[reflect(ignore)]
     pub texture: PathBuf,
-    pub width: usize,
-    pub height: usize,
-    pub positions: Vec<(usize, usize)>,
+    /// Top-left corner of the first tile.
+    pub first: UVec2,
+    /// Bottom-right corner of the last tile.
+    pub last: UVec2,
+    pub tile_size: UVec2,
+    /// Gap between the tiles.
+    #[serde(default)]
+    pub padding: Option<UVec2>,
+    #[serde(flatten)]
+    pub image: ImageOptions,
+    #[serde(flatten)]
+    pub options: DefinitionOptions,
     #[doc(hidden)]
     #[serde(skip)]
+    #[reflect(ignore)]
     pub state: SingleTextureProcessState,
 }
 
-/// Defines a [TextureAtlas] as a series of images, read from a folder.
-/// The sequence of the images is unknown, and may change each invocation.
+/// Defines a [TextureAtlas] composed as similar sized, mahually placed, regions inside an image.
 ///
 /// # Example:
-/// ```rust
+/// ```
 /// # use std::path::Path;
+/// # use bevy::prelude::*;
 /// # use bevy_atlas_loader::*;
-/// let _ = FolderAtlasDefinition {
-///     path: Path::new("imagefolder").into(),
-///     ..Default::default()
-/// };
+/// let _ = PatchAtlasDefinition::patch(Path::new("image.png"), 16, 16, vec![UVec2::new(0, 0)]);
 /// ```
-#[derive(Debug, Default, Deserialize)]
-pub struct FolderAtlasDefinition {
-    pub path: PathBuf,
+#[derive(Debug, Clone, PartialEq, SmartDefault, Deserialize, Reflect)]
+pub struct PatchAtlasDefinition {
+    #[reflect(ignore)]
+    pub texture: PathBuf,
+    pub width: u32,
+    pub height: u32,
+    pub positions: Vec<UVec2>,
+    /// How to treat positions listed more than once.
+    #[serde(default)]
+    #[reflect(ignore)]
+    pub duplicates: DuplicatePositions,
+    /// Color of the image to treat as transparent, for images lacking an alpha channel.
+    #[serde(default)]
+    #[reflect(ignore)]
+    pub color_key: Option<[u8; 3]>,
+    /// Nine-slice borders of every region, for use when rendering e.g. UI elements.
+    #[serde(default)]
+    pub nine_patch: Option<NineSliceBorders>,
+    /// Factor to resample the image by before creating the atlas, see
+    /// [GridAtlasDefinition::scale].
+    #[serde(default)]
+    pub scale: Option<f32>,
+    #[serde(flatten)]
+    pub image: ImageOptions,
+    #[serde(flatten)]
+    pub options: DefinitionOptions,
     #[doc(hidden)]
     #[serde(skip)]
-    pub state: MultiTextureProcessState,
+    #[reflect(ignore)]
+    pub state: SingleTextureProcessState,
 }
 
-#[doc(hidden)]
-#[derive(Debug, EnumDefault)]
-pub enum SingleTextureProcessState {
+/// How a [PatchAtlasDefinition] treats positions listed more than once, usually a copy-paste
+/// error.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, EnumDefault, Deserialize)]
+pub enum DuplicatePositions {
+    /// Warn, keeping the duplicate regions.
     #[default]
-    None,
-    LoadingTexture(HandleUntyped),
-    AtlasCreated(Handle<TextureAtlas>),
+    Keep,
+    /// Warn, removing the duplicate regions.
+    Remove,
+    /// Fail with [ResourceError::DuplicateRegion].
+    Fail,
 }
 
-#[doc(hidden)]
-#[derive(Debug, EnumDefault)]
-pub enum MultiTextureProcessState {
+/// How the image of an atlas is sampled past its edges.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, EnumDefault, Deserialize)]
+pub enum AddressMode {
+    /// The pixels of the edges extend outwards.
     #[default]
-    None,
-    LoadingTextures(Vec<HandleUntyped>),
-    AtlasCreated(Handle<TextureAtlas>),
+    ClampToEdge,
+    /// The image repeats, e.g. for a seamless tiled background.
+    Repeat,
+    /// The image repeats, mirrored every other time.
+    MirrorRepeat,
 }
 
-/// Resource specifying how to create a specific [AtlasTextures<T>](crate::AtlasTextures<T>).
+impl From<AddressMode> for bevy::render::render_resource::AddressMode {
+    fn from(address_mode: AddressMode) -> Self {
+        match address_mode {
+            AddressMode::ClampToEdge => Self::ClampToEdge,
+            AddressMode::Repeat => Self::Repeat,
+            AddressMode::MirrorRepeat => Self::MirrorRepeat,
+        }
+    }
+}
+
+/// Options common to every kind of definition. In files, they're given along with the other
+/// fields of the definition.
 ///
-/// For an example of how to load the definition as an asset, see [GenericAtlasDefinitions].
 /// # Example:
+/// ```ron
+/// ({
+///     "Pacman": (
+///         texture: "Pac-Man.png",
+///         columns: 8,
+///         rows: 4,
+///         tile_size: (20, 20),
+///         default_index: 2,
+///         priority: 10,
+///     )
+/// })
 /// ```
-/// # use bevy::{
-/// #     prelude::*,
-/// #     utils::HashMap,
-/// #     asset::AssetPlugin,
-/// #     core_pipeline::CorePipelinePlugin,
-/// #     render::{settings::WgpuSettings, RenderPlugin},
-/// #     sprite::SpritePlugin,
-/// #     window::WindowPlugin,
-/// #     MinimalPlugins,
-/// # };
-/// # use bevy_atlas_loader::*;
-/// # use std::{
-/// #     path::Path,
-/// #     sync::{atomic::AtomicBool, Arc},
-/// # };
-/// #
-/// #[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
-/// #[derive(strum::EnumVariantNames, strum::EnumString)]
-/// enum MyAtlasTextures {
-///     Pacman,
-/// }
-///
-/// let mut app = App::default();
-/// # app.add_plugins(MinimalPlugins)
-/// #     .insert_resource(WgpuSettings {
-/// #         backends: None,
-/// #         ..Default::default()
-/// #     })
-/// #     .add_plugin(WindowPlugin::default())
-/// #     .add_plugin(AssetPlugin::default())
-/// #     .add_plugin(RenderPlugin::default())
-/// #     .add_plugin(CorePipelinePlugin::default())
-/// #     .add_plugin(SpritePlugin::default());
-/// app.add_plugin(AtlasTexturePlugin::<MyAtlasTextures>::default());
-///
-/// app.add_startup_system(move |mut cmds: Commands| {
-///     cmds.insert_resource(TypedAtlasDefinition::<MyAtlasTextures>::from(
-///         [(
-///             String::from("Pacman"),
-///             AtlasDefinition::from(GridAtlasDefinition {
-///                 texture: Path::new("Pac-Man.png").into(),
-///                 columns: 3,
-///                 rows: 3,
-///                 tile_size: (19, 19),
-///                 ..Default::default()
-///             }),
-///         )].into_iter().collect::<HashMap<String, AtlasDefinition>>(),
-///     ));
-/// });
-#[derive(Debug)]
-#[allow(unused)]
-pub struct TypedAtlasDefinition<T> {
-    pub(crate) definitions: DefinitionsType,
-    pub(crate) state: DefinitionProcessState,
-    _marker: PhantomData<T>,
+#[derive(Debug, Clone, PartialEq, SmartDefault, Deserialize, Reflect)]
+#[serde(default)]
+pub struct DefinitionOptions {
+    /// Named animations, indexing into the created [TextureAtlas] - continuously across the
+    /// pages of paged definitions.
+    pub animations: HashMap<String, AnimationDefinition>,
+    /// Stable names for indexes into the created [TextureAtlas], letting code refer to a name
+    /// while the physical layout of the image is free to change.
+    ///
+    /// Names of indexes outside the created atlas are left out, with a warning.
+    pub remap: HashMap<String, usize>,
+    /// Index shown by sprites not asking for a specific one, e.g. the idle frame.
+    pub default_index: usize,
+    /// Disabled definitions are not created, and not required to be present.
+    #[default = true]
+    pub enabled: bool,
+    /// Definitions of a higher priority are loaded first, so their atlases are ready sooner - e.g.
+    /// for the player character while decorations are still loading.
+    pub priority: i32,
 }
 
-#[derive(Debug)]
-pub(crate) enum DefinitionsType {
-    Direct(Box<GenericAtlasDefinitions>),
-    Indirect(Handle<GenericAtlasDefinitions>),
+/// Builder methods for the [DefinitionOptions] of a definition.
+pub trait WithDefinitionOptions: Sized {
+    /// Options of the definition.
+    fn options_mut(&mut self) -> &mut DefinitionOptions;
+
+    /// Adds a named animation.
+    fn with_animation(mut self, name: impl Into<String>, animation: AnimationDefinition) -> Self {
+        self.options_mut().animations.insert(name.into(), animation);
+        self
+    }
+
+    /// Adds a stable name for an index into the created [TextureAtlas].
+    fn with_remap(mut self, name: impl Into<String>, index: usize) -> Self {
+        self.options_mut().remap.insert(name.into(), index);
+        self
+    }
+
+    /// Sets the index shown by sprites not asking for a specific one.
+    fn with_default_index(mut self, default_index: usize) -> Self {
+        self.options_mut().default_index = default_index;
+        self
+    }
+
+    /// Sets whether the definition should be created.
+    fn with_enabled(mut self, enabled: bool) -> Self {
+        self.options_mut().enabled = enabled;
+        self
+    }
+
+    /// Sets the priority of loading the definition, relative to the others.
+    fn with_priority(mut self, priority: i32) -> Self {
+        self.options_mut().priority = priority;
+        self
+    }
 }
 
-#[derive(Debug, PartialEq, Eq, Clone, Copy, IsVariant)]
-pub(crate) enum DefinitionProcessState {
-    Loading,
-    Processing,
-    Finalizing,
-    Done,
-    Failed,
+/// Options for the image of the atlas, shared by the kinds of definitions creating one. In files,
+/// they're given along with the other fields of the definition.
+///
+/// The options are applied to a copy of the image owned by the atlas, leaving the loaded image as
+/// it is for other definitions using it.
+///
+/// # Example:
+/// ```rust
+/// # use std::path::Path;
+/// # use bevy::prelude::*;
+/// # use bevy_atlas_loader::*;
+/// let _ = GridAtlasDefinition::grid(Path::new("normals.png"), 4, 3, UVec2::new(16, 16))
+///     .with_linear(true)
+///     .with_generate_mipmaps(true);
+/// ```
+#[derive(Debug, Default, Clone, PartialEq, Deserialize, Reflect)]
+#[serde(default)]
+pub struct ImageOptions {
+    /// Sample the image as linear rather than sRGB, e.g. for normal maps or masks.
+    pub linear: bool,
+    /// Premultiply the colors of the image by their alpha, for materials blending with
+    /// premultiplied colors - avoiding dark halos around the sprites.
+    pub premultiply_alpha: bool,
+    /// Generate mipmaps for the image, reducing shimmering when the sprites are scaled down.
+    /// Images loaded with mip levels, e.g. from KTX2 files, keep theirs.
+    ///
+    /// Neighbouring regions bleed into each other at the lower levels, unless padded apart.
+    pub generate_mipmaps: bool,
+    /// How the image is sampled past its edges, clamping to the edge if not given.
+    ///
+    /// Repeating only makes sense for atlases of a single region spanning the whole image, e.g.
+    /// a tiled background, as the regions of other atlases would bleed into each other.
+    #[reflect(ignore)]
+    pub address_mode: Option<AddressMode>,
 }
 
-impl<T> From<GenericAtlasDefinitions> for TypedAtlasDefinition<T>
-where
-    T: Send + Sync,
-{
-    fn from(definitions_map: GenericAtlasDefinitions) -> Self {
-        Self {
-            definitions: DefinitionsType::Direct(Box::new(definitions_map)),
-            state: DefinitionProcessState::Loading,
-            _marker: PhantomData::default(),
-        }
+impl ImageOptions {
+    /// Whether the image is used as loaded.
+    pub(crate) fn is_default(&self) -> bool {
+        *self == Self::default()
     }
 }
 
-impl<T> From<HashMap<String, AtlasDefinition>> for TypedAtlasDefinition<T>
-where
-    T: Send + Sync,
-{
-    fn from(definitions_map: HashMap<String, AtlasDefinition>) -> Self {
-        Self {
-            definitions: DefinitionsType::Direct(Box::new(GenericAtlasDefinitions::from(
-                definitions_map,
-            ))),
-            state: DefinitionProcessState::Loading,
-            _marker: PhantomData::default(),
-        }
+/// Builder methods for the [ImageOptions] of a definition.
+pub trait WithImageOptions: Sized {
+    /// Options for the image of the atlas of the definition.
+    fn image_options_mut(&mut self) -> &mut ImageOptions;
+
+    /// Sets whether the image should be sampled as linear rather than sRGB.
+    fn with_linear(mut self, linear: bool) -> Self {
+        self.image_options_mut().linear = linear;
+        self
     }
-}
 
-impl<T> From<Handle<GenericAtlasDefinitions>> for TypedAtlasDefinition<T>
-where
-    T: Send + Sync,
-{
-    fn from(handle: Handle<GenericAtlasDefinitions>) -> Self {
-        Self {
-            definitions: DefinitionsType::Indirect(handle),
-            state: DefinitionProcessState::Loading,
-            _marker: PhantomData::default(),
-        }
+    /// Sets whether the colors of the image should be premultiplied by their alpha.
+    fn with_premultiply_alpha(mut self, premultiply_alpha: bool) -> Self {
+        self.image_options_mut().premultiply_alpha = premultiply_alpha;
+        self
     }
-}
 
-impl GetTextureAtlas for SingleTextureProcessState {
-    fn texture_atlas(&self) -> Option<&Handle<TextureAtlas>> {
-        match self {
-            Self::AtlasCreated(handle) => Some(handle),
-            _ => None,
-        }
+    /// Sets whether mipmaps should be generated for the image.
+    fn with_generate_mipmaps(mut self, generate_mipmaps: bool) -> Self {
+        self.image_options_mut().generate_mipmaps = generate_mipmaps;
+        self
     }
-}
 
-impl GetTextureAtlas for MultiTextureProcessState {
-    fn texture_atlas(&self) -> Option<&Handle<TextureAtlas>> {
-        match self {
-            Self::AtlasCreated(handle) => Some(handle),
-            _ => None,
-        }
+    /// Sets how the image is sampled past its edges.
+    fn with_address_mode(mut self, address_mode: AddressMode) -> Self {
+        self.image_options_mut().address_mode = Some(address_mode);
+        self
     }
 }
 
-impl GetTextureAtlas for AtlasDefinition {
-    fn texture_atlas(&self) -> Option<&Handle<TextureAtlas>> {
-        match self {
-            AtlasDefinition::Grid(d) => d.state.texture_atlas(),
-            AtlasDefinition::Manual(d) => d.state.texture_atlas(),
-            AtlasDefinition::Folder(d) => d.state.texture_atlas(),
-        }
-    }
+/// Options for packing the images of the definitions creating an atlas from several images. In
+/// files, they're given along with the other fields of the definition.
+///
+/// # Example:
+/// ```rust
+/// # use std::path::Path;
+/// # use bevy::prelude::*;
+/// # use bevy_atlas_loader::*;
+/// let _ = FolderAtlasDefinition::folder(Path::new("imagefolder"))
+///     .with_output_padding(2)
+///     .with_background(Color::FUCHSIA);
+/// ```
+#[derive(Debug, Default, Clone, PartialEq, Deserialize, Reflect)]
+#[serde(default)]
+pub struct PackOptions {
+    /// Transparent gap, in pixels, between the packed images - keeping them from bleeding into
+    /// each other when sampled.
+    pub output_padding: u32,
+    /// Color filling the space of the atlas not covered by any image, including the gaps of
+    /// `output_padding` - e.g. for visualizing wasted space. Transparent if not given.
+    pub background: Option<Color>,
 }
 
-#[cfg(test)]
-mod tests {
+/// Builder methods for the [PackOptions] of a definition.
+pub trait WithPackOptions: Sized {
+    /// Options for packing the images of the definition.
+    fn pack_options_mut(&mut self) -> &mut PackOptions;
 
-    mod config_file {
-        mod allows_format {
-            use crate::*;
+    /// Sets the transparent gap, in pixels, between the packed images.
+    fn with_output_padding(mut self, output_padding: u32) -> Self {
+        self.pack_options_mut().output_padding = output_padding;
+        self
+    }
 
-            type Result = anyhow::Result<()>;
+    /// Sets the color filling the space not covered by any image.
+    fn with_background(mut self, background: Color) -> Self {
+        self.pack_options_mut().background = Some(background);
+        self
+    }
+}
 
-            #[test]
-            fn patchwork() -> Result {
-                let cfg_file = indoc::indoc! {r#"
-                    ({
-                        "patchwork": (
-                            texture: "Pac-Man.png",
-                            width: 19,
-                            height: 19,
-                            positions: [
-                                (65, 86),
-                                (86, 86),
-                                (107, 86),
-                            ]
-                        ),
-                    })"#};
+macro_rules! impl_with_definition_options {
+    ($($definition:ty),* $(,)?) => {
+        $(
+            impl WithDefinitionOptions for $definition {
+                fn options_mut(&mut self) -> &mut DefinitionOptions {
+                    &mut self.options
+                }
+            }
+        )*
+    };
+}
 
-                let config: GenericAtlasDefinitions = ron::from_str(cfg_file)?;
-                dbg!(config);
-                Ok(())
+impl_with_definition_options!(
+    GridAtlasDefinition,
+    GridByBoundsAtlasDefinition,
+    PatchAtlasDefinition,
+    FramesAtlasDefinition,
+    FolderAtlasDefinition,
+    GlobAtlasDefinition,
+    FolderManifestAtlasDefinition,
+    TexturePackerAtlasDefinition,
+    ArrayAtlasDefinition,
+    PagedAtlasDefinition,
+    AutoDetectAtlasDefinition,
+    MosaicAtlasDefinition,
+    FractionalGridAtlasDefinition,
+);
+
+macro_rules! impl_with_pack_options {
+    ($($definition:ty),* $(,)?) => {
+        $(
+            impl WithPackOptions for $definition {
+                fn pack_options_mut(&mut self) -> &mut PackOptions {
+                    &mut self.packing
+                }
             }
+        )*
+    };
+}
 
-            #[test]
-            fn grid() -> Result {
-                let cfg_file = indoc::indoc! {r#"
-                    ({
-                        "grid": (
-                            texture: "Pac-Man.png",
-                            columns: 8,
-                            rows: 4,
-                            tile_size: (20, 20),
-                            padding: None,
-                        ),
-                    })"#};
+impl_with_pack_options!(
+    FolderAtlasDefinition,
+    GlobAtlasDefinition,
+    FolderManifestAtlasDefinition,
+);
 
-                let config: GenericAtlasDefinitions = ron::from_str(cfg_file)?;
-                dbg!(config);
-                Ok(())
+macro_rules! impl_with_image_options {
+    ($($definition:ty),* $(,)?) => {
+        $(
+            impl WithImageOptions for $definition {
+                fn image_options_mut(&mut self) -> &mut ImageOptions {
+                    &mut self.image
+                }
             }
+        )*
+    };
+}
 
-            #[test]
+impl_with_image_options!(
+    GridAtlasDefinition,
+    GridByBoundsAtlasDefinition,
+    PatchAtlasDefinition,
+    FramesAtlasDefinition,
+    FolderAtlasDefinition,
+    GlobAtlasDefinition,
+    FolderManifestAtlasDefinition,
+    TexturePackerAtlasDefinition,
+    ArrayAtlasDefinition,
+    AutoDetectAtlasDefinition,
+    MosaicAtlasDefinition,
+    FractionalGridAtlasDefinition,
+);
+
+/// Defines a [TextureAtlas] composed of individually sized, optionally named, frames inside an
+/// image.
+///
+/// # Example:
+/// ```
+/// # use std::path::Path;
+/// # use bevy_atlas_loader::*;
+/// let _ = FramesAtlasDefinition::frames(
+///     Path::new("image.png"),
+///     vec![
+///         AtlasFrame::new(0, 0, 16, 16).with_name("player"),
+///         AtlasFrame::new(16, 0, 32, 8),
+///     ],
+/// );
+/// ```
+#[derive(Debug, Clone, PartialEq, SmartDefault, Deserialize, Reflect)]
+pub struct FramesAtlasDefinition {
+    #[reflect(ignore)]
+    pub texture: PathBuf,
+    /// Regions of the image, in the order of their index in the created [TextureAtlas].
+    #[reflect(ignore)]
+    pub frames: Vec<AtlasFrame>,
+    #[serde(flatten)]
+    pub image: ImageOptions,
+    #[serde(flatten)]
+    pub options: DefinitionOptions,
+    #[doc(hidden)]
+    #[serde(skip)]
+    #[reflect(ignore)]
+    pub state: SingleTextureProcessState,
+}
+
+/// Region of an image at `x`, `y` of `w` x `h` pixels, named for lookup through
+/// [AtlasTextures::index_of](crate::AtlasTextures::index_of).
+#[derive(Debug, Default, Clone, PartialEq, Eq, Deserialize)]
+pub struct AtlasFrame {
+    pub x: u32,
+    pub y: u32,
+    pub w: u32,
+    pub h: u32,
+    #[serde(default)]
+    pub name: Option<String>,
+}
+
+/// Defines a [TextureAtlas] as a series of images, read from a folder.
+/// The sequence of the images is unknown, and may change each invocation.
+///
+/// Packing the images may be slow for large folders. Specifying a `cache` file (relative to the
+/// asset folder) stores the packed layout, which is then reused as long as it matches the images
+/// in the folder - fixing the sequence of the images as well. Caching is not available on
+/// platforms without direct file system access, such as web.
+///
+/// # Example:
+/// ```rust
+/// # use std::path::Path;
+/// # use bevy_atlas_loader::*;
+/// let _ = FolderAtlasDefinition::folder(Path::new("imagefolder"));
+/// ```
+#[derive(Debug, Clone, PartialEq, SmartDefault, Deserialize, Reflect)]
+pub struct FolderAtlasDefinition {
+    #[reflect(ignore)]
+    pub path: PathBuf,
+    /// File caching the packed layout, relative to the asset folder.
+    #[serde(default)]
+    #[reflect(ignore)]
+    pub cache: Option<PathBuf>,
+    #[serde(flatten)]
+    pub packing: PackOptions,
+    #[serde(flatten)]
+    pub image: ImageOptions,
+    #[serde(flatten)]
+    pub options: DefinitionOptions,
+    #[doc(hidden)]
+    #[serde(skip)]
+    #[reflect(ignore)]
+    pub state: MultiTextureProcessState,
+}
+
+/// Defines a [TextureAtlas] as the images of a folder matching a glob pattern, e.g. only the
+/// `walk_*.png` of a folder holding several animations.
+///
+/// Only the file name of the pattern may hold wildcards: `*` matching any run of characters, and
+/// `?` a single character. The matching images are loaded in the order of their names.
+///
+/// Like [FolderAtlasDefinition], this is not supported via web, as the pattern is expanded
+/// against the file system.
+///
+/// # Example:
+/// ```rust
+/// # use bevy_atlas_loader::*;
+/// let _ = GlobAtlasDefinition::glob("characters/walk_*.png");
+/// ```
+#[derive(Debug, Clone, PartialEq, SmartDefault, Deserialize, Reflect)]
+pub struct GlobAtlasDefinition {
+    /// Pattern of the images, relative to the asset folder.
+    pub pattern: String,
+    #[serde(flatten)]
+    pub packing: PackOptions,
+    #[serde(flatten)]
+    pub image: ImageOptions,
+    #[serde(flatten)]
+    pub options: DefinitionOptions,
+    #[doc(hidden)]
+    #[serde(skip)]
+    #[reflect(ignore)]
+    pub state: MultiTextureProcessState,
+}
+
+/// Defines a [TextureAtlas] as a series of images, listed by a [FolderManifest].
+///
+/// Unlike [FolderAtlasDefinition], the images are loaded individually, making this usable on
+/// platforms where folders can't be loaded, such as web.
+///
+/// # Example:
+/// ```rust
+/// # use std::path::Path;
+/// # use bevy_atlas_loader::*;
+/// let _ = FolderManifestAtlasDefinition::manifest(Path::new("imagefolder.manifest.ron"));
+/// ```
+#[derive(Debug, Clone, PartialEq, SmartDefault, Deserialize, Reflect)]
+pub struct FolderManifestAtlasDefinition {
+    #[reflect(ignore)]
+    pub manifest: PathBuf,
+    #[serde(flatten)]
+    pub packing: PackOptions,
+    #[serde(flatten)]
+    pub image: ImageOptions,
+    #[serde(flatten)]
+    pub options: DefinitionOptions,
+    #[doc(hidden)]
+    #[serde(skip)]
+    #[reflect(ignore)]
+    pub state: ManifestProcessState,
+}
+
+/// Defines a [TextureAtlas] from a sprite sheet exported by
+/// [TexturePacker](https://www.codeandweb.com/texturepacker) in one of the JSON formats.
+///
+/// The sheet must be named `*.tpsheet.json`, and the regions are indexed in the order they appear
+/// in the sheet. The name of each frame is available as a named index.
+///
+/// # Example:
+/// ```rust
+/// # use std::path::Path;
+/// # use bevy_atlas_loader::*;
+/// let _ = TexturePackerAtlasDefinition::texture_packer(Path::new("sprites.tpsheet.json"));
+/// ```
+#[derive(Debug, Clone, PartialEq, SmartDefault, Deserialize, Reflect)]
+pub struct TexturePackerAtlasDefinition {
+    #[reflect(ignore)]
+    pub sheet: PathBuf,
+    /// Factor to resample the image by before creating the atlas, see
+    /// [GridAtlasDefinition::scale].
+    #[serde(default)]
+    pub scale: Option<f32>,
+    #[serde(flatten)]
+    pub image: ImageOptions,
+    #[serde(flatten)]
+    pub options: DefinitionOptions,
+    #[doc(hidden)]
+    #[serde(skip)]
+    #[reflect(ignore)]
+    pub state: SheetProcessState,
+}
+
+/// Defines a texture array of equally sized images, one per layer, e.g. for custom material
+/// pipelines sampling the layers in a shader.
+///
+/// The array is available through
+/// [AtlasTextures::texture_array](crate::AtlasTextures::texture_array). The layers are stacked
+/// into a [TextureAtlas] as well, one region per layer.
+///
+/// # Example:
+/// ```rust
+/// # use std::path::Path;
+/// # use bevy_atlas_loader::*;
+/// let _ = ArrayAtlasDefinition::array([Path::new("frame0.png"), Path::new("frame1.png")]);
+/// ```
+#[derive(Debug, Clone, PartialEq, SmartDefault, Deserialize, Reflect)]
+pub struct ArrayAtlasDefinition {
+    /// Images of the layers, all sharing the same size and format.
+    #[reflect(ignore)]
+    pub layers: Vec<PathBuf>,
+    #[serde(flatten)]
+    pub image: ImageOptions,
+    #[serde(flatten)]
+    pub options: DefinitionOptions,
+    #[doc(hidden)]
+    #[serde(skip)]
+    #[reflect(ignore)]
+    pub state: ArrayProcessState,
+}
+
+/// Defines a tileset spread across several grids, each becoming a [TextureAtlas] of its own -
+/// e.g. for tilesets exceeding the texture size limits of the GPU.
+///
+/// The tiles of the pages are indexed continuously, in the order of the pages. Use
+/// [AtlasTextures::page_and_index](crate::AtlasTextures::page_and_index) for finding the page
+/// of an index, and [AtlasTextures::pages](crate::AtlasTextures::pages) for its handle.
+/// Animations and remaps of the pages themselves are ignored, in favor of those of the paged
+/// definition.
+///
+/// # Example:
+/// ```rust
+/// # use std::path::Path;
+/// # use bevy::prelude::*;
+/// # use bevy_atlas_loader::*;
+/// let _ = PagedAtlasDefinition::pages([
+///     GridAtlasDefinition::grid(Path::new("tiles0.png"), 64, 64, UVec2::new(32, 32)),
+///     GridAtlasDefinition::grid(Path::new("tiles1.png"), 64, 64, UVec2::new(32, 32)),
+/// ]);
+/// ```
+#[derive(Debug, Clone, PartialEq, SmartDefault, Deserialize, Reflect)]
+pub struct PagedAtlasDefinition {
+    /// Grids of the pages, in order.
+    #[reflect(ignore)]
+    pub pages: Vec<GridAtlasDefinition>,
+    #[serde(flatten)]
+    pub options: DefinitionOptions,
+}
+
+/// Defines a [TextureAtlas] of the irregularly placed sprites of an image, each detected as a
+/// connected area of opaque pixels - saving the hand-listing of positions for hand-drawn sheets.
+///
+/// The regions are indexed top-left to bottom-right, by their top edge first.
+///
+/// # Example:
+/// ```rust
+/// # use std::path::Path;
+/// # use bevy_atlas_loader::*;
+/// let _ = AutoDetectAtlasDefinition::auto_detect(Path::new("doodles.png"), 0).with_max_regions(64);
+/// ```
+#[derive(Debug, Clone, PartialEq, SmartDefault, Deserialize, Reflect)]
+pub struct AutoDetectAtlasDefinition {
+    #[reflect(ignore)]
+    pub texture: PathBuf,
+    /// Pixels with an alpha above the threshold are part of a sprite.
+    pub alpha_threshold: u8,
+    /// Upper limit on the number of regions, failing the definition for images with more - e.g.
+    /// noisy ones where every pixel would become a region.
+    #[default = 1024]
+    #[serde(default = "max_regions_by_default")]
+    pub max_regions: usize,
+    #[serde(flatten)]
+    pub image: ImageOptions,
+    #[serde(flatten)]
+    pub options: DefinitionOptions,
+    #[doc(hidden)]
+    #[serde(skip)]
+    #[reflect(ignore)]
+    pub state: SingleTextureProcessState,
+}
+
+/// Defines a [TextureAtlas] of separate images, each placed into a chosen cell of a grid - e.g.
+/// for composing the icons of a HUD from individual files, without packing them.
+///
+/// The cells are as large as the largest image, with each image placed at the top-left of its
+/// cell. The regions are indexed in the order of the cells, and no two may share a cell.
+///
+/// # Example:
+/// ```rust
+/// # use std::path::Path;
+/// # use bevy::prelude::*;
+/// # use bevy_atlas_loader::*;
+/// let _ = MosaicAtlasDefinition::mosaic([
+///     (UVec2::new(0, 0), Path::new("icons/health.png")),
+///     (UVec2::new(1, 0), Path::new("icons/mana.png")),
+/// ]);
+/// ```
+#[derive(Debug, Clone, PartialEq, SmartDefault, Deserialize, Reflect)]
+pub struct MosaicAtlasDefinition {
+    /// Cells of the grid, by column and row, along with the image placed into each.
+    #[reflect(ignore)]
+    pub cells: Vec<(UVec2, PathBuf)>,
+    #[serde(flatten)]
+    pub image: ImageOptions,
+    #[serde(flatten)]
+    pub options: DefinitionOptions,
+    #[doc(hidden)]
+    #[serde(skip)]
+    #[reflect(ignore)]
+    pub state: MultiTextureProcessState,
+}
+
+/// Defines a [TextureAtlas] composed from a grid of an image, with tiles of a fractional size -
+/// e.g. for sheets rendered from vector art at a scale not matching whole pixels.
+///
+/// Every tile is placed relative to the first rather than the previous one, so rounding doesn't
+/// accumulate along long rows, and the regions may start and end between pixels. For tiles of a
+/// whole number of pixels, use [GridAtlasDefinition].
+///
+/// When defined in a file, the sizes are written with a decimal point - e.g. `(12.5, 12.5)`.
+///
+/// # Example:
+/// ```rust
+/// # use std::path::Path;
+/// # use bevy::prelude::*;
+/// # use bevy_atlas_loader::*;
+/// let _ = FractionalGridAtlasDefinition::fractional_grid(
+///     Path::new("image.png"),
+///     10,
+///     4,
+///     Vec2::new(25.6, 25.6),
+/// );
+/// ```
+#[derive(Debug, Clone, PartialEq, SmartDefault, Deserialize, Reflect)]
+pub struct FractionalGridAtlasDefinition {
+    #[reflect(ignore)]
+    pub texture: PathBuf,
+    pub columns: usize,
+    pub rows: usize,
+    /// Size of the tiles, in pixels.
+    pub tile_size: Vec2,
+    /// Top-left corner of the first tile.
+    #[serde(default)]
+    pub offset: Vec2,
+    /// Gap between the tiles.
+    #[serde(default)]
+    pub padding: Option<Vec2>,
+    #[serde(flatten)]
+    pub image: ImageOptions,
+    #[serde(flatten)]
+    pub options: DefinitionOptions,
+    #[doc(hidden)]
+    #[serde(skip)]
+    #[reflect(ignore)]
+    pub state: SingleTextureProcessState,
+}
+
+fn max_regions_by_default() -> usize {
+    1024
+}
+
+/// Defines a named animation as a sequence of frames from a [TextureAtlas], played at a fixed
+/// rate.
+///
+/// # Example:
+/// ```ron
+/// ({
+///     "Pacman": (
+///         texture: "Pac-Man.png",
+///         width: 19,
+///         height: 19,
+///         positions: [(65, 86), (86, 86), (107, 86)],
+///         animations: {
+///             "chomp": (frames: [0, 1, 2, 1], fps: 5.0),
+///         },
+///     )
+/// })
+/// ```
+#[derive(Debug, Default, Clone, PartialEq, Deserialize, Constructor, Reflect)]
+pub struct AnimationDefinition {
+    /// Indexes into the [TextureAtlas], in the order they should be shown.
+    pub frames: Vec<usize>,
+    /// Frames per second.
+    pub fps: f32,
+}
+
+impl AnimationDefinition {
+    /// Duration each frame should be shown, in seconds.
+    pub fn frame_duration(&self) -> f32 {
+        1.0 / self.fps
+    }
+}
+
+#[doc(hidden)]
+#[derive(Debug, Clone, PartialEq, EnumDefault)]
+pub enum SingleTextureProcessState {
+    #[default]
+    None,
+    LoadingTexture(HandleUntyped),
+    AtlasCreated(Handle<TextureAtlas>, Vec<Handle<Image>>),
+}
+
+/// State of a grid, loading either a single sheet or several sheets sharing its layout.
+#[doc(hidden)]
+#[derive(Debug, Clone, PartialEq, EnumDefault)]
+pub enum GridProcessState {
+    #[default]
+    None,
+    LoadingTexture(HandleUntyped),
+    LoadingTextures(Vec<HandleUntyped>),
+    AtlasCreated(Handle<TextureAtlas>, Vec<Handle<Image>>),
+}
+
+#[doc(hidden)]
+#[derive(Debug, Clone, PartialEq, EnumDefault)]
+pub enum MultiTextureProcessState {
+    #[default]
+    None,
+    LoadingTextures(Vec<HandleUntyped>),
+    AtlasCreated(Handle<TextureAtlas>, Vec<Handle<Image>>),
+}
+
+#[doc(hidden)]
+#[derive(Debug, Clone, PartialEq, EnumDefault)]
+pub enum ManifestProcessState {
+    #[default]
+    None,
+    LoadingManifest(Handle<FolderManifest>),
+    LoadingTextures(Vec<HandleUntyped>),
+    AtlasCreated(Handle<TextureAtlas>, Vec<Handle<Image>>),
+}
+
+#[doc(hidden)]
+#[derive(Debug, Clone, PartialEq, EnumDefault)]
+pub enum SheetProcessState {
+    #[default]
+    None,
+    LoadingSheet(Handle<TexturePackerSheet>),
+    LoadingTexture(Handle<TexturePackerSheet>, HandleUntyped),
+    AtlasCreated(
+        Handle<TextureAtlas>,
+        HashMap<String, usize>,
+        Vec<Handle<Image>>,
+    ),
+}
+
+#[doc(hidden)]
+#[derive(Debug, Clone, PartialEq, EnumDefault)]
+pub enum ArrayProcessState {
+    #[default]
+    None,
+    LoadingTextures(Vec<HandleUntyped>),
+    AtlasCreated(Handle<TextureAtlas>, Handle<Image>, Vec<Handle<Image>>),
+}
+
+/// Resource specifying how to create a specific [AtlasTextures<T>](crate::AtlasTextures<T>).
+///
+/// For an example of how to load the definition as an asset, see [GenericAtlasDefinitions].
+/// # Example:
+/// ```
+/// # use bevy::{
+/// #     prelude::*,
+/// #     utils::HashMap,
+/// #     asset::AssetPlugin,
+/// #     core_pipeline::CorePipelinePlugin,
+/// #     render::{settings::WgpuSettings, RenderPlugin},
+/// #     sprite::SpritePlugin,
+/// #     window::WindowPlugin,
+/// #     MinimalPlugins,
+/// # };
+/// # use bevy_atlas_loader::*;
+/// # use std::{
+/// #     path::Path,
+/// #     sync::{atomic::AtomicBool, Arc},
+/// # };
+/// #
+/// #[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
+/// #[derive(strum::EnumVariantNames, strum::EnumString)]
+/// enum MyAtlasTextures {
+///     Pacman,
+/// }
+///
+/// let mut app = App::default();
+/// # app.add_plugins(MinimalPlugins)
+/// #     .insert_resource(WgpuSettings {
+/// #         backends: None,
+/// #         ..Default::default()
+/// #     })
+/// #     .add_plugin(WindowPlugin::default())
+/// #     .add_plugin(AssetPlugin::default())
+/// #     .add_plugin(RenderPlugin::default())
+/// #     .add_plugin(CorePipelinePlugin::default())
+/// #     .add_plugin(SpritePlugin::default());
+/// app.add_plugin(AtlasTexturePlugin::<MyAtlasTextures>::default());
+///
+/// app.add_startup_system(move |mut cmds: Commands| {
+///     cmds.insert_resource(TypedAtlasDefinition::<MyAtlasTextures>::from(
+///         [(
+///             String::from("Pacman"),
+///             AtlasDefinition::from(GridAtlasDefinition::grid(
+///                 Path::new("Pac-Man.png"),
+///                 3,
+///                 3,
+///                 UVec2::new(19, 19),
+///             )),
+///         )].into_iter().collect::<HashMap<String, AtlasDefinition>>(),
+///     ));
+/// });
+#[derive(Debug)]
+#[allow(unused)]
+pub struct TypedAtlasDefinition<T> {
+    pub(crate) definitions: DefinitionsType,
+    pub(crate) state: DefinitionProcessState,
+    pub(crate) base_path: PathBuf,
+    /// Whether paths are relative to the file defining them, rather than the asset folder.
+    pub(crate) relative_paths: bool,
+    /// Directory of the file defining each definition, when paths are relative to it.
+    pub(crate) definition_dirs: HashMap<String, PathBuf>,
+    /// Key of the definition currently loading, along with the time it started.
+    pub(crate) pending_since: Option<(String, f64)>,
+    /// Keys of the definitions that failed, along with the reason.
+    pub(crate) failures: Vec<(String, ResourceError)>,
+    /// Keys of the variants to create, or all when `None`.
+    pub(crate) requested: Option<HashSet<String>>,
+    /// Profile whose definitions replace the usual ones, if any.
+    pub(crate) profile: Option<String>,
+    /// Whether more variants were requested, or the profile changed, since the last batch
+    /// started.
+    pub(crate) rebuild_pending: bool,
+    /// Whether the definitions were replaced, with the
+    /// [Reloading](crate::ResourceStatus::Reloading) event yet to be sent.
+    pub(crate) reload_pending: bool,
+    /// Definitions the current atlases were created from, keyed by name - reloaded definitions
+    /// left unchanged reuse their atlas instead of being created again.
+    pub(crate) created: HashMap<String, AtlasDefinition>,
+    _marker: PhantomData<T>,
+}
+
+#[derive(Debug)]
+pub(crate) enum DefinitionsType {
+    Direct(Box<GenericAtlasDefinitions>),
+    Indirect(Handle<GenericAtlasDefinitions>),
+    /// Definitions loaded from a directory, merged once all are loaded.
+    Directory {
+        handles: Vec<Handle<GenericAtlasDefinitions>>,
+        merged: Box<GenericAtlasDefinitions>,
+    },
+}
+
+impl DefinitionsType {
+    /// Whether the definitions are loaded from the asset `handle`.
+    pub(crate) fn is_loaded_from(&self, handle: &Handle<GenericAtlasDefinitions>) -> bool {
+        match self {
+            DefinitionsType::Direct(_) => false,
+            DefinitionsType::Indirect(h) => h == handle,
+            DefinitionsType::Directory { handles, .. } => handles.contains(handle),
+        }
+    }
+}
+
+/// State of processing, along with every definition - see [TypedAtlasDefinition::progress].
+pub(crate) type DefinitionProgress = (DefinitionProcessState, Vec<(String, AtlasDefinition)>);
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy, IsVariant)]
+pub(crate) enum DefinitionProcessState {
+    Loading,
+    Processing,
+    Finalizing,
+    Done,
+    Failed,
+    /// The definitions asset was removed, along with the created [AtlasTextures](crate::AtlasTextures).
+    Unloaded,
+}
+
+impl<T> TypedAtlasDefinition<T> {
+    fn new(definitions: DefinitionsType) -> Self {
+        Self {
+            definitions,
+            state: DefinitionProcessState::Loading,
+            base_path: PathBuf::new(),
+            relative_paths: false,
+            definition_dirs: HashMap::default(),
+            pending_since: None,
+            failures: Vec::new(),
+            requested: None,
+            profile: None,
+            rebuild_pending: false,
+            reload_pending: false,
+            created: HashMap::default(),
+            _marker: PhantomData::default(),
+        }
+    }
+
+    /// Loads and merges all `.atlasmap` files in the directory at `path`, relative to the asset
+    /// folder.
+    ///
+    /// Loading `.atlasmap` files requires a loader for [GenericAtlasDefinitions], as described
+    /// there. If several files define the same key, the first file (by path) is used.
+    ///
+    /// Not supported on platforms where folders can't be loaded, such as web.
+    pub fn from_dir(
+        asset_server: &AssetServer,
+        path: impl AsRef<Path>,
+    ) -> Result<Self, AssetServerError> {
+        let handles = asset_server
+            .load_folder(path.as_ref())?
+            .into_iter()
+            .filter(|handle| {
+                asset_server.get_handle_path(handle).map_or(false, |path| {
+                    path.path()
+                        .extension()
+                        .map_or(false, |ext| ext == "atlasmap")
+                })
+            })
+            .map(|handle| handle.typed())
+            .collect();
+        Ok(Self::new(DefinitionsType::Directory {
+            handles,
+            merged: Box::default(),
+        }))
+    }
+
+    /// Prefixes the `texture`/`path` of every definition with `base_path`, e.g. when the
+    /// definitions are shared between projects using different folder layouts.
+    ///
+    /// # Example:
+    /// ```
+    /// # use bevy::{prelude::*, utils::HashMap};
+    /// # use bevy_atlas_loader::*;
+    /// #[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
+    /// #[derive(strum::EnumVariantNames, strum::EnumString)]
+    /// enum MyAtlasTextures {
+    ///     Pacman,
+    /// }
+    ///
+    /// // "Pac-Man.png" will be loaded as "sprites/Pac-Man.png"
+    /// let _ = TypedAtlasDefinition::<MyAtlasTextures>::from(
+    ///     HashMap::<String, AtlasDefinition>::default()
+    /// ).with_base_path("sprites");
+    /// ```
+    pub fn with_base_path(mut self, base_path: impl Into<PathBuf>) -> Self {
+        self.base_path = base_path.into();
+        self
+    }
+
+    /// Resolves the `texture`/`path` of every definition relative to the directory of the
+    /// `.atlasmap` file defining it, rather than the asset folder - just like most asset formats
+    /// reference sibling files. Any `base_path` is then relative to that directory as well.
+    ///
+    /// Has no effect on definitions given directly.
+    ///
+    /// # Example:
+    /// ```no_run
+    /// # use bevy::prelude::*;
+    /// # use bevy_atlas_loader::*;
+    /// # #[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
+    /// # #[derive(strum::EnumVariantNames, strum::EnumString)]
+    /// # enum MyAtlasTextures {
+    /// #     Pacman,
+    /// # }
+    /// fn setup(mut cmds: Commands, assets: Res<AssetServer>) {
+    ///     // "Pac-Man.png" inside the file will be loaded as "sprites/Pac-Man.png"
+    ///     cmds.insert_resource(
+    ///         TypedAtlasDefinition::<MyAtlasTextures>::from(
+    ///             assets.load("sprites/sprite_sheets.atlasmap"),
+    ///         )
+    ///         .with_relative_paths(true),
+    ///     );
+    /// }
+    /// ```
+    pub fn with_relative_paths(mut self, relative_paths: bool) -> Self {
+        self.relative_paths = relative_paths;
+        self
+    }
+
+    /// Only requires and creates the definitions of `variants`, rather than every variant of
+    /// `T`, e.g. for loading the atlases of each scene as needed. More variants can be requested
+    /// later on with [request_variants](Self::request_variants).
+    ///
+    /// # Example:
+    /// ```
+    /// # use bevy::{prelude::*, utils::HashMap};
+    /// # use bevy_atlas_loader::*;
+    /// #[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
+    /// #[derive(strum::EnumVariantNames, strum::EnumString)]
+    /// enum MyAtlasTextures {
+    ///     Pacman,
+    ///     Ghost,
+    /// }
+    ///
+    /// // only "Pacman" needs to be defined, and only it is created
+    /// let _ = TypedAtlasDefinition::<MyAtlasTextures>::from(
+    ///     HashMap::<String, AtlasDefinition>::default()
+    /// ).with_variants(&[MyAtlasTextures::Pacman]);
+    /// ```
+    pub fn with_variants(mut self, variants: &[T]) -> Self
+    where
+        T: AtlasKey,
+    {
+        self.request_variants(variants);
+        self
+    }
+
+    /// Requests the definitions of `variants` to be created as well, when only some variants
+    /// were requested through [with_variants](Self::with_variants).
+    ///
+    /// Once the definitions are done, the [AtlasTextures<T>](crate::AtlasTextures<T>) are
+    /// recreated with the newly requested atlases, without recreating the existing ones, and
+    /// another [AtlasTexturesEvent<T>](crate::AtlasTexturesEvent<T>) is sent.
+    pub fn request_variants(&mut self, variants: &[T])
+    where
+        T: AtlasKey,
+    {
+        let requested = self.requested.get_or_insert_with(HashSet::default);
+        for variant in variants {
+            if requested.insert(variant.to_key().into_owned()) {
+                self.rebuild_pending = true;
+            }
+        }
+    }
+
+    /// Creates the atlases from the definitions of `profile`, e.g. a high-contrast set of
+    /// sprites for accessibility, or a theme.
+    ///
+    /// Definitions of a profile are keyed by the variant, `@` and the name of the profile - e.g.
+    /// `"Pacman@high-contrast"` - replacing the usual definition of the variant while the
+    /// profile is selected. Variants without a definition for the profile use the usual one.
+    ///
+    /// # Example:
+    /// ```
+    /// # use std::path::Path;
+    /// # use bevy::{prelude::*, utils::HashMap};
+    /// # use bevy_atlas_loader::*;
+    /// #[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
+    /// #[derive(strum::EnumVariantNames, strum::EnumString)]
+    /// enum MyAtlasTextures {
+    ///     Pacman,
+    /// }
+    ///
+    /// let _ = TypedAtlasDefinition::<MyAtlasTextures>::from(
+    ///     [
+    ///         (String::from("Pacman"), GridAtlasDefinition::grid(
+    ///             Path::new("Pac-Man.png"), 3, 3, UVec2::new(19, 19),
+    ///         ).into()),
+    ///         (String::from("Pacman@high-contrast"), GridAtlasDefinition::grid(
+    ///             Path::new("Pac-Man-contrast.png"), 3, 3, UVec2::new(19, 19),
+    ///         ).into()),
+    ///     ].into_iter().collect::<HashMap<String, AtlasDefinition>>()
+    /// ).with_profile("high-contrast");
+    /// ```
+    pub fn with_profile(mut self, profile: impl Into<String>) -> Self {
+        self.set_profile(Some(&profile.into()));
+        self
+    }
+
+    /// Switches to the definitions of `profile`, or back to the usual ones with `None` - see
+    /// [with_profile](Self::with_profile).
+    ///
+    /// Once the definitions are done, the [AtlasTextures<T>](crate::AtlasTextures<T>) are
+    /// recreated from the definitions of the profile, and another
+    /// [AtlasTexturesEvent<T>](crate::AtlasTexturesEvent<T>) is sent.
+    pub fn set_profile(&mut self, profile: Option<&str>) {
+        if self.profile.as_deref() != profile {
+            self.profile = profile.map(String::from);
+            self.rebuild_pending = true;
+        }
+    }
+
+    /// Returns the selected profile, if any.
+    pub fn profile(&self) -> Option<&str> {
+        self.profile.as_deref()
+    }
+
+    /// Replaces the definitions with those parsed from `ron`, e.g. pasted into a dev console, and
+    /// recreates the atlases from them.
+    ///
+    /// Definitions loaded from an asset are replaced as well, and no longer follow changes to the
+    /// asset. On a parse error the definitions, and the atlases created from them, are left
+    /// intact.
+    ///
+    /// As for a reloaded asset, an [AtlasTexturesEvent<T>](crate::AtlasTexturesEvent<T>) with
+    /// [ResourceStatus::Reloading](crate::ResourceStatus::Reloading) is sent first.
+    pub fn replace_from_ron(&mut self, ron: &str) -> Result<(), ron::Error> {
+        let definitions: GenericAtlasDefinitions = ron::from_str(ron)?;
+        self.definitions = DefinitionsType::Direct(Box::new(definitions));
+        self.definition_dirs.clear();
+        self.state = DefinitionProcessState::Loading;
+        self.pending_since = None;
+        self.failures.clear();
+        self.reload_pending = true;
+        Ok(())
+    }
+
+    /// Whether the definition `key` should be created, given the profile and requested variants.
+    fn is_selected(&self, definitions: &GenericAtlasDefinitions, key: &str) -> bool {
+        definitions.is_selected(key, self.profile.as_deref(), self.requested.as_ref())
+    }
+
+    /// Returns the keys of the definitions still loading, e.g. for showing a loading screen.
+    ///
+    /// Returns nothing until the definitions themselves have loaded.
+    pub fn currently_loading(
+        &self,
+        atlas_definitions: &Assets<GenericAtlasDefinitions>,
+    ) -> Vec<String> {
+        if !matches!(
+            self.state,
+            DefinitionProcessState::Loading | DefinitionProcessState::Processing
+        ) {
+            return Vec::new();
+        }
+        let mut keys = self
+            .generic_definitions(atlas_definitions)
+            .into_iter()
+            .flat_map(|definitions| {
+                definitions.iter().filter(|(key, definition)| {
+                    definition.is_pending()
+                        && self.is_selected(definitions, key)
+                        && !self.has_failed(key)
+                })
+            })
+            .map(|(key, _definition)| key.clone())
+            .collect::<Vec<_>>();
+        keys.sort();
+        keys
+    }
+
+    /// Iterates the definitions along with a summary of their progress, e.g. for inspecting
+    /// them in an editor.
+    ///
+    /// Yields nothing until the definitions themselves have loaded.
+    pub fn definitions_status<'a>(
+        &'a self,
+        atlas_definitions: &'a Assets<GenericAtlasDefinitions>,
+    ) -> impl Iterator<Item = (&'a str, DefinitionStatus)> + 'a {
+        let failed = self.state.is_failed();
+        self.generic_definitions(atlas_definitions)
+            .into_iter()
+            .flat_map(|definitions| {
+                definitions
+                    .iter()
+                    .map(|(key, definition)| (key, definition, self.is_selected(definitions, key)))
+            })
+            .map(move |(key, definition, selected)| {
+                let status = if !selected {
+                    DefinitionStatus::None
+                } else if (failed || self.has_failed(key)) && definition.is_pending() {
+                    DefinitionStatus::Failed
+                } else {
+                    definition.status()
+                };
+                (key.as_str(), status)
+            })
+    }
+
+    /// Returns a concise summary of the progress, e.g. `"3/5 atlases created, 1 loading, 1 failed"`
+    /// for logging while debugging loading issues.
+    ///
+    /// Disabled definitions, and those not requested or of another profile, are left out.
+    pub fn status_summary(&self, atlas_definitions: &Assets<GenericAtlasDefinitions>) -> String {
+        let definitions = match self.generic_definitions(atlas_definitions) {
+            Some(definitions) => definitions,
+            None => return String::from("definitions loading"),
+        };
+        let (mut total, mut created, mut loading, mut failed) = (0, 0, 0, 0);
+        for (_key, status) in self
+            .definitions_status(atlas_definitions)
+            .filter(|(key, status)| !status.is_disabled() && self.is_selected(definitions, key))
+        {
+            total += 1;
+            match status {
+                DefinitionStatus::Created => created += 1,
+                DefinitionStatus::Loading => loading += 1,
+                DefinitionStatus::Failed => failed += 1,
+                DefinitionStatus::None | DefinitionStatus::Disabled => {}
+            }
+        }
+        let mut summary = format!("{}/{} atlases created", created, total);
+        if loading > 0 {
+            summary += &format!(", {} loading", loading);
+        }
+        if failed > 0 {
+            summary += &format!(", {} failed", failed);
+        }
+        summary
+    }
+
+    /// Returns the keys of the definitions that failed, along with the reason - e.g. for logging
+    /// or showing all problems at once after a [ResourceStatus::Failed](crate::ResourceStatus).
+    ///
+    /// In lenient mode, these are all definitions left out. Otherwise creating the atlases stops
+    /// at the first failing definition, though every missing definition is listed.
+    pub fn failures(&self) -> &[(String, ResourceError)] {
+        &self.failures
+    }
+
+    /// Whether the definition `key` failed.
+    pub(crate) fn has_failed(&self, key: &str) -> bool {
+        self.failures
+            .iter()
+            .any(|(failed_key, _)| failed_key == key)
+    }
+
+    /// Whether processing is done, or failed, with nothing new to create.
+    pub(crate) fn is_settled(&self) -> bool {
+        matches!(
+            self.state,
+            DefinitionProcessState::Done
+                | DefinitionProcessState::Failed
+                | DefinitionProcessState::Unloaded
+        ) && !self.rebuild_pending
+    }
+
+    /// Snapshot of the processing, for telling whether a step of it changed anything.
+    pub(crate) fn progress(
+        &self,
+        atlas_definitions: &Assets<GenericAtlasDefinitions>,
+    ) -> DefinitionProgress {
+        let definitions = self
+            .generic_definitions(atlas_definitions)
+            .into_iter()
+            .flat_map(|definitions| definitions.iter())
+            .map(|(key, definition)| (key.clone(), definition.clone()))
+            .collect();
+        (self.state, definitions)
+    }
+
+    fn generic_definitions<'a>(
+        &'a self,
+        atlas_definitions: &'a Assets<GenericAtlasDefinitions>,
+    ) -> Option<&'a GenericAtlasDefinitions> {
+        match &self.definitions {
+            DefinitionsType::Direct(definitions) => Some(definitions.as_ref()),
+            DefinitionsType::Indirect(handle) => atlas_definitions.get(handle),
+            DefinitionsType::Directory { merged, .. } => Some(merged.as_ref()),
+        }
+    }
+}
+
+/// Whether the definition `key` is among the `requested`, when only some are.
+pub(crate) fn is_requested(requested: Option<&HashSet<String>>, key: &str) -> bool {
+    requested.map_or(true, |requested| requested.contains(key))
+}
+
+/// Separates the variant of a definition key from its profile, see
+/// [TypedAtlasDefinition::with_profile].
+pub(crate) const PROFILE_SEPARATOR: char = '@';
+
+/// Splits the definition `key` into its variant and profile, if any.
+pub(crate) fn split_profile(key: &str) -> (&str, Option<&str>) {
+    match key.split_once(PROFILE_SEPARATOR) {
+        Some((variant, profile)) => (variant, Some(profile)),
+        None => (key, None),
+    }
+}
+
+/// Summary of the progress of a single [AtlasDefinition], see
+/// [TypedAtlasDefinition::definitions_status].
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy, Display, IsVariant)]
+pub enum DefinitionStatus {
+    /// Processing hasn't started yet.
+    None,
+    /// The assets of the definition are loading.
+    Loading,
+    /// The [TextureAtlas] has been created.
+    Created,
+    /// Creating the [AtlasTextures<T>](crate::AtlasTextures<T>) failed before the definition
+    /// was created.
+    Failed,
+    /// The definition is disabled, and won't be created.
+    Disabled,
+}
+
+impl<T> From<GenericAtlasDefinitions> for TypedAtlasDefinition<T>
+where
+    T: Send + Sync,
+{
+    fn from(definitions_map: GenericAtlasDefinitions) -> Self {
+        Self::new(DefinitionsType::Direct(Box::new(definitions_map)))
+    }
+}
+
+impl<T> From<HashMap<String, AtlasDefinition>> for TypedAtlasDefinition<T>
+where
+    T: Send + Sync,
+{
+    fn from(definitions_map: HashMap<String, AtlasDefinition>) -> Self {
+        Self::new(DefinitionsType::Direct(Box::new(
+            GenericAtlasDefinitions::from(definitions_map),
+        )))
+    }
+}
+
+#[cfg(feature = "ordered")]
+impl<T> From<AtlasDefinitionMap> for TypedAtlasDefinition<T>
+where
+    T: Send + Sync,
+{
+    fn from(definitions_map: AtlasDefinitionMap) -> Self {
+        Self::new(DefinitionsType::Direct(Box::new(
+            GenericAtlasDefinitions::from(definitions_map),
+        )))
+    }
+}
+
+impl<T> From<Handle<GenericAtlasDefinitions>> for TypedAtlasDefinition<T>
+where
+    T: Send + Sync,
+{
+    fn from(handle: Handle<GenericAtlasDefinitions>) -> Self {
+        Self::new(DefinitionsType::Indirect(handle))
+    }
+}
+
+impl GridAtlasDefinition {
+    /// Defines a grid of `columns` x `rows` tiles of `tile_size` pixels.
+    ///
+    /// Either may be `0`, fitting as many tiles as the image holds - failing with
+    /// [ResourceError::UnevenGrid] unless it holds a whole number of them.
+    pub fn grid(
+        texture: impl Into<PathBuf>,
+        columns: usize,
+        rows: usize,
+        tile_size: UVec2,
+    ) -> Self {
+        Self {
+            texture: texture.into(),
+            columns,
+            rows,
+            tile_size,
+            ..Default::default()
+        }
+    }
+
+    /// Defines a grid of `columns` x `rows` tiles of `tile_size` pixels, from an image that's
+    /// already an asset - e.g. one generated at runtime.
+    ///
+    /// The atlas is created once the image is present in `Assets<Image>`.
+    pub fn grid_from_image(
+        image: Handle<Image>,
+        columns: usize,
+        rows: usize,
+        tile_size: UVec2,
+    ) -> Self {
+        Self {
+            columns,
+            rows,
+            tile_size,
+            state: GridProcessState::LoadingTexture(image.clone_untyped()),
+            ..Default::default()
+        }
+    }
+
+    /// Appends a sheet sharing the layout of the texture, its tiles following those before it.
+    pub fn with_texture(mut self, texture: impl Into<PathBuf>) -> Self {
+        self.textures.push(texture.into());
+        self
+    }
+
+    /// Sets the gap between the tiles.
+    pub fn with_padding(mut self, padding: UVec2) -> Self {
+        self.padding = Some(padding);
+        self
+    }
+
+    /// Sets the order in which the tiles are indexed.
+    pub fn with_order(mut self, order: GridOrder) -> Self {
+        self.order = order;
+        self
+    }
+
+    /// Sets the inset of the region used inside each tile.
+    pub fn with_inner_margin(mut self, inner_margin: UVec2) -> Self {
+        self.inner_margin = Some(inner_margin);
+        self
+    }
+
+    /// Sets the color of the image to treat as transparent.
+    pub fn with_color_key(mut self, color_key: [u8; 3]) -> Self {
+        self.color_key = Some(color_key);
+        self
+    }
+
+    /// Sets the nine-slice borders of every region.
+    pub fn with_nine_patch(mut self, nine_patch: NineSliceBorders) -> Self {
+        self.nine_patch = Some(nine_patch);
+        self
+    }
+
+    /// Sets the factor to resample the image by before creating the atlas.
+    pub fn with_scale(mut self, scale: f32) -> Self {
+        self.scale = Some(scale);
+        self
+    }
+
+    /// Packs the grid into the [TextureAtlas] shared by the group `combine`, see
+    /// [combine](Self::combine).
+    pub fn with_combine(mut self, combine: impl Into<String>) -> Self {
+        self.combine = Some(combine.into());
+        self
+    }
+
+    /// Appends an irregular region after the tiles of the grid.
+    pub fn with_extra_region(mut self, region: AtlasRegion) -> Self {
+        self.extra_regions.push(region);
+        self
+    }
+
+    /// Computes the regions of the grid inside an image of size `image_size`, as when creating
+    /// the atlas - e.g. for previewing the layout in tooling.
+    ///
+    /// Fails if the grid exceeds the image, or doesn't fit a whole number of tiles when computing
+    /// its columns or rows from the image.
+    pub fn rects(&self, image_size: UVec2) -> Result<Vec<Rect>, ResourceError> {
+        crate::layout::grid_rects(self, image_size)
+    }
+
+    /// Total size of the grid, in pixels, including padding between the tiles.
+    ///
+    /// Columns or rows computed from the image count as none.
+    pub fn size(&self) -> UVec2 {
+        crate::layout::grid_size(
+            UVec2::new(self.columns as u32, self.rows as u32),
+            self.tile_size,
+            self.padding.unwrap_or_default(),
+        )
+    }
+}
+
+impl GridByBoundsAtlasDefinition {
+    /// Defines a grid of `tile_size` pixel tiles, from the top-left corner `first` of its first
+    /// tile to the bottom-right corner `last` of its last tile.
+    pub fn bounds(
+        texture: impl Into<PathBuf>,
+        first: UVec2,
+        last: UVec2,
+        tile_size: UVec2,
+    ) -> Self {
+        Self {
+            texture: texture.into(),
+            first,
+            last,
+            tile_size,
+            ..Default::default()
+        }
+    }
+
+    /// Sets the gap between the tiles.
+    pub fn with_padding(mut self, padding: UVec2) -> Self {
+        self.padding = Some(padding);
+        self
+    }
+}
+
+impl PatchAtlasDefinition {
+    /// Defines regions of `width` x `height` pixels, placed at `positions` inside the texture.
+    pub fn patch(
+        texture: impl Into<PathBuf>,
+        width: u32,
+        height: u32,
+        positions: Vec<UVec2>,
+    ) -> Self {
+        Self {
+            texture: texture.into(),
+            width,
+            height,
+            positions,
+            ..Default::default()
+        }
+    }
+
+    /// Sets how to treat positions listed more than once.
+    pub fn with_duplicates(mut self, duplicates: DuplicatePositions) -> Self {
+        self.duplicates = duplicates;
+        self
+    }
+
+    /// Sets the color of the image to treat as transparent.
+    pub fn with_color_key(mut self, color_key: [u8; 3]) -> Self {
+        self.color_key = Some(color_key);
+        self
+    }
+
+    /// Sets the nine-slice borders of every region.
+    pub fn with_nine_patch(mut self, nine_patch: NineSliceBorders) -> Self {
+        self.nine_patch = Some(nine_patch);
+        self
+    }
+
+    /// Sets the factor to resample the image by before creating the atlas.
+    pub fn with_scale(mut self, scale: f32) -> Self {
+        self.scale = Some(scale);
+        self
+    }
+}
+
+impl FramesAtlasDefinition {
+    /// Defines an atlas of the `frames` inside the texture.
+    pub fn frames(texture: impl Into<PathBuf>, frames: Vec<AtlasFrame>) -> Self {
+        Self {
+            texture: texture.into(),
+            frames,
+            ..Default::default()
+        }
+    }
+
+    /// Appends a frame.
+    pub fn with_frame(mut self, frame: AtlasFrame) -> Self {
+        self.frames.push(frame);
+        self
+    }
+}
+
+impl AtlasFrame {
+    /// Defines an unnamed frame at `x`, `y` of `w` x `h` pixels.
+    pub fn new(x: u32, y: u32, w: u32, h: u32) -> Self {
+        Self {
+            x,
+            y,
+            w,
+            h,
+            name: None,
+        }
+    }
+
+    /// Sets the name of the frame.
+    pub fn with_name(mut self, name: impl Into<String>) -> Self {
+        self.name = Some(name.into());
+        self
+    }
+}
+
+impl FolderAtlasDefinition {
+    /// Defines an atlas from all the images inside the folder at `path`.
+    pub fn folder(path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: path.into(),
+            ..Default::default()
+        }
+    }
+
+    /// Defines an atlas packing images that are already assets - e.g. sprites generated at
+    /// runtime.
+    ///
+    /// The atlas is created once all images are present in `Assets<Image>`. The packed layout
+    /// can't be cached, as the images have no files.
+    pub fn from_images(images: impl IntoIterator<Item = Handle<Image>>) -> Self {
+        Self {
+            state: MultiTextureProcessState::LoadingTextures(
+                images
+                    .into_iter()
+                    .map(|image| image.clone_untyped())
+                    .collect(),
+            ),
+            ..Default::default()
+        }
+    }
+
+    /// Sets the file caching the packed layout.
+    pub fn with_cache(mut self, cache: impl Into<PathBuf>) -> Self {
+        self.cache = Some(cache.into());
+        self
+    }
+}
+
+impl GlobAtlasDefinition {
+    /// Defines an atlas of the images matching `pattern`.
+    pub fn glob(pattern: impl Into<String>) -> Self {
+        Self {
+            pattern: pattern.into(),
+            ..Default::default()
+        }
+    }
+}
+
+impl FolderManifestAtlasDefinition {
+    /// Defines an atlas from all the images listed by the manifest at `manifest`.
+    pub fn manifest(manifest: impl Into<PathBuf>) -> Self {
+        Self {
+            manifest: manifest.into(),
+            ..Default::default()
+        }
+    }
+}
+
+impl TexturePackerAtlasDefinition {
+    /// Defines an atlas from the TexturePacker sheet at `sheet`.
+    pub fn texture_packer(sheet: impl Into<PathBuf>) -> Self {
+        Self {
+            sheet: sheet.into(),
+            ..Default::default()
+        }
+    }
+
+    /// Sets the factor to resample the image by before creating the atlas.
+    pub fn with_scale(mut self, scale: f32) -> Self {
+        self.scale = Some(scale);
+        self
+    }
+}
+
+impl ArrayAtlasDefinition {
+    /// Defines an array of the images at `layers`, in order.
+    pub fn array(layers: impl IntoIterator<Item = impl Into<PathBuf>>) -> Self {
+        Self {
+            layers: layers.into_iter().map(Into::into).collect(),
+            ..Default::default()
+        }
+    }
+
+    /// Adds the image at `layer` as the next layer.
+    pub fn with_layer(mut self, layer: impl Into<PathBuf>) -> Self {
+        self.layers.push(layer.into());
+        self
+    }
+}
+
+impl PagedAtlasDefinition {
+    /// Defines a tileset of the grids `pages`, in order.
+    pub fn pages(pages: impl IntoIterator<Item = GridAtlasDefinition>) -> Self {
+        Self {
+            pages: pages.into_iter().collect(),
+            ..Default::default()
+        }
+    }
+
+    /// Adds the grid `page` as the next page.
+    pub fn with_page(mut self, page: GridAtlasDefinition) -> Self {
+        self.pages.push(page);
+        self
+    }
+}
+
+impl AutoDetectAtlasDefinition {
+    /// Defines an atlas of the areas of the image at `texture` with an alpha above
+    /// `alpha_threshold`.
+    pub fn auto_detect(texture: impl Into<PathBuf>, alpha_threshold: u8) -> Self {
+        Self {
+            texture: texture.into(),
+            alpha_threshold,
+            ..Default::default()
+        }
+    }
+
+    /// Sets the upper limit on the number of regions.
+    pub fn with_max_regions(mut self, max_regions: usize) -> Self {
+        self.max_regions = max_regions;
+        self
+    }
+}
+
+impl MosaicAtlasDefinition {
+    /// Defines a mosaic of the images of `cells`, each placed into its cell.
+    pub fn mosaic(cells: impl IntoIterator<Item = (UVec2, impl Into<PathBuf>)>) -> Self {
+        Self {
+            cells: cells
+                .into_iter()
+                .map(|(cell, image)| (cell, image.into()))
+                .collect(),
+            ..Default::default()
+        }
+    }
+
+    /// Places the image at `image` into `cell`, as the next region.
+    pub fn with_cell(mut self, cell: UVec2, image: impl Into<PathBuf>) -> Self {
+        self.cells.push((cell, image.into()));
+        self
+    }
+}
+
+impl FractionalGridAtlasDefinition {
+    /// Defines a grid of `columns` by `rows` tiles of `tile_size` pixels, at the top-left corner
+    /// of the image at `texture`.
+    pub fn fractional_grid(
+        texture: impl Into<PathBuf>,
+        columns: usize,
+        rows: usize,
+        tile_size: Vec2,
+    ) -> Self {
+        Self {
+            texture: texture.into(),
+            columns,
+            rows,
+            tile_size,
+            ..Default::default()
+        }
+    }
+
+    /// Sets the top-left corner of the first tile.
+    pub fn with_offset(mut self, offset: Vec2) -> Self {
+        self.offset = offset;
+        self
+    }
+
+    /// Sets the gap between the tiles.
+    pub fn with_padding(mut self, padding: Vec2) -> Self {
+        self.padding = Some(padding);
+        self
+    }
+}
+
+impl AtlasDefinition {
+    /// Returns the kind of the definition.
+    pub fn kind(&self) -> AtlasDefinitionKind {
+        match self {
+            AtlasDefinition::Grid(_) => AtlasDefinitionKind::Grid,
+            AtlasDefinition::GridByBounds(_) => AtlasDefinitionKind::GridByBounds,
+            AtlasDefinition::Manual(_) => AtlasDefinitionKind::Manual,
+            AtlasDefinition::Frames(_) => AtlasDefinitionKind::Frames,
+            AtlasDefinition::Folder(_) => AtlasDefinitionKind::Folder,
+            AtlasDefinition::FolderManifest(_) => AtlasDefinitionKind::FolderManifest,
+            AtlasDefinition::TexturePacker(_) => AtlasDefinitionKind::TexturePacker,
+            AtlasDefinition::Array(_) => AtlasDefinitionKind::Array,
+            AtlasDefinition::Paged(_) => AtlasDefinitionKind::Paged,
+            AtlasDefinition::AutoDetect(_) => AtlasDefinitionKind::AutoDetect,
+            AtlasDefinition::Glob(_) => AtlasDefinitionKind::Glob,
+            AtlasDefinition::Mosaic(_) => AtlasDefinitionKind::Mosaic,
+            AtlasDefinition::FractionalGrid(_) => AtlasDefinitionKind::FractionalGrid,
+        }
+    }
+
+    /// Options common to every kind of definition.
+    pub(crate) fn options(&self) -> &DefinitionOptions {
+        match self {
+            AtlasDefinition::Grid(d) => &d.options,
+            AtlasDefinition::GridByBounds(d) => &d.options,
+            AtlasDefinition::Manual(d) => &d.options,
+            AtlasDefinition::Frames(d) => &d.options,
+            AtlasDefinition::Folder(d) => &d.options,
+            AtlasDefinition::FolderManifest(d) => &d.options,
+            AtlasDefinition::TexturePacker(d) => &d.options,
+            AtlasDefinition::Array(d) => &d.options,
+            AtlasDefinition::Paged(d) => &d.options,
+            AtlasDefinition::AutoDetect(d) => &d.options,
+            AtlasDefinition::Glob(d) => &d.options,
+            AtlasDefinition::Mosaic(d) => &d.options,
+            AtlasDefinition::FractionalGrid(d) => &d.options,
+        }
+    }
+
+    /// Whether the definition should be created.
+    pub(crate) fn is_enabled(&self) -> bool {
+        self.options().enabled
+    }
+
+    /// Priority of loading the definition, higher first.
+    pub(crate) fn priority(&self) -> i32 {
+        self.options().priority
+    }
+
+    /// Progress of the definition, disregarding failures.
+    pub(crate) fn status(&self) -> DefinitionStatus {
+        let started = match self {
+            AtlasDefinition::Grid(d) => !matches!(d.state, GridProcessState::None),
+            AtlasDefinition::GridByBounds(d) => !matches!(d.state, SingleTextureProcessState::None),
+            AtlasDefinition::Manual(d) => !matches!(d.state, SingleTextureProcessState::None),
+            AtlasDefinition::Frames(d) => !matches!(d.state, SingleTextureProcessState::None),
+            AtlasDefinition::Folder(d) => !matches!(d.state, MultiTextureProcessState::None),
+            AtlasDefinition::FolderManifest(d) => !matches!(d.state, ManifestProcessState::None),
+            AtlasDefinition::TexturePacker(d) => !matches!(d.state, SheetProcessState::None),
+            AtlasDefinition::Array(d) => !matches!(d.state, ArrayProcessState::None),
+            AtlasDefinition::Paged(d) => d
+                .pages
+                .iter()
+                .any(|page| !matches!(page.state, GridProcessState::None)),
+            AtlasDefinition::AutoDetect(d) => !matches!(d.state, SingleTextureProcessState::None),
+            AtlasDefinition::Glob(d) => !matches!(d.state, MultiTextureProcessState::None),
+            AtlasDefinition::Mosaic(d) => !matches!(d.state, MultiTextureProcessState::None),
+            AtlasDefinition::FractionalGrid(d) => {
+                !matches!(d.state, SingleTextureProcessState::None)
+            }
+        };
+        if !self.is_enabled() {
+            DefinitionStatus::Disabled
+        } else if self.texture_atlas().is_some() {
+            DefinitionStatus::Created
+        } else if started {
+            DefinitionStatus::Loading
+        } else {
+            DefinitionStatus::None
+        }
+    }
+
+    /// Takes over the created atlas of `previous`, if it was defined the same way - e.g. when
+    /// other definitions of a reloaded file changed, but this one didn't.
+    ///
+    /// Returns whether the atlas was taken over. Only definitions not started yet take over one.
+    pub(crate) fn reuse_unchanged(&mut self, previous: &AtlasDefinition) -> bool {
+        if self.status() != DefinitionStatus::None || previous.texture_atlas().is_none() {
+            return false;
+        }
+        let mut unprocessed = previous.clone();
+        unprocessed.reset_state();
+        if unprocessed != *self {
+            return false;
+        }
+        *self = previous.clone();
+        true
+    }
+
+    /// Resets the progress of the definition, as if it was never processed.
+    fn reset_state(&mut self) {
+        match self {
+            AtlasDefinition::Grid(d) => d.state = GridProcessState::None,
+            AtlasDefinition::GridByBounds(d) => d.state = SingleTextureProcessState::None,
+            AtlasDefinition::Manual(d) => d.state = SingleTextureProcessState::None,
+            AtlasDefinition::Frames(d) => d.state = SingleTextureProcessState::None,
+            AtlasDefinition::Folder(d) => d.state = MultiTextureProcessState::None,
+            AtlasDefinition::FolderManifest(d) => d.state = ManifestProcessState::None,
+            AtlasDefinition::TexturePacker(d) => d.state = SheetProcessState::None,
+            AtlasDefinition::Array(d) => d.state = ArrayProcessState::None,
+            AtlasDefinition::Paged(d) => d
+                .pages
+                .iter_mut()
+                .for_each(|page| page.state = GridProcessState::None),
+            AtlasDefinition::AutoDetect(d) => d.state = SingleTextureProcessState::None,
+            AtlasDefinition::Glob(d) => d.state = MultiTextureProcessState::None,
+            AtlasDefinition::Mosaic(d) => d.state = MultiTextureProcessState::None,
+            AtlasDefinition::FractionalGrid(d) => d.state = SingleTextureProcessState::None,
+        }
+    }
+
+    /// Whether the definition should be created, but isn't yet.
+    pub(crate) fn is_pending(&self) -> bool {
+        self.is_enabled() && self.texture_atlas().is_none()
+    }
+
+    /// Path of the asset the definition is created from.
+    pub(crate) fn source_path(&self) -> &Path {
+        match self {
+            AtlasDefinition::Grid(d) => &d.texture,
+            AtlasDefinition::GridByBounds(d) => &d.texture,
+            AtlasDefinition::Manual(d) => &d.texture,
+            AtlasDefinition::Frames(d) => &d.texture,
+            AtlasDefinition::Folder(d) => &d.path,
+            AtlasDefinition::FolderManifest(d) => &d.manifest,
+            AtlasDefinition::TexturePacker(d) => &d.sheet,
+            AtlasDefinition::Array(d) => d.layers.first().map_or(Path::new(""), PathBuf::as_path),
+            AtlasDefinition::Paged(d) => d
+                .pages
+                .first()
+                .map_or(Path::new(""), |page| page.texture.as_path()),
+            AtlasDefinition::AutoDetect(d) => &d.texture,
+            AtlasDefinition::Glob(d) => Path::new(&d.pattern),
+            AtlasDefinition::Mosaic(d) => d
+                .cells
+                .first()
+                .map_or(Path::new(""), |(_, image)| image.as_path()),
+            AtlasDefinition::FractionalGrid(d) => &d.texture,
+        }
+    }
+
+    /// Named animations of the definition.
+    pub(crate) fn animations(&self) -> &HashMap<String, AnimationDefinition> {
+        &self.options().animations
+    }
+
+    /// Index shown by sprites not asking for a specific one.
+    pub(crate) fn default_index(&self) -> usize {
+        self.options().default_index
+    }
+
+    /// Group of definitions sharing a single [TextureAtlas].
+    pub(crate) fn combine(&self) -> Option<&str> {
+        match self {
+            AtlasDefinition::Grid(d) => d.combine.as_deref(),
+            _ => None,
+        }
+    }
+
+    /// Nine-slice borders of the regions of the definition.
+    pub(crate) fn nine_patch(&self) -> Option<NineSliceBorders> {
+        match self {
+            AtlasDefinition::Grid(d) => d.nine_patch,
+            AtlasDefinition::Manual(d) => d.nine_patch,
+            _ => None,
+        }
+    }
+
+    /// Stable names for indexes into the created [TextureAtlas].
+    pub(crate) fn remap(&self) -> &HashMap<String, usize> {
+        &self.options().remap
+    }
+
+    /// Named indexes into the created [TextureAtlas], with the `remap` of the definition taking
+    /// precedence over names from the source.
+    pub(crate) fn indices(&self) -> HashMap<String, usize> {
+        let mut indices = match self {
+            AtlasDefinition::TexturePacker(TexturePackerAtlasDefinition {
+                state: SheetProcessState::AtlasCreated(_, indices, _),
+                ..
+            }) => indices.clone(),
+            AtlasDefinition::Frames(d) => d
+                .frames
+                .iter()
+                .enumerate()
+                .filter_map(|(index, frame)| Some((frame.name.clone()?, index)))
+                .collect(),
+            _ => HashMap::default(),
+        };
+        indices.extend(
+            self.remap()
+                .iter()
+                .map(|(name, index)| (name.clone(), *index)),
+        );
+        indices
+    }
+
+    /// Images the [TextureAtlas] was created from, if retained.
+    pub(crate) fn source_images(&self) -> &[Handle<Image>] {
+        match self {
+            AtlasDefinition::Grid(GridAtlasDefinition {
+                state: GridProcessState::AtlasCreated(_, images),
+                ..
+            })
+            | AtlasDefinition::GridByBounds(GridByBoundsAtlasDefinition {
+                state: SingleTextureProcessState::AtlasCreated(_, images),
+                ..
+            })
+            | AtlasDefinition::Manual(PatchAtlasDefinition {
+                state: SingleTextureProcessState::AtlasCreated(_, images),
+                ..
+            })
+            | AtlasDefinition::Frames(FramesAtlasDefinition {
+                state: SingleTextureProcessState::AtlasCreated(_, images),
+                ..
+            })
+            | AtlasDefinition::AutoDetect(AutoDetectAtlasDefinition {
+                state: SingleTextureProcessState::AtlasCreated(_, images),
+                ..
+            })
+            | AtlasDefinition::FractionalGrid(FractionalGridAtlasDefinition {
+                state: SingleTextureProcessState::AtlasCreated(_, images),
+                ..
+            })
+            | AtlasDefinition::Folder(FolderAtlasDefinition {
+                state: MultiTextureProcessState::AtlasCreated(_, images),
+                ..
+            })
+            | AtlasDefinition::Glob(GlobAtlasDefinition {
+                state: MultiTextureProcessState::AtlasCreated(_, images),
+                ..
+            })
+            | AtlasDefinition::Mosaic(MosaicAtlasDefinition {
+                state: MultiTextureProcessState::AtlasCreated(_, images),
+                ..
+            })
+            | AtlasDefinition::FolderManifest(FolderManifestAtlasDefinition {
+                state: ManifestProcessState::AtlasCreated(_, images),
+                ..
+            })
+            | AtlasDefinition::TexturePacker(TexturePackerAtlasDefinition {
+                state: SheetProcessState::AtlasCreated(_, _, images),
+                ..
+            })
+            | AtlasDefinition::Array(ArrayAtlasDefinition {
+                state: ArrayProcessState::AtlasCreated(_, _, images),
+                ..
+            }) => images,
+            _ => &[],
+        }
+    }
+
+    /// Texture array of the layers, for array definitions.
+    pub(crate) fn texture_array(&self) -> Option<&Handle<Image>> {
+        match self {
+            AtlasDefinition::Array(ArrayAtlasDefinition {
+                state: ArrayProcessState::AtlasCreated(_, array, _),
+                ..
+            }) => Some(array),
+            _ => None,
+        }
+    }
+
+    /// Atlases of the pages once all are created, or the single atlas of other definitions.
+    pub(crate) fn pages(&self) -> Vec<&Handle<TextureAtlas>> {
+        match self {
+            AtlasDefinition::Paged(d) => d
+                .pages
+                .iter()
+                .map(|page| page.state.texture_atlas())
+                .collect::<Option<_>>()
+                .unwrap_or_default(),
+            _ => self.texture_atlas().into_iter().collect(),
+        }
+    }
+}
+
+impl GetTextureAtlas for SingleTextureProcessState {
+    fn texture_atlas(&self) -> Option<&Handle<TextureAtlas>> {
+        match self {
+            Self::AtlasCreated(handle, _) => Some(handle),
+            _ => None,
+        }
+    }
+}
+
+impl GetTextureAtlas for GridProcessState {
+    fn texture_atlas(&self) -> Option<&Handle<TextureAtlas>> {
+        match self {
+            Self::AtlasCreated(handle, _) => Some(handle),
+            _ => None,
+        }
+    }
+}
+
+impl GetTextureAtlas for MultiTextureProcessState {
+    fn texture_atlas(&self) -> Option<&Handle<TextureAtlas>> {
+        match self {
+            Self::AtlasCreated(handle, _) => Some(handle),
+            _ => None,
+        }
+    }
+}
+
+impl GetTextureAtlas for ManifestProcessState {
+    fn texture_atlas(&self) -> Option<&Handle<TextureAtlas>> {
+        match self {
+            Self::AtlasCreated(handle, _) => Some(handle),
+            _ => None,
+        }
+    }
+}
+
+impl GetTextureAtlas for SheetProcessState {
+    fn texture_atlas(&self) -> Option<&Handle<TextureAtlas>> {
+        match self {
+            Self::AtlasCreated(handle, ..) => Some(handle),
+            _ => None,
+        }
+    }
+}
+
+impl GetTextureAtlas for ArrayProcessState {
+    fn texture_atlas(&self) -> Option<&Handle<TextureAtlas>> {
+        match self {
+            Self::AtlasCreated(handle, ..) => Some(handle),
+            _ => None,
+        }
+    }
+}
+
+impl GetTextureAtlas for AtlasDefinition {
+    fn texture_atlas(&self) -> Option<&Handle<TextureAtlas>> {
+        match self {
+            AtlasDefinition::Grid(d) => d.state.texture_atlas(),
+            AtlasDefinition::GridByBounds(d) => d.state.texture_atlas(),
+            AtlasDefinition::Manual(d) => d.state.texture_atlas(),
+            AtlasDefinition::Frames(d) => d.state.texture_atlas(),
+            AtlasDefinition::Folder(d) => d.state.texture_atlas(),
+            AtlasDefinition::FolderManifest(d) => d.state.texture_atlas(),
+            AtlasDefinition::TexturePacker(d) => d.state.texture_atlas(),
+            AtlasDefinition::Array(d) => d.state.texture_atlas(),
+            AtlasDefinition::Paged(d) => {
+                // only created once all of the pages are
+                let mut atlases = d.pages.iter().map(|page| page.state.texture_atlas());
+                let first = atlases.next()??;
+                atlases.all(|atlas| atlas.is_some()).then(|| first)
+            }
+            AtlasDefinition::AutoDetect(d) => d.state.texture_atlas(),
+            AtlasDefinition::Glob(d) => d.state.texture_atlas(),
+            AtlasDefinition::Mosaic(d) => d.state.texture_atlas(),
+            AtlasDefinition::FractionalGrid(d) => d.state.texture_atlas(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    mod config_file {
+        mod allows_format {
+            use crate::*;
+
+            type Result = anyhow::Result<()>;
+
+            #[test]
+            fn patchwork() -> Result {
+                let cfg_file = indoc::indoc! {r#"
+                    ({
+                        "patchwork": (
+                            texture: "Pac-Man.png",
+                            width: 19,
+                            height: 19,
+                            positions: [
+                                (65, 86),
+                                (86, 86),
+                                (107, 86),
+                            ]
+                        ),
+                    })"#};
+
+                let config: GenericAtlasDefinitions = ron::from_str(cfg_file)?;
+                match &config["patchwork"] {
+                    AtlasDefinition::Manual(patch) => {
+                        assert_eq!(patch.texture, std::path::Path::new("Pac-Man.png"));
+                        assert_eq!((patch.width, patch.height), (19, 19));
+                        assert_eq!(
+                            patch.positions,
+                            vec![UVec2::new(65, 86), UVec2::new(86, 86), UVec2::new(107, 86)]
+                        );
+                    }
+                    definition => panic!("unexpected definition {:?}", definition),
+                }
+                Ok(())
+            }
+
+            #[test]
+            fn grid() -> Result {
+                let cfg_file = indoc::indoc! {r#"
+                    ({
+                        "grid": (
+                            texture: "Pac-Man.png",
+                            columns: 8,
+                            rows: 4,
+                            tile_size: (20, 20),
+                            padding: None,
+                        ),
+                    })"#};
+
+                let config: GenericAtlasDefinitions = ron::from_str(cfg_file)?;
+                match &config["grid"] {
+                    AtlasDefinition::Grid(grid) => {
+                        assert_eq!(grid.texture, std::path::Path::new("Pac-Man.png"));
+                        assert_eq!((grid.columns, grid.rows), (8, 4));
+                        assert_eq!(grid.tile_size, UVec2::new(20, 20));
+                        assert_eq!(grid.padding, None);
+                    }
+                    definition => panic!("unexpected definition {:?}", definition),
+                }
+                Ok(())
+            }
+
+            #[test]
+            fn grid_in_column_major_order() -> Result {
+                let cfg_file = indoc::indoc! {r#"
+                    ({
+                        "grid": (
+                            texture: "Pac-Man.png",
+                            columns: 8,
+                            rows: 4,
+                            tile_size: (20, 20),
+                            padding: None,
+                            order: ColumnMajor,
+                        ),
+                    })"#};
+
+                let config: GenericAtlasDefinitions = ron::from_str(cfg_file)?;
+                match &config["grid"] {
+                    AtlasDefinition::Grid(grid) => assert_eq!(grid.order, GridOrder::ColumnMajor),
+                    definition => panic!("unexpected definition {:?}", definition),
+                }
+                Ok(())
+            }
+
+            #[test]
+            fn pages() -> Result {
+                let cfg_file = indoc::indoc! {r#"
+                    ({
+                        "tiles": (
+                            pages: [
+                                (
+                                    texture: "tiles0.png",
+                                    columns: 64,
+                                    rows: 64,
+                                    tile_size: (32, 32),
+                                ),
+                                (
+                                    texture: "tiles1.png",
+                                    columns: 64,
+                                    rows: 16,
+                                    tile_size: (32, 32),
+                                ),
+                            ],
+                            remap: {
+                                "water": 4096,
+                            },
+                        ),
+                    })"#};
+
+                let config: GenericAtlasDefinitions = ron::from_str(cfg_file)?;
+                match &config["tiles"] {
+                    AtlasDefinition::Paged(paged) => assert_eq!(paged.pages.len(), 2),
+                    definition => panic!("unexpected definition {:?}", definition),
+                }
+                Ok(())
+            }
+
+            #[cfg(feature = "ordered")]
+            #[test]
+            fn in_listed_order() -> Result {
+                let cfg_file = indoc::indoc! {r#"
+                    ({
+                        "walk": (texture: "walk.png", columns: 8, rows: 1, tile_size: (16, 16)),
+                        "idle": (texture: "idle.png", columns: 4, rows: 1, tile_size: (16, 16)),
+                        "jump": (texture: "jump.png", columns: 6, rows: 1, tile_size: (16, 16)),
+                    })"#};
+
+                let config: GenericAtlasDefinitions = ron::from_str(cfg_file)?;
+                assert_eq!(
+                    config.keys().collect::<Vec<_>>(),
+                    vec!["walk", "idle", "jump"]
+                );
+                Ok(())
+            }
+
+            #[test]
+            fn json() -> Result {
+                let ron_file = indoc::indoc! {r#"
+                    ({
+                        "grid": (
+                            texture: "Pac-Man.png",
+                            columns: 8,
+                            rows: 4,
+                            tile_size: (20, 20),
+                        ),
+                    })"#};
+                let json_file = indoc::indoc! {r#"
+                    {
+                        "grid": {
+                            "texture": "Pac-Man.png",
+                            "columns": 8,
+                            "rows": 4,
+                            "tile_size": [20, 20]
+                        }
+                    }"#};
+
+                let from_ron = GenericAtlasDefinitions::from_ron_bytes(ron_file.as_bytes())?;
+                let from_json = GenericAtlasDefinitions::from_json_bytes(json_file.as_bytes())?;
+                assert_eq!(from_ron.len(), from_json.len());
+                for (key, definition) in from_ron.iter() {
+                    assert_eq!(format!("{:?}", definition), format!("{:?}", from_json[key]));
+                }
+                Ok(())
+            }
+
+            #[cfg(feature = "toml")]
+            #[test]
+            fn toml() -> Result {
+                let ron_file = indoc::indoc! {r#"
+                    ({
+                        "grid": (
+                            texture: "Pac-Man.png",
+                            columns: 8,
+                            rows: 4,
+                            tile_size: (20, 20),
+                            padding: Some((1, 1)),
+                        ),
+                        "frames": (
+                            texture: "Pac-Man.png",
+                            frames: [
+                                (x: 0, y: 0, w: 20, h: 20),
+                                (x: 20, y: 0, w: 20, h: 20),
+                            ],
+                        ),
+                    })"#};
+                let toml_file = indoc::indoc! {r#"
+                    [grid]
+                    texture = "Pac-Man.png"
+                    columns = 8
+                    rows = 4
+                    tile_size = [20, 20]
+                    padding = [1, 1]
+
+                    [frames]
+                    texture = "Pac-Man.png"
+                    frames = [
+                        { x = 0, y = 0, w = 20, h = 20 },
+                        { x = 20, y = 0, w = 20, h = 20 },
+                    ]
+                    "#};
+
+                let from_ron = GenericAtlasDefinitions::from_ron_bytes(ron_file.as_bytes())?;
+                let from_toml = GenericAtlasDefinitions::from_toml_str(toml_file)?;
+                assert_eq!(from_ron.len(), from_toml.len());
+                for (key, definition) in from_ron.iter() {
+                    assert_eq!(format!("{:?}", definition), format!("{:?}", from_toml[key]));
+                }
+                Ok(())
+            }
+
+            #[cfg(feature = "toml")]
+            #[test]
+            fn toml_asset() -> Result {
+                let from_ron = GenericAtlasDefinitions::from_ron_bytes(include_bytes!(
+                    "../assets/sprite_sheets.atlasmap"
+                ))?;
+                let from_toml = GenericAtlasDefinitions::from_toml_bytes(include_bytes!(
+                    "../assets/sprite_sheets.atlas.toml"
+                ))?;
+                assert_eq!(
+                    format!("{:?}", from_ron["Pacman"]),
+                    format!("{:?}", from_toml["Pacman"])
+                );
+                Ok(())
+            }
+
+            #[test]
+            fn grid_with_array_sizes() -> Result {
+                let cfg_file = indoc::indoc! {r#"
+                    ({
+                        "grid": (
+                            texture: "Pac-Man.png",
+                            columns: 8,
+                            rows: 4,
+                            tile_size: [20, 20],
+                            padding: Some([1, 1]),
+                            inner_margin: Some([1, 1]),
+                        ),
+                    })"#};
+
+                let config: GenericAtlasDefinitions = ron::from_str(cfg_file)?;
+                match &config["grid"] {
+                    AtlasDefinition::Grid(grid) => {
+                        assert_eq!(grid.tile_size, UVec2::new(20, 20));
+                        assert_eq!(grid.padding, Some(UVec2::new(1, 1)));
+                        assert_eq!(grid.inner_margin, Some(UVec2::new(1, 1)));
+                    }
+                    definition => panic!("unexpected definition {:?}", definition),
+                }
+                Ok(())
+            }
+
+            #[test]
+            fn color_key() -> Result {
+                let cfg_file = indoc::indoc! {r#"
+                    ({
+                        "grid": (
+                            texture: "Pac-Man.png",
+                            columns: 8,
+                            rows: 4,
+                            tile_size: (20, 20),
+                            color_key: Some((255, 0, 255)),
+                        ),
+                    })"#};
+
+                let config: GenericAtlasDefinitions = ron::from_str(cfg_file)?;
+                match &config["grid"] {
+                    AtlasDefinition::Grid(grid) => assert_eq!(grid.color_key, Some([255, 0, 255])),
+                    definition => panic!("unexpected definition {:?}", definition),
+                }
+                Ok(())
+            }
+
+            #[test]
+            fn grid_with_extra_regions() -> Result {
+                let cfg_file = indoc::indoc! {r#"
+                    ({
+                        "grid": (
+                            texture: "Pac-Man.png",
+                            columns: 8,
+                            rows: 4,
+                            tile_size: (20, 20),
+                            extra_regions: [
+                                (position: (0, 80), size: (40, 20)),
+                            ],
+                        ),
+                    })"#};
+
+                let config: GenericAtlasDefinitions = ron::from_str(cfg_file)?;
+                match &config["grid"] {
+                    AtlasDefinition::Grid(grid) => assert_eq!(
+                        grid.extra_regions,
+                        vec![AtlasRegion::new(UVec2::new(0, 80), UVec2::new(40, 20))]
+                    ),
+                    definition => panic!("unexpected definition {:?}", definition),
+                }
+                Ok(())
+            }
+
+            #[test]
+            fn nine_patch() -> Result {
+                let cfg_file = indoc::indoc! {r#"
+                    ({
+                        "button": (
+                            texture: "button.png",
+                            width: 32,
+                            height: 16,
+                            positions: [(0, 0), (32, 0)],
+                            nine_patch: Some((left: 4, right: 4, top: 2, bottom: 3)),
+                        ),
+                    })"#};
+
+                let config: GenericAtlasDefinitions = ron::from_str(cfg_file)?;
+                assert_eq!(
+                    config["button"].nine_patch(),
+                    Some(NineSliceBorders::new(4, 4, 2, 3))
+                );
+                Ok(())
+            }
+
+            #[test]
+            fn animations() -> Result {
+                let cfg_file = indoc::indoc! {r#"
+                    ({
+                        "patchwork": (
+                            texture: "Pac-Man.png",
+                            width: 19,
+                            height: 19,
+                            positions: [
+                                (65, 86),
+                                (86, 86),
+                                (107, 86),
+                            ],
+                            animations: {
+                                "chomp": (frames: [0, 1, 2, 1], fps: 5.0),
+                            },
+                        ),
+                    })"#};
+
+                let config: GenericAtlasDefinitions = ron::from_str(cfg_file)?;
+                let animation = &config["patchwork"].animations()["chomp"];
+                assert_eq!(animation.frames, vec![0, 1, 2, 1]);
+                assert_eq!(animation.fps, 5.0);
+                Ok(())
+            }
+
+            #[test]
             fn folder() -> Result {
                 let cfg_file = indoc::indoc! {r#"
                     ({
@@ -385,7 +2864,173 @@ mod tests {
                     })"#};
 
                 let config: GenericAtlasDefinitions = ron::from_str(cfg_file)?;
-                dbg!(config);
+                match &config["folder"] {
+                    AtlasDefinition::Folder(folder) => {
+                        assert_eq!(folder.path, std::path::Path::new("texture-folder"));
+                        assert_eq!(folder.cache, None);
+                    }
+                    definition => panic!("unexpected definition {:?}", definition),
+                }
+                Ok(())
+            }
+
+            #[test]
+            fn folder_with_cache() -> Result {
+                let cfg_file = indoc::indoc! {r#"
+                    ({
+                        "folder": (
+                            path: "texture-folder",
+                            cache: Some("texture-folder.layout"),
+                        ),
+                    })"#};
+
+                let config: GenericAtlasDefinitions = ron::from_str(cfg_file)?;
+                match &config["folder"] {
+                    AtlasDefinition::Folder(folder) => assert_eq!(
+                        folder.cache.as_deref(),
+                        Some(std::path::Path::new("texture-folder.layout"))
+                    ),
+                    definition => panic!("unexpected definition {:?}", definition),
+                }
+                Ok(())
+            }
+
+            #[test]
+            fn folder_manifest() -> Result {
+                let cfg_file = indoc::indoc! {r#"
+                    ({
+                        "folder": (
+                            manifest: "texture-folder.manifest.ron",
+                        ),
+                    })"#};
+
+                let config: GenericAtlasDefinitions = ron::from_str(cfg_file)?;
+                assert_eq!(config["folder"].kind(), AtlasDefinitionKind::FolderManifest);
+                Ok(())
+            }
+
+            #[test]
+            fn disabled() -> Result {
+                let cfg_file = indoc::indoc! {r#"
+                    ({
+                        "folder": (
+                            path: "texture-folder",
+                            enabled: false,
+                        ),
+                    })"#};
+
+                let config: GenericAtlasDefinitions = ron::from_str(cfg_file)?;
+                assert!(!config["folder"].is_enabled());
+                Ok(())
+            }
+
+            #[test]
+            fn texture_packer() -> Result {
+                let cfg_file = indoc::indoc! {r#"
+                    ({
+                        "sheet": (
+                            sheet: "sprites.tpsheet.json",
+                        ),
+                    })"#};
+
+                let config: GenericAtlasDefinitions = ron::from_str(cfg_file)?;
+                assert!(matches!(config["sheet"], AtlasDefinition::TexturePacker(_)));
+                Ok(())
+            }
+
+            #[test]
+            fn merged_from_several_files() -> Result {
+                let first: GenericAtlasDefinitions = ron::from_str(indoc::indoc! {r#"
+                    ({
+                        "grid": (
+                            texture: "first.png",
+                            columns: 8,
+                            rows: 4,
+                            tile_size: (20, 20),
+                        ),
+                    })"#})?;
+                let second: GenericAtlasDefinitions = ron::from_str(indoc::indoc! {r#"
+                    ({
+                        "grid": (
+                            texture: "second.png",
+                            columns: 8,
+                            rows: 4,
+                            tile_size: (20, 20),
+                        ),
+                        "folder": (
+                            path: "texture-folder",
+                        ),
+                    })"#})?;
+
+                let merged = GenericAtlasDefinitions::merge([
+                    (String::from("first.atlasmap"), &first),
+                    (String::from("second.atlasmap"), &second),
+                ]);
+
+                assert_eq!(merged.len(), 2);
+                assert_eq!(
+                    merged["grid"].source_path(),
+                    std::path::Path::new("first.png")
+                );
+                Ok(())
+            }
+
+            #[test]
+            fn remap() -> Result {
+                let cfg_file = indoc::indoc! {r#"
+                    ({
+                        "grid": (
+                            texture: "Pac-Man.png",
+                            columns: 8,
+                            rows: 4,
+                            tile_size: (20, 20),
+                            remap: {
+                                "player": 3,
+                                "ghost": 12,
+                            },
+                        ),
+                    })"#};
+
+                let config: GenericAtlasDefinitions = ron::from_str(cfg_file)?;
+                let indices = config["grid"].indices();
+                assert_eq!(indices.get("player"), Some(&3));
+                assert_eq!(indices.get("ghost"), Some(&12));
+                Ok(())
+            }
+
+            #[test]
+            fn grid_by_bounds() -> Result {
+                let cfg_file = indoc::indoc! {r#"
+                    ({
+                        "bounds": (
+                            texture: "Pac-Man.png",
+                            first: (4, 4),
+                            last: (64, 24),
+                            tile_size: (20, 20),
+                        ),
+                    })"#};
+
+                let config: GenericAtlasDefinitions = ron::from_str(cfg_file)?;
+                assert_eq!(config["bounds"].kind(), AtlasDefinitionKind::GridByBounds);
+                Ok(())
+            }
+
+            #[test]
+            fn frames() -> Result {
+                let cfg_file = indoc::indoc! {r#"
+                    ({
+                        "frames": (
+                            texture: "Pac-Man.png",
+                            frames: [
+                                (x: 0, y: 0, w: 19, h: 19, name: Some("pacman")),
+                                (x: 19, y: 0, w: 38, h: 19),
+                            ],
+                        ),
+                    })"#};
+
+                let config: GenericAtlasDefinitions = ron::from_str(cfg_file)?;
+                assert_eq!(config["frames"].kind(), AtlasDefinitionKind::Frames);
+                assert_eq!(config["frames"].indices().get("pacman"), Some(&0));
                 Ok(())
             }
 
@@ -416,9 +3061,61 @@ mod tests {
                     })"#};
 
                 let config: GenericAtlasDefinitions = ron::from_str(cfg_file)?;
-                dbg!(config);
+                assert_eq!(config.len(), 3);
+                assert_eq!(config["patchwork"].kind(), AtlasDefinitionKind::Manual);
+                assert_eq!(config["grid"].kind(), AtlasDefinitionKind::Grid);
+                assert_eq!(config["folder"].kind(), AtlasDefinitionKind::Folder);
                 Ok(())
             }
         }
     }
+
+    mod kinds {
+        use crate::*;
+
+        #[test]
+        fn lists_every_kind_in_order() {
+            // adding a kind fails to compile here, as a reminder to list it
+            let position = |kind: &AtlasDefinitionKind| match kind {
+                AtlasDefinitionKind::Grid => 0,
+                AtlasDefinitionKind::GridByBounds => 1,
+                AtlasDefinitionKind::Manual => 2,
+                AtlasDefinitionKind::Frames => 3,
+                AtlasDefinitionKind::Folder => 4,
+                AtlasDefinitionKind::FolderManifest => 5,
+                AtlasDefinitionKind::TexturePacker => 6,
+                AtlasDefinitionKind::Array => 7,
+                AtlasDefinitionKind::Paged => 8,
+                AtlasDefinitionKind::AutoDetect => 9,
+                AtlasDefinitionKind::Glob => 10,
+                AtlasDefinitionKind::Mosaic => 11,
+                AtlasDefinitionKind::FractionalGrid => 12,
+            };
+
+            assert_eq!(
+                AtlasDefinitionKind::KINDS
+                    .iter()
+                    .map(position)
+                    .collect::<Vec<_>>(),
+                (0..13).collect::<Vec<_>>()
+            );
+        }
+    }
+
+    mod reflect {
+        use crate::*;
+        use bevy::prelude::*;
+        use std::path::Path;
+
+        #[test]
+        fn exposes_fields_but_not_state() {
+            let definition =
+                GridAtlasDefinition::grid(Path::new("image.png"), 4, 3, UVec2::new(16, 16));
+
+            let reflected: &dyn Struct = &definition;
+            assert_eq!(reflected.get_field::<usize>("columns"), Some(&4));
+            assert_eq!(reflected.get_field::<usize>("rows"), Some(&3));
+            assert!(reflected.field("state").is_none());
+        }
+    }
 }
@@ -0,0 +1,163 @@
+//! Detection of the regions of a sheet, as the connected opaque areas of the image.
+
+use bevy::{prelude::*, render::render_resource::TextureFormat, sprite::Rect};
+
+use crate::ResourceError;
+
+/// Finds the bounds of every connected area of `image` with an alpha above `alpha_threshold`,
+/// sorted top-left to bottom-right.
+///
+/// Pixels touching diagonally are connected as well. Fails if more than `max_regions` are found.
+pub(crate) fn detect_regions(
+    image: &Image,
+    alpha_threshold: u8,
+    max_regions: usize,
+) -> Result<Vec<Rect>, ResourceError> {
+    match image.texture_descriptor.format {
+        TextureFormat::Rgba8Unorm
+        | TextureFormat::Rgba8UnormSrgb
+        | TextureFormat::Bgra8Unorm
+        | TextureFormat::Bgra8UnormSrgb => {}
+        format => return Err(ResourceError::UnsupportedTextureFormat(format)),
+    }
+    let size = image.size().as_uvec2();
+    let (width, height) = (size.x as usize, size.y as usize);
+    let mut opaque = image
+        .data
+        .chunks_exact(4)
+        .take(width * height)
+        .map(|pixel| pixel[3] > alpha_threshold)
+        .collect::<Vec<_>>();
+
+    let mut regions = Vec::new();
+    let mut pending = Vec::new();
+    for start in 0..opaque.len() {
+        if !opaque[start] {
+            continue;
+        }
+        if regions.len() == max_regions {
+            return Err(ResourceError::TooManyRegions(max_regions));
+        }
+        // flood fill the area, clearing its pixels so they're only visited once
+        opaque[start] = false;
+        pending.push(start);
+        let (mut min, mut max) = (UVec2::splat(u32::MAX), UVec2::ZERO);
+        while let Some(pixel) = pending.pop() {
+            let (x, y) = (pixel % width, pixel / width);
+            let position = UVec2::new(x as u32, y as u32);
+            min = min.min(position);
+            max = max.max(position + UVec2::ONE);
+            for ny in y.saturating_sub(1)..(y + 2).min(height) {
+                for nx in x.saturating_sub(1)..(x + 2).min(width) {
+                    let neighbour = ny * width + nx;
+                    if opaque[neighbour] {
+                        opaque[neighbour] = false;
+                        pending.push(neighbour);
+                    }
+                }
+            }
+        }
+        regions.push(Rect {
+            min: min.as_vec2(),
+            max: max.as_vec2(),
+        });
+    }
+    regions.sort_by(|a, b| {
+        (a.min.y, a.min.x)
+            .partial_cmp(&(b.min.y, b.min.x))
+            .expect("regions have whole coordinates")
+    });
+    Ok(regions)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy::render::render_resource::{Extent3d, TextureDimension};
+
+    /// Image of `rows`, with `#` for opaque and `.` for transparent pixels.
+    fn image(rows: &[&str]) -> Image {
+        Image::new(
+            Extent3d {
+                width: rows[0].len() as u32,
+                height: rows.len() as u32,
+                depth_or_array_layers: 1,
+            },
+            TextureDimension::D2,
+            rows.iter()
+                .flat_map(|row| row.chars())
+                .flat_map(|pixel| [255, 255, 255, if pixel == '#' { 255 } else { 0 }])
+                .collect(),
+            TextureFormat::Rgba8UnormSrgb,
+        )
+    }
+
+    fn bounds(regions: &[Rect]) -> Vec<[f32; 4]> {
+        regions
+            .iter()
+            .map(|rect| [rect.min.x, rect.min.y, rect.max.x, rect.max.y])
+            .collect()
+    }
+
+    #[test]
+    fn regions_are_sorted_top_left_to_bottom_right() {
+        let image = image(&[
+            "....##", //
+            "##..##", //
+            "##....", //
+            "...#..", //
+        ]);
+
+        let regions = detect_regions(&image, 0, 16).unwrap();
+
+        assert_eq!(
+            bounds(&regions),
+            vec![[4., 0., 6., 2.], [0., 1., 2., 3.], [3., 3., 4., 4.]]
+        );
+    }
+
+    #[test]
+    fn diagonal_pixels_are_connected() {
+        let image = image(&[
+            "#...", //
+            ".#..", //
+            "..##", //
+        ]);
+
+        let regions = detect_regions(&image, 0, 16).unwrap();
+
+        assert_eq!(bounds(&regions), vec![[0., 0., 4., 3.]]);
+    }
+
+    #[test]
+    fn faint_pixels_below_the_threshold_are_transparent() {
+        let mut image = image(&["#.#"]);
+        image.data[7] = 100;
+
+        assert_eq!(detect_regions(&image, 0, 16).unwrap().len(), 1);
+        assert_eq!(detect_regions(&image, 100, 16).unwrap().len(), 2);
+    }
+
+    #[test]
+    fn too_many_regions_fail() {
+        let image = image(&["#.#.#"]);
+
+        assert_eq!(
+            detect_regions(&image, 0, 2),
+            Err(ResourceError::TooManyRegions(2))
+        );
+    }
+
+    #[test]
+    fn other_formats_are_unsupported() {
+        let mut image = image(&["#"]);
+        image.texture_descriptor.format = TextureFormat::Rg16Uint;
+
+        assert_eq!(
+            detect_regions(&image, 0, 16),
+            Err(ResourceError::UnsupportedTextureFormat(
+                TextureFormat::Rg16Uint
+            ))
+        );
+    }
+}
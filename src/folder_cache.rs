@@ -0,0 +1,160 @@
+//! Cache of the packed layout of folder atlases, allowing to skip packing on later runs.
+
+use bevy::{
+    asset::AssetServerSettings,
+    prelude::*,
+    render::render_resource::{Extent3d, TextureDimension, TextureFormat},
+    sprite::{Rect, TextureAtlas},
+    utils::HashMap,
+};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+/// Packed layout of a folder atlas, as stored in the cache file.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub(crate) struct FolderAtlasLayout {
+    size: UVec2,
+    textures: Vec<CachedTexture>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct CachedTexture {
+    path: PathBuf,
+    position: UVec2,
+    size: UVec2,
+}
+
+impl FolderAtlasLayout {
+    /// Collects the layout of an atlas created by a [TextureAtlasBuilder].
+    pub(crate) fn from_atlas(atlas: &TextureAtlas, asset_server: &AssetServer) -> Option<Self> {
+        let mut textures = atlas
+            .texture_handles
+            .as_ref()?
+            .iter()
+            .map(|(handle, &index)| {
+                let rect = atlas.textures[index];
+                Some((
+                    index,
+                    CachedTexture {
+                        path: asset_server.get_handle_path(handle)?.path().to_path_buf(),
+                        position: rect.min.as_uvec2(),
+                        size: (rect.max - rect.min).as_uvec2(),
+                    },
+                ))
+            })
+            .collect::<Option<Vec<_>>>()?;
+        textures.sort_by_key(|&(index, _)| index);
+        Some(Self {
+            size: atlas.size.as_uvec2(),
+            textures: textures.into_iter().map(|(_, texture)| texture).collect(),
+        })
+    }
+
+    /// Creates an atlas from the layout, copying the loaded textures into place.
+    ///
+    /// Returns `None` if the layout is outdated compared to the loaded textures.
+    pub(crate) fn create_atlas(
+        &self,
+        handles: &[HandleUntyped],
+        asset_server: &AssetServer,
+        texture_images: &mut Assets<Image>,
+    ) -> Option<TextureAtlas> {
+        if handles.len() != self.textures.len() {
+            return None;
+        }
+        let handles = handles
+            .iter()
+            .map(|handle| {
+                Some((
+                    asset_server.get_handle_path(handle)?.path().to_path_buf(),
+                    handle.clone().typed::<Image>(),
+                ))
+            })
+            .collect::<Option<HashMap<_, _>>>()?;
+
+        let mut atlas_image = Image::new_fill(
+            Extent3d {
+                width: self.size.x,
+                height: self.size.y,
+                depth_or_array_layers: 1,
+            },
+            TextureDimension::D2,
+            &[0, 0, 0, 0],
+            TextureFormat::Rgba8UnormSrgb,
+        );
+        let mut rects = Vec::with_capacity(self.textures.len());
+        let mut texture_handles = HashMap::default();
+        for (index, cached) in self.textures.iter().enumerate() {
+            let handle = handles.get(&cached.path)?;
+            let texture = texture_images.get(handle)?;
+            if texture.texture_descriptor.format != TextureFormat::Rgba8UnormSrgb
+                || texture.size().as_uvec2() != cached.size
+                || (cached.position + cached.size).cmpgt(self.size).any()
+            {
+                return None;
+            }
+            copy_texture(&mut atlas_image, texture, cached.position);
+            rects.push(Rect {
+                min: cached.position.as_vec2(),
+                max: (cached.position + cached.size).as_vec2(),
+            });
+            texture_handles.insert(handle.clone_weak(), index);
+        }
+
+        Some(TextureAtlas {
+            texture: texture_images.add(atlas_image),
+            size: self.size.as_vec2(),
+            textures: rects,
+            texture_handles: Some(texture_handles),
+        })
+    }
+}
+
+/// Copies the pixels of a RGBA `texture` into `atlas_image` at `position`.
+fn copy_texture(atlas_image: &mut Image, texture: &Image, position: UVec2) {
+    const PIXEL_SIZE: usize = 4;
+    let atlas_width = atlas_image.texture_descriptor.size.width as usize;
+    let row_size = texture.texture_descriptor.size.width as usize * PIXEL_SIZE;
+    let rows = texture.texture_descriptor.size.height as usize;
+    for (row, pixels) in texture.data.chunks_exact(row_size).take(rows).enumerate() {
+        let begin = ((position.y as usize + row) * atlas_width + position.x as usize) * PIXEL_SIZE;
+        atlas_image.data[begin..begin + row_size].copy_from_slice(pixels);
+    }
+}
+
+/// Reads the layout cached at `path`, relative to the asset folder.
+#[cfg(not(any(target_arch = "wasm32", target_os = "android")))]
+pub(crate) fn load(settings: &AssetServerSettings, path: &Path) -> Option<FolderAtlasLayout> {
+    let content = std::fs::read_to_string(resolve(settings, path)).ok()?;
+    ron::from_str(&content).ok()
+}
+
+/// Writes the layout to the cache at `path`, relative to the asset folder.
+#[cfg(not(any(target_arch = "wasm32", target_os = "android")))]
+pub(crate) fn save(settings: &AssetServerSettings, path: &Path, layout: &FolderAtlasLayout) {
+    let result = ron::ser::to_string_pretty(layout, Default::default())
+        .map_err(|err| err.to_string())
+        .and_then(|content| {
+            std::fs::write(resolve(settings, path), content).map_err(|err| err.to_string())
+        });
+    if let Err(error) = result {
+        warn!(?path, %error, "Unable to write folder atlas cache.");
+    }
+}
+
+#[cfg(not(any(target_arch = "wasm32", target_os = "android")))]
+fn resolve(settings: &AssetServerSettings, path: &Path) -> PathBuf {
+    bevy::asset::FileAssetIo::get_base_path()
+        .join(&settings.asset_folder)
+        .join(path)
+}
+
+/// Caching is not supported without direct file system access.
+#[cfg(any(target_arch = "wasm32", target_os = "android"))]
+pub(crate) fn load(_settings: &AssetServerSettings, _path: &Path) -> Option<FolderAtlasLayout> {
+    None
+}
+
+/// Caching is not supported without direct file system access.
+#[cfg(any(target_arch = "wasm32", target_os = "android"))]
+pub(crate) fn save(_settings: &AssetServerSettings, _path: &Path, _layout: &FolderAtlasLayout) {}
@@ -0,0 +1,118 @@
+//! Manifest listing the images of a folder atlas, for platforms where folders can't be loaded
+//! directly, such as web.
+
+use bevy::{
+    asset::{AssetLoader, LoadContext, LoadedAsset},
+    reflect::TypeUuid,
+    utils::BoxedFuture,
+};
+use serde::Deserialize;
+use std::path::{Path, PathBuf};
+
+/// List of images making up a folder atlas, loaded as an asset from files ending in
+/// `.manifest.ron` or `.manifest.json`.
+///
+/// The manifest is a plain list of image paths, relative to the manifest itself:
+/// ```ron
+/// [
+///     "pacman/open.png",
+///     "pacman/closed.png",
+/// ]
+/// ```
+///
+/// As the manifest mirrors the content of a folder, it's usually generated when building, e.g.
+/// from a `build.rs` script:
+/// ```no_run
+/// # use std::{fs, path::Path};
+/// let folder = Path::new("assets/pacman");
+/// let mut paths = fs::read_dir(folder)
+///     .unwrap()
+///     .map(|entry| entry.unwrap().path())
+///     .filter(|path| path.extension().map_or(false, |ext| ext == "png"))
+///     .map(|path| format!("{:?}", path.strip_prefix("assets").unwrap()))
+///     .collect::<Vec<_>>();
+/// paths.sort();
+/// fs::write("assets/pacman.manifest.ron", format!("[{}]", paths.join(", "))).unwrap();
+/// ```
+#[derive(Debug, TypeUuid)]
+#[uuid = "9d3c6b1e-7a4f-4f0e-8d2b-3e6f1c5a2b7d"]
+pub struct FolderManifest {
+    /// Paths of the images, relative to the asset folder.
+    pub paths: Vec<PathBuf>,
+}
+
+impl FolderManifest {
+    /// Parses a manifest located at `path`, resolving the images relative to it.
+    ///
+    /// The manifest is read as JSON when `path` has the `json` extension, otherwise as RON.
+    pub fn from_bytes(bytes: &[u8], path: &Path) -> anyhow::Result<Self> {
+        let paths: Vec<PathBuf> = match path.extension() {
+            Some(extension) if extension == "json" => serde_json::from_slice(bytes)?,
+            _ => ron::de::from_bytes(bytes)?,
+        };
+        let parent = path.parent().unwrap_or_else(|| Path::new(""));
+        Ok(Self {
+            paths: paths.into_iter().map(|image| parent.join(image)).collect(),
+        })
+    }
+}
+
+/// [AssetLoader] for [FolderManifest].
+#[derive(Debug, Default)]
+pub struct FolderManifestLoader;
+
+impl AssetLoader for FolderManifestLoader {
+    fn load<'a>(
+        &'a self,
+        bytes: &'a [u8],
+        load_context: &'a mut LoadContext,
+    ) -> BoxedFuture<'a, anyhow::Result<()>> {
+        Box::pin(async move {
+            let manifest = FolderManifest::from_bytes(bytes, load_context.path())?;
+            load_context.set_default_asset(LoadedAsset::new(manifest));
+            Ok(())
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["manifest.ron", "manifest.json"]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    type Result = anyhow::Result<()>;
+
+    #[test]
+    fn ron_format() -> Result {
+        let manifest = FolderManifest::from_bytes(
+            br#"["open.png", "closed.png"]"#,
+            Path::new("pacman/pacman.manifest.ron"),
+        )?;
+
+        assert_eq!(
+            manifest.paths,
+            vec![
+                PathBuf::from("pacman/open.png"),
+                PathBuf::from("pacman/closed.png")
+            ]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn json_format() -> Result {
+        let manifest = FolderManifest::from_bytes(
+            br#"["open.png", "closed.png"]"#,
+            Path::new("pacman.manifest.json"),
+        )?;
+
+        assert_eq!(
+            manifest.paths,
+            vec![PathBuf::from("open.png"), PathBuf::from("closed.png")]
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,112 @@
+//! Expansion of glob patterns against the asset folder, for atlases of only some of the images
+//! of a folder.
+
+use bevy::asset::AssetServerSettings;
+use std::path::{Path, PathBuf};
+
+use crate::ResourceError;
+
+/// Paths of the files matching `pattern`, relative to the asset folder and sorted by name.
+///
+/// Only the file name of the pattern may hold wildcards, see [matches].
+#[cfg(not(any(target_arch = "wasm32", target_os = "android")))]
+pub(crate) fn expand(
+    settings: &AssetServerSettings,
+    pattern: &Path,
+) -> Result<Vec<PathBuf>, ResourceError> {
+    let folder = pattern.parent().unwrap_or_else(|| Path::new(""));
+    let name_pattern = pattern
+        .file_name()
+        .and_then(|name| name.to_str())
+        .unwrap_or_default();
+    let entries = bevy::asset::FileAssetIo::get_base_path()
+        .join(&settings.asset_folder)
+        .join(folder)
+        .read_dir()
+        .map_err(|_| ResourceError::LoadFailed(folder.to_path_buf()))?;
+    let mut names = entries
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| {
+            entry
+                .file_type()
+                .map_or(false, |file_type| file_type.is_file())
+        })
+        .filter_map(|entry| entry.file_name().into_string().ok())
+        .filter(|name| matches(name_pattern, name))
+        .collect::<Vec<_>>();
+    names.sort();
+    Ok(names.into_iter().map(|name| folder.join(name)).collect())
+}
+
+/// Globs are not supported without direct file system access.
+#[cfg(any(target_arch = "wasm32", target_os = "android"))]
+pub(crate) fn expand(
+    _settings: &AssetServerSettings,
+    _pattern: &Path,
+) -> Result<Vec<PathBuf>, ResourceError> {
+    Err(ResourceError::AtlasBuildFailed(String::from(
+        "glob patterns need direct file system access",
+    )))
+}
+
+/// Whether `name` matches `pattern`, where `*` matches any run of characters and `?` any single
+/// character.
+pub(crate) fn matches(pattern: &str, name: &str) -> bool {
+    let pattern = pattern.chars().collect::<Vec<_>>();
+    let name = name.chars().collect::<Vec<_>>();
+    let (mut p, mut n) = (0, 0);
+    // position after the last `*`, along with the position in `name` it started matching at
+    let mut star = None;
+    while n < name.len() {
+        match pattern.get(p) {
+            Some('*') => {
+                star = Some((p + 1, n));
+                p += 1;
+            }
+            Some(&c) if c == '?' || c == name[n] => {
+                p += 1;
+                n += 1;
+            }
+            _ => match star {
+                // let the `*` match one more character, and retry
+                Some((star_p, star_n)) => {
+                    star = Some((star_p, star_n + 1));
+                    p = star_p;
+                    n = star_n + 1;
+                }
+                None => return false,
+            },
+        }
+    }
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn literal_names_match_themselves() {
+        assert!(matches("walk.png", "walk.png"));
+        assert!(!matches("walk.png", "walk.png.bak"));
+        assert!(!matches("walk.png", "run.png"));
+    }
+
+    #[test]
+    fn star_matches_any_run() {
+        assert!(matches("walk_*.png", "walk_0.png"));
+        assert!(matches("walk_*.png", "walk_.png"));
+        assert!(matches("walk_*.png", "walk_left_10.png"));
+        assert!(!matches("walk_*.png", "run_0.png"));
+        assert!(matches("*", "anything"));
+        assert!(matches("*_*_*.png", "a_b_c.png"));
+        assert!(!matches("*_*_*.png", "a_b.png"));
+    }
+
+    #[test]
+    fn question_mark_matches_a_single_character() {
+        assert!(matches("walk_?.png", "walk_0.png"));
+        assert!(!matches("walk_?.png", "walk_10.png"));
+        assert!(!matches("walk_?.png", "walk_.png"));
+    }
+}
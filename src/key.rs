@@ -0,0 +1,170 @@
+use derive_more::{Display, From};
+use std::borrow::Cow;
+#[cfg(feature = "strum")]
+use std::str::FromStr;
+#[cfg(feature = "strum")]
+use strum::VariantNames;
+
+/// Trait for the key `T` indexing the atlases of [AtlasTextures<T>](crate::AtlasTextures<T>),
+/// mapping it to and from the keys of the definitions.
+///
+/// With the `strum` feature (enabled by default), implemented for any enumeration deriving
+/// `strum::EnumVariantNames` and `strum::EnumString`. For other keys, e.g. a newtype around a
+/// `String`, or when building without default features, implement the trait manually.
+///
+/// # Example:
+/// ```
+/// # use bevy_atlas_loader::*;
+/// # use std::borrow::Cow;
+/// #[derive(Debug, PartialEq, Eq, Hash, Clone)]
+/// struct AtlasName(String);
+///
+/// impl AtlasKey for AtlasName {
+///     fn variants() -> &'static [&'static str] {
+///         // no atlas is required to be defined
+///         &[]
+///     }
+///
+///     fn from_key(key: &str) -> Option<Self> {
+///         Some(Self(key.to_string()))
+///     }
+///
+///     fn to_key(&self) -> Cow<'_, str> {
+///         Cow::Borrowed(&self.0)
+///     }
+/// }
+/// ```
+pub trait AtlasKey: Sized {
+    /// Keys required to be present in the definitions.
+    fn variants() -> &'static [&'static str];
+
+    /// Converts a definition key into `Self`, returning `None` if the key is invalid.
+    fn from_key(key: &str) -> Option<Self>;
+
+    /// Converts `self` into the corresponding definition key.
+    fn to_key(&self) -> Cow<'_, str>;
+}
+
+#[cfg(feature = "strum")]
+impl<T> AtlasKey for T
+where
+    T: VariantNames + FromStr + Eq,
+{
+    fn variants() -> &'static [&'static str] {
+        T::VARIANTS
+    }
+
+    fn from_key(key: &str) -> Option<Self> {
+        T::from_str(key).ok()
+    }
+
+    fn to_key(&self) -> Cow<'_, str> {
+        T::VARIANTS
+            .iter()
+            .find(|&&variant| T::from_str(variant).map_or(false, |key| key == *self))
+            .map(|&variant| Cow::Borrowed(variant))
+            .expect("every value of T should be one of its variants")
+    }
+}
+
+/// String key of atlas' only known at runtime, e.g. when loading mods.
+///
+/// No atlas is required to be defined, and every definition present is created - so inserting
+/// a new [TypedAtlasDefinition](crate::TypedAtlasDefinition) with more definitions recreates
+/// the [DynamicAtlasTextures] with those as well.
+///
+/// # Example:
+/// ```no_run
+/// # use bevy::prelude::*;
+/// # use bevy_atlas_loader::*;
+/// fn use_atlas(atlases: Res<DynamicAtlasTextures>) {
+///     let _ = atlases.handle(DynamicAtlasKey::from("Pacman"));
+///     let _ = atlases.handle_by_name("Pacman");
+/// }
+///
+/// App::new().add_plugin(AtlasTexturePlugin::<DynamicAtlasKey>::default());
+/// ```
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Display, From)]
+pub struct DynamicAtlasKey(pub String);
+
+impl From<&str> for DynamicAtlasKey {
+    fn from(key: &str) -> Self {
+        Self(key.to_string())
+    }
+}
+
+impl AtlasKey for DynamicAtlasKey {
+    fn variants() -> &'static [&'static str] {
+        &[]
+    }
+
+    fn from_key(key: &str) -> Option<Self> {
+        Some(Self::from(key))
+    }
+
+    fn to_key(&self) -> Cow<'_, str> {
+        Cow::Borrowed(&self.0)
+    }
+}
+
+/// [AtlasTextures](crate::AtlasTextures) keyed by [DynamicAtlasKey], created by
+/// `AtlasTexturePlugin::<DynamicAtlasKey>`.
+pub type DynamicAtlasTextures = crate::AtlasTextures<DynamicAtlasKey>;
+
+/// Defines an enumeration usable as the key `T` of `AtlasTexturePlugin::<T>`, deriving the
+/// traits required by the plugin and implementing [AtlasKey] with the variant names as keys.
+///
+/// Doesn't depend on `strum`, and so is available without default features as well.
+///
+/// # Example:
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy_atlas_loader::*;
+/// define_atlas_textures! {
+///     /// The atlases of the game.
+///     pub enum MyAtlasTextures {
+///         Pacman,
+///         Ghost,
+///     }
+/// }
+///
+/// assert_eq!(MyAtlasTextures::variants(), &["Pacman", "Ghost"]);
+/// assert_eq!(MyAtlasTextures::from_key("Ghost"), Some(MyAtlasTextures::Ghost));
+/// assert_eq!(MyAtlasTextures::Pacman.to_key(), "Pacman");
+///
+/// App::new().add_plugin(AtlasTexturePlugin::<MyAtlasTextures>::default());
+/// ```
+#[macro_export]
+macro_rules! define_atlas_textures {
+    (
+        $(#[$meta:meta])*
+        $vis:vis enum $name:ident {
+            $($(#[$variant_meta:meta])* $variant:ident),* $(,)?
+        }
+    ) => {
+        $(#[$meta])*
+        #[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
+        $vis enum $name {
+            $($(#[$variant_meta])* $variant),*
+        }
+
+        impl $crate::AtlasKey for $name {
+            fn variants() -> &'static [&'static str] {
+                &[$(stringify!($variant)),*]
+            }
+
+            fn from_key(key: &str) -> Option<Self> {
+                match key {
+                    $(stringify!($variant) => Some(Self::$variant),)*
+                    _ => None,
+                }
+            }
+
+            fn to_key(&self) -> ::std::borrow::Cow<'_, str> {
+                ::std::borrow::Cow::Borrowed(match *self {
+                    $(Self::$variant => stringify!($variant),)*
+                })
+            }
+        }
+    };
+}
@@ -0,0 +1,847 @@
+//! Computation of the regions of the atlases, independent of any loaded assets.
+
+use bevy::{prelude::*, sprite::Rect};
+
+use crate::{
+    AtlasFrame, AtlasRegion, DuplicatePositions, FractionalGridAtlasDefinition,
+    FramesAtlasDefinition, GridAtlasDefinition, GridByBoundsAtlasDefinition, GridOrder,
+    PatchAtlasDefinition, ResourceError,
+};
+
+/// Computes the regions of a grid, in its order, inside an image of size `image_size`.
+///
+/// Any extra regions follow the tiles of the grid.
+pub(crate) fn grid_rects(
+    grid_definition: &GridAtlasDefinition,
+    image_size: UVec2,
+) -> Result<Vec<Rect>, ResourceError> {
+    stacked_grid_rects(grid_definition, &[image_size])
+}
+
+/// Computes the regions of a grid repeated on several sheets of size `sheet_sizes`, stacked on
+/// top of each other. The tiles of each sheet follow those of the previous one.
+///
+/// Any extra regions, placed relative to the first sheet, follow the tiles of all sheets.
+pub(crate) fn stacked_grid_rects(
+    grid_definition: &GridAtlasDefinition,
+    sheet_sizes: &[UVec2],
+) -> Result<Vec<Rect>, ResourceError> {
+    let tile_size = grid_definition.tile_size;
+    let padding = grid_definition.padding.unwrap_or_default();
+    let tiles = grid_tiles(
+        grid_definition,
+        sheet_sizes.first().copied().unwrap_or_default(),
+    )?;
+    let expected = grid_size(tiles, tile_size, padding);
+    if let Some(&actual) = sheet_sizes
+        .iter()
+        .find(|sheet_size| expected.cmpgt(**sheet_size).any())
+    {
+        return Err(ResourceError::GridExceedsImage { expected, actual });
+    }
+
+    let step = tile_size + padding;
+    let inner_margin = grid_definition.inner_margin.unwrap_or_default().as_vec2();
+    let order = grid_definition.order;
+    let sheet_offsets = sheet_sizes.iter().scan(0, |offset, sheet_size| {
+        let sheet_offset = *offset;
+        *offset += sheet_size.y;
+        Some(UVec2::new(0, sheet_offset))
+    });
+    let extra_regions = grid_definition.extra_regions.iter().map(|region| Rect {
+        min: region.position.as_vec2(),
+        max: (region.position + region.size).as_vec2(),
+    });
+    Ok(sheet_offsets
+        .flat_map(|sheet_offset| {
+            grid_positions(tiles, order).map(move |position| sheet_offset + position * step)
+        })
+        .map(|min| Rect {
+            min: min.as_vec2() + inner_margin,
+            max: (min + tile_size).as_vec2() - inner_margin,
+        })
+        .chain(extra_regions)
+        .collect())
+}
+
+/// Column and row of every tile of a grid of `tiles` columns and rows, in `order`.
+fn grid_positions(tiles: UVec2, order: GridOrder) -> impl Iterator<Item = UVec2> {
+    let (outer, inner) = match order {
+        GridOrder::RowMajor => (tiles.y, tiles.x),
+        GridOrder::ColumnMajor => (tiles.x, tiles.y),
+    };
+    (0..outer).flat_map(move |outer| {
+        (0..inner).map(move |inner| match order {
+            GridOrder::RowMajor => UVec2::new(inner, outer),
+            GridOrder::ColumnMajor => UVec2::new(outer, inner),
+        })
+    })
+}
+
+/// Number of columns and rows of a grid inside an image of size `image_size`, computing any
+/// left as `0` from the size of the image.
+///
+/// Left as `0` along with the tile size, there are no tiles - as for grids of only extra regions,
+/// e.g. written by the `pack_folder` example.
+///
+/// Fails unless the computed dimensions fit a whole number of tiles, including the padding
+/// between them.
+fn grid_tiles(
+    grid_definition: &GridAtlasDefinition,
+    image_size: UVec2,
+) -> Result<UVec2, ResourceError> {
+    let tile_size = grid_definition.tile_size;
+    let padding = grid_definition.padding.unwrap_or_default();
+    let tiles = |count: usize, image: u32, tile: u32, padding: u32| {
+        if count != 0 || tile == 0 {
+            return Some(count as u32);
+        }
+        let step = tile + padding;
+        (step > 0 && (image + padding) % step == 0).then(|| (image + padding) / step)
+    };
+    match (
+        tiles(
+            grid_definition.columns,
+            image_size.x,
+            tile_size.x,
+            padding.x,
+        ),
+        tiles(grid_definition.rows, image_size.y, tile_size.y, padding.y),
+    ) {
+        (Some(columns), Some(rows)) => Ok(UVec2::new(columns, rows)),
+        _ => Err(ResourceError::UnevenGrid {
+            image_size,
+            tile_size,
+        }),
+    }
+}
+
+/// Total size of `tiles` tiles of `tile_size` pixels, including `padding` between them.
+pub(crate) fn grid_size(tiles: UVec2, tile_size: UVec2, padding: UVec2) -> UVec2 {
+    let gaps = tiles.max(UVec2::ONE) - UVec2::ONE;
+    tiles * tile_size + gaps * padding
+}
+
+/// Size of an image of `image_size` pixels in the units of the definitions, when the images are
+/// `atlas_scale` times the size the definitions were made for.
+pub(crate) fn unscaled_size(image_size: UVec2, atlas_scale: f32) -> UVec2 {
+    (image_size.as_vec2() / atlas_scale).round().as_uvec2()
+}
+
+/// Scales regions given in the units of the definitions by `atlas_scale`, rounding them to whole
+/// pixels.
+pub(crate) fn scale_rects(rects: Vec<Rect>, atlas_scale: f32) -> Vec<Rect> {
+    if atlas_scale == 1. {
+        return rects;
+    }
+    rects
+        .into_iter()
+        .map(|rect| Rect {
+            min: (rect.min * atlas_scale).round(),
+            max: (rect.max * atlas_scale).round(),
+        })
+        .collect()
+}
+
+/// Computes the regions of a grid given by its bounds, row by row, inside an image of size
+/// `image_size`.
+///
+/// Fails unless the bounds fit a whole number of tiles, including the padding between them.
+pub(crate) fn grid_by_bounds_rects(
+    bounds_definition: &GridByBoundsAtlasDefinition,
+    image_size: UVec2,
+) -> Result<Vec<Rect>, ResourceError> {
+    let GridByBoundsAtlasDefinition {
+        first,
+        last,
+        tile_size,
+        padding,
+        ..
+    } = *bounds_definition;
+    let invalid_bounds = ResourceError::InvalidGridBounds {
+        first,
+        last,
+        tile_size,
+    };
+    if last.cmple(first).any() || tile_size.cmpeq(UVec2::ZERO).any() {
+        return Err(invalid_bounds);
+    }
+    if last.cmpgt(image_size).any() {
+        return Err(ResourceError::GridExceedsImage {
+            expected: last,
+            actual: image_size,
+        });
+    }
+
+    // the padding only lies between tiles, so the bounds span one gap less than tiles
+    let step = tile_size + padding.unwrap_or_default();
+    let span = last - first + padding.unwrap_or_default();
+    if (span % step).cmpne(UVec2::ZERO).any() {
+        return Err(invalid_bounds);
+    }
+    let tiles = span / step;
+    let grid = GridAtlasDefinition {
+        columns: tiles.x as usize,
+        rows: tiles.y as usize,
+        tile_size,
+        padding,
+        ..Default::default()
+    };
+    let offset = first.as_vec2();
+    Ok(grid_rects(&grid, image_size - first)?
+        .into_iter()
+        .map(|rect| Rect {
+            min: rect.min + offset,
+            max: rect.max + offset,
+        })
+        .collect())
+}
+
+/// Computes the regions of a grid of fractionally sized tiles, row by row, inside an image of size
+/// `image_size`.
+///
+/// Each tile is placed relative to the first, so rounding doesn't accumulate along the rows.
+pub(crate) fn fractional_grid_rects(
+    grid_definition: &FractionalGridAtlasDefinition,
+    image_size: Vec2,
+) -> Result<Vec<Rect>, ResourceError> {
+    // leeway for the rounding of tiles adding up to the size of the image
+    const TOLERANCE: f32 = 1e-3;
+
+    let FractionalGridAtlasDefinition {
+        columns,
+        rows,
+        tile_size,
+        offset,
+        padding,
+        ..
+    } = *grid_definition;
+    if columns == 0 || rows == 0 || !tile_size.cmpgt(Vec2::ZERO).all() {
+        return Err(ResourceError::AtlasBuildFailed(String::from(
+            "fractional grid without tiles",
+        )));
+    }
+    let tiles = UVec2::new(columns as u32, rows as u32);
+    let padding = padding.unwrap_or_default();
+    let gaps = (tiles - UVec2::ONE).as_vec2();
+    let expected = offset + tiles.as_vec2() * tile_size + gaps * padding;
+    if expected.cmpgt(image_size + TOLERANCE).any() {
+        return Err(ResourceError::GridExceedsImage {
+            expected: expected.ceil().as_uvec2(),
+            actual: image_size.as_uvec2(),
+        });
+    }
+
+    let step = tile_size + padding;
+    Ok(grid_positions(tiles, GridOrder::RowMajor)
+        .map(|position| {
+            let min = offset + position.as_vec2() * step;
+            Rect {
+                min,
+                max: min + tile_size,
+            }
+        })
+        .collect())
+}
+
+/// Computes the manually placed regions of a patch definition.
+pub(crate) fn patch_rects(
+    patch_definition: &PatchAtlasDefinition,
+    image_size: UVec2,
+) -> Result<Vec<Rect>, ResourceError> {
+    let size = UVec2::new(patch_definition.width, patch_definition.height);
+    let mut positions: Vec<UVec2> = Vec::with_capacity(patch_definition.positions.len());
+    for &position in patch_definition.positions.iter() {
+        if positions.contains(&position) {
+            match patch_definition.duplicates {
+                DuplicatePositions::Keep => {
+                    warn!(%position, "Region listed more than once.");
+                }
+                DuplicatePositions::Remove => {
+                    warn!(%position, "Region listed more than once - it will be removed.");
+                    continue;
+                }
+                DuplicatePositions::Fail => {
+                    return Err(ResourceError::DuplicateRegion(position));
+                }
+            }
+        }
+        if (position + size).cmpgt(image_size).any() {
+            return Err(ResourceError::RegionOutOfBounds {
+                position,
+                image_size,
+            });
+        }
+        positions.push(position);
+    }
+    Ok(positions
+        .into_iter()
+        .map(|position| Rect {
+            min: position.as_vec2(),
+            max: (position + size).as_vec2(),
+        })
+        .collect())
+}
+
+/// Computes the regions of a frames definition, in the order of its frames.
+///
+/// Fails if a frame extends past the image, rather than creating regions outside the texture.
+pub(crate) fn frames_rects(
+    frames_definition: &FramesAtlasDefinition,
+    image_size: UVec2,
+) -> Result<Vec<Rect>, ResourceError> {
+    frames_definition
+        .frames
+        .iter()
+        .map(|&AtlasFrame { x, y, w, h, .. }| {
+            let position = UVec2::new(x, y);
+            match (x.checked_add(w), y.checked_add(h)) {
+                (Some(right), Some(bottom)) if right <= image_size.x && bottom <= image_size.y => {
+                    Ok(Rect {
+                        min: position.as_vec2(),
+                        max: Vec2::new(right as f32, bottom as f32),
+                    })
+                }
+                _ => Err(ResourceError::RegionOutOfBounds {
+                    position,
+                    image_size,
+                }),
+            }
+        })
+        .collect()
+}
+
+/// Computes the regions of `layers` images of size `layer_size`, stacked on top of each other.
+pub(crate) fn array_rects(layer_size: UVec2, layers: usize) -> Vec<Rect> {
+    (0..layers as u32)
+        .map(|layer| {
+            let min = UVec2::new(0, layer * layer_size.y);
+            Rect {
+                min: min.as_vec2(),
+                max: (min + layer_size).as_vec2(),
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rect(min: (f32, f32), max: (f32, f32)) -> Rect {
+        Rect {
+            min: Vec2::new(min.0, min.1),
+            max: Vec2::new(max.0, max.1),
+        }
+    }
+
+    mod atlas_scale {
+        use super::*;
+
+        #[test]
+        fn scales_regions_to_whole_pixels() {
+            let rects = vec![rect((1., 2.), (3., 5.))];
+
+            assert_eq!(scale_rects(rects, 1.5), vec![rect((2., 3.), (5., 8.))]);
+        }
+
+        #[test]
+        fn unscales_image_size() {
+            assert_eq!(
+                unscaled_size(UVec2::new(760, 462), 2.),
+                UVec2::new(380, 231)
+            );
+        }
+    }
+
+    mod grid {
+        use super::*;
+
+        #[test]
+        fn is_laid_out_row_by_row() {
+            let grid = GridAtlasDefinition {
+                columns: 2,
+                rows: 2,
+                tile_size: UVec2::new(10, 20),
+                ..Default::default()
+            };
+
+            let rects = grid_rects(&grid, UVec2::new(20, 40)).unwrap();
+
+            assert_eq!(
+                rects,
+                vec![
+                    rect((0., 0.), (10., 20.)),
+                    rect((10., 0.), (20., 20.)),
+                    rect((0., 20.), (10., 40.)),
+                    rect((10., 20.), (20., 40.)),
+                ]
+            );
+        }
+
+        #[test]
+        fn computes_auto_dimensions_from_image() {
+            let grid = GridAtlasDefinition {
+                columns: 0,
+                rows: 0,
+                tile_size: UVec2::new(10, 10),
+                padding: Some(UVec2::new(2, 2)),
+                ..Default::default()
+            };
+
+            let rects = grid_rects(&grid, UVec2::new(34, 22)).unwrap();
+
+            assert_eq!(rects.len(), 3 * 2);
+            assert_eq!(rects[5], rect((24., 12.), (34., 22.)));
+        }
+
+        #[test]
+        fn fails_auto_dimensions_not_dividing_image() {
+            let grid = GridAtlasDefinition {
+                columns: 2,
+                rows: 0,
+                tile_size: UVec2::new(10, 10),
+                ..Default::default()
+            };
+
+            assert_eq!(
+                grid_rects(&grid, UVec2::new(20, 25)),
+                Err(ResourceError::UnevenGrid {
+                    image_size: UVec2::new(20, 25),
+                    tile_size: UVec2::new(10, 10),
+                })
+            );
+        }
+
+        #[test]
+        fn can_be_laid_out_column_by_column() {
+            let grid = GridAtlasDefinition {
+                columns: 2,
+                rows: 2,
+                tile_size: UVec2::new(10, 20),
+                order: GridOrder::ColumnMajor,
+                ..Default::default()
+            };
+
+            let rects = grid_rects(&grid, UVec2::new(20, 40)).unwrap();
+
+            assert_eq!(
+                rects,
+                vec![
+                    rect((0., 0.), (10., 20.)),
+                    rect((0., 20.), (10., 40.)),
+                    rect((10., 0.), (20., 20.)),
+                    rect((10., 20.), (20., 40.)),
+                ]
+            );
+        }
+
+        #[test]
+        fn skips_padding_between_tiles() {
+            let grid = GridAtlasDefinition {
+                columns: 2,
+                rows: 1,
+                tile_size: UVec2::new(10, 10),
+                padding: Some(UVec2::new(2, 2)),
+                ..Default::default()
+            };
+
+            let rects = grid_rects(&grid, UVec2::new(22, 10)).unwrap();
+
+            assert_eq!(
+                rects,
+                vec![rect((0., 0.), (10., 10.)), rect((12., 0.), (22., 10.))]
+            );
+        }
+
+        #[test]
+        fn insets_tiles_by_inner_margin() {
+            let grid = GridAtlasDefinition {
+                columns: 1,
+                rows: 1,
+                tile_size: UVec2::new(10, 10),
+                inner_margin: Some(UVec2::new(1, 2)),
+                ..Default::default()
+            };
+
+            let rects = grid_rects(&grid, UVec2::new(10, 10)).unwrap();
+
+            assert_eq!(rects, vec![rect((1., 2.), (9., 8.))]);
+        }
+
+        #[test]
+        fn appends_extra_regions() {
+            let grid = GridAtlasDefinition {
+                columns: 2,
+                rows: 1,
+                tile_size: UVec2::new(10, 10),
+                extra_regions: vec![AtlasRegion::new(UVec2::new(0, 10), UVec2::new(20, 5))],
+                ..Default::default()
+            };
+
+            let rects = grid_rects(&grid, UVec2::new(20, 15)).unwrap();
+
+            assert_eq!(
+                rects,
+                vec![
+                    rect((0., 0.), (10., 10.)),
+                    rect((10., 0.), (20., 10.)),
+                    rect((0., 10.), (20., 15.)),
+                ]
+            );
+        }
+
+        #[test]
+        fn without_tiles_has_only_extra_regions() {
+            // as written by the `pack_folder` example
+            let grid: GridAtlasDefinition = ron::from_str(
+                r#"(
+                    texture: "packed.png",
+                    columns: 0,
+                    rows: 0,
+                    tile_size: (0, 0),
+                    extra_regions: [
+                        (position: (0, 0), size: (16, 16)),
+                        (position: (16, 0), size: (8, 24)),
+                    ],
+                )"#,
+            )
+            .unwrap();
+
+            let rects = grid_rects(&grid, UVec2::new(24, 24)).unwrap();
+
+            assert_eq!(
+                rects,
+                vec![rect((0., 0.), (16., 16.)), rect((16., 0.), (24., 24.))]
+            );
+        }
+
+        #[test]
+        fn exceeding_image_fails() {
+            let grid = GridAtlasDefinition {
+                columns: 3,
+                rows: 1,
+                tile_size: UVec2::new(10, 10),
+                ..Default::default()
+            };
+
+            let result = grid_rects(&grid, UVec2::new(29, 10));
+
+            assert_eq!(
+                result,
+                Err(ResourceError::GridExceedsImage {
+                    expected: UVec2::new(30, 10),
+                    actual: UVec2::new(29, 10),
+                })
+            );
+        }
+    }
+
+    mod stacked_grid {
+        use super::*;
+
+        #[test]
+        fn continues_indexing_on_following_sheets() {
+            let grid = GridAtlasDefinition {
+                columns: 2,
+                rows: 1,
+                tile_size: UVec2::new(10, 10),
+                extra_regions: vec![AtlasRegion::new(UVec2::new(0, 10), UVec2::new(20, 5))],
+                ..Default::default()
+            };
+
+            let rects =
+                stacked_grid_rects(&grid, &[UVec2::new(20, 15), UVec2::new(20, 10)]).unwrap();
+
+            assert_eq!(
+                rects,
+                vec![
+                    rect((0., 0.), (10., 10.)),
+                    rect((10., 0.), (20., 10.)),
+                    rect((0., 15.), (10., 25.)),
+                    rect((10., 15.), (20., 25.)),
+                    rect((0., 10.), (20., 15.)),
+                ]
+            );
+        }
+
+        #[test]
+        fn any_sheet_exceeding_grid_fails() {
+            let grid = GridAtlasDefinition {
+                columns: 2,
+                rows: 1,
+                tile_size: UVec2::new(10, 10),
+                ..Default::default()
+            };
+
+            let result = stacked_grid_rects(&grid, &[UVec2::new(20, 10), UVec2::new(10, 10)]);
+
+            assert_eq!(
+                result,
+                Err(ResourceError::GridExceedsImage {
+                    expected: UVec2::new(20, 10),
+                    actual: UVec2::new(10, 10),
+                })
+            );
+        }
+    }
+
+    mod grid_by_bounds {
+        use super::*;
+
+        #[test]
+        fn infers_tiles_from_bounds() {
+            let bounds = GridByBoundsAtlasDefinition::bounds(
+                "image.png",
+                UVec2::new(5, 5),
+                UVec2::new(27, 15),
+                UVec2::new(10, 10),
+            )
+            .with_padding(UVec2::new(2, 2));
+
+            let rects = grid_by_bounds_rects(&bounds, UVec2::new(30, 20)).unwrap();
+
+            assert_eq!(
+                rects,
+                vec![rect((5., 5.), (15., 15.)), rect((17., 5.), (27., 15.))]
+            );
+        }
+
+        #[test]
+        fn partial_tiles_fail() {
+            let bounds = GridByBoundsAtlasDefinition::bounds(
+                "image.png",
+                UVec2::new(0, 0),
+                UVec2::new(25, 10),
+                UVec2::new(10, 10),
+            );
+
+            assert_eq!(
+                grid_by_bounds_rects(&bounds, UVec2::new(30, 20)),
+                Err(ResourceError::InvalidGridBounds {
+                    first: UVec2::new(0, 0),
+                    last: UVec2::new(25, 10),
+                    tile_size: UVec2::new(10, 10),
+                })
+            );
+        }
+
+        #[test]
+        fn exceeding_image_fails() {
+            let bounds = GridByBoundsAtlasDefinition::bounds(
+                "image.png",
+                UVec2::new(0, 0),
+                UVec2::new(20, 30),
+                UVec2::new(10, 10),
+            );
+
+            assert_eq!(
+                grid_by_bounds_rects(&bounds, UVec2::new(30, 20)),
+                Err(ResourceError::GridExceedsImage {
+                    expected: UVec2::new(20, 30),
+                    actual: UVec2::new(30, 20),
+                })
+            );
+        }
+    }
+
+    mod fractional_grid {
+        use super::*;
+
+        #[test]
+        fn places_tiles_between_pixels() {
+            let grid =
+                FractionalGridAtlasDefinition::fractional_grid("image.png", 2, 2, Vec2::splat(2.5))
+                    .with_offset(Vec2::new(1., 0.));
+
+            let rects = fractional_grid_rects(&grid, Vec2::new(6., 5.)).unwrap();
+
+            assert_eq!(
+                rects,
+                vec![
+                    rect((1., 0.), (3.5, 2.5)),
+                    rect((3.5, 0.), (6., 2.5)),
+                    rect((1., 2.5), (3.5, 5.)),
+                    rect((3.5, 2.5), (6., 5.)),
+                ]
+            );
+        }
+
+        #[test]
+        fn long_rows_dont_drift() {
+            let grid = FractionalGridAtlasDefinition::fractional_grid(
+                "image.png",
+                1000,
+                1,
+                Vec2::splat(0.1),
+            );
+
+            let rects = fractional_grid_rects(&grid, Vec2::new(100., 0.1)).unwrap();
+
+            assert!((rects[999].min.x - 99.9).abs() < 1e-3);
+        }
+
+        #[test]
+        fn exceeding_image_fails() {
+            let grid =
+                FractionalGridAtlasDefinition::fractional_grid("image.png", 4, 1, Vec2::splat(2.5));
+
+            assert_eq!(
+                fractional_grid_rects(&grid, Vec2::new(8., 3.)),
+                Err(ResourceError::GridExceedsImage {
+                    expected: UVec2::new(10, 3),
+                    actual: UVec2::new(8, 3),
+                })
+            );
+        }
+    }
+
+    mod patch {
+        use super::*;
+
+        #[test]
+        fn places_regions_at_positions() {
+            let patch = PatchAtlasDefinition {
+                width: 5,
+                height: 10,
+                positions: vec![UVec2::new(0, 0), UVec2::new(20, 30)],
+                ..Default::default()
+            };
+
+            assert_eq!(
+                patch_rects(&patch, UVec2::new(40, 40)),
+                Ok(vec![
+                    rect((0., 0.), (5., 10.)),
+                    rect((20., 30.), (25., 40.))
+                ])
+            );
+        }
+
+        #[test]
+        fn handles_duplicate_positions() {
+            let patch = PatchAtlasDefinition {
+                width: 5,
+                height: 10,
+                positions: vec![UVec2::new(0, 0), UVec2::new(20, 30), UVec2::new(0, 0)],
+                ..Default::default()
+            };
+
+            assert_eq!(
+                patch_rects(&patch, UVec2::new(40, 40)).map(|rects| rects.len()),
+                Ok(3)
+            );
+            assert_eq!(
+                patch_rects(
+                    &PatchAtlasDefinition {
+                        duplicates: DuplicatePositions::Remove,
+                        ..patch.clone()
+                    },
+                    UVec2::new(40, 40)
+                ),
+                Ok(vec![
+                    rect((0., 0.), (5., 10.)),
+                    rect((20., 30.), (25., 40.))
+                ])
+            );
+            assert_eq!(
+                patch_rects(
+                    &PatchAtlasDefinition {
+                        duplicates: DuplicatePositions::Fail,
+                        ..patch
+                    },
+                    UVec2::new(40, 40)
+                ),
+                Err(ResourceError::DuplicateRegion(UVec2::new(0, 0)))
+            );
+        }
+
+        #[test]
+        fn exceeding_image_fails() {
+            let patch = PatchAtlasDefinition {
+                width: 5,
+                height: 10,
+                positions: vec![UVec2::new(0, 0), UVec2::new(36, 30)],
+                ..Default::default()
+            };
+
+            assert_eq!(
+                patch_rects(&patch, UVec2::new(40, 40)),
+                Err(ResourceError::RegionOutOfBounds {
+                    position: UVec2::new(36, 30),
+                    image_size: UVec2::new(40, 40),
+                })
+            );
+        }
+    }
+
+    mod frames {
+        use super::*;
+
+        fn frames(frames: &[(u32, u32, u32, u32)]) -> FramesAtlasDefinition {
+            FramesAtlasDefinition {
+                frames: frames
+                    .iter()
+                    .map(|&(x, y, w, h)| AtlasFrame {
+                        x,
+                        y,
+                        w,
+                        h,
+                        name: None,
+                    })
+                    .collect(),
+                ..Default::default()
+            }
+        }
+
+        #[test]
+        fn places_regions_at_frames() {
+            assert_eq!(
+                frames_rects(
+                    &frames(&[(0, 0, 5, 10), (20, 30, 20, 10)]),
+                    UVec2::new(40, 40)
+                ),
+                Ok(vec![
+                    rect((0., 0.), (5., 10.)),
+                    rect((20., 30.), (40., 40.))
+                ])
+            );
+        }
+
+        #[test]
+        fn exceeding_image_fails() {
+            assert_eq!(
+                frames_rects(
+                    &frames(&[(0, 0, 5, 10), (36, 30, 5, 10)]),
+                    UVec2::new(40, 40)
+                ),
+                Err(ResourceError::RegionOutOfBounds {
+                    position: UVec2::new(36, 30),
+                    image_size: UVec2::new(40, 40),
+                })
+            );
+        }
+
+        #[test]
+        fn overflowing_frame_fails() {
+            assert_eq!(
+                frames_rects(&frames(&[(u32::MAX, 0, 1, 10)]), UVec2::new(40, 40)),
+                Err(ResourceError::RegionOutOfBounds {
+                    position: UVec2::new(u32::MAX, 0),
+                    image_size: UVec2::new(40, 40),
+                })
+            );
+        }
+    }
+
+    mod array {
+        use super::*;
+
+        #[test]
+        fn stacks_layers() {
+            assert_eq!(
+                array_rects(UVec2::new(5, 10), 2),
+                vec![rect((0., 0.), (5., 10.)), rect((0., 10.), (5., 20.))]
+            );
+        }
+    }
+}
@@ -2,7 +2,7 @@
 //! Plugin for defining and loading [TextureAtlas] assets.
 //!
 //! The definitions are added as resources (see [GenericAtlasDefinitions] or
-//! [TypedAtlasDefinition]), generic over some enumeration index `T`.
+//! [TypedAtlasDefinition]), generic over some enumeration index `T` (see [AtlasKey]).
 //!
 //! The plugin then loads the needed images as assets before creating the individual
 //! [TextureAtlas].
@@ -11,38 +11,336 @@
 //! [TextureAtlas] handles can be retrieved by the enumeration index `T`.
 //!
 //! The plugin also provides an event [AtlasTexturesEvent<T>] upon completion or failure.
+//!
+//! # Features
+//!
+//! - `strum` (default): implements [AtlasKey] for enumerations deriving
+//!   `strum::EnumVariantNames` and `strum::EnumString`. Without it, [AtlasKey] is implemented
+//!   manually, and everything else is available as is.
+//! - `ordered`: keeps the definitions in the order they're inserted or listed in a file, see
+//!   [AtlasDefinitionMap] - making the order they're processed in reproducible.
+//! - `test-support`: adds the [test_support] module, with a headless app for testing the atlas
+//!   integration.
+//! - `progress-tracking`: adds `atlas_textures_progress`, reporting the progress of creating the
+//!   atlases to loading states tracked by `iyes_progress` - e.g. those of `bevy_asset_loader`.
+//! - `toml`: adds `GenericAtlasDefinitions::from_toml_bytes` and `from_toml_str`, and loads
+//!   definitions from `.atlas.toml` files.
 
-use bevy::{prelude::*, sprite::TextureAtlas, utils::HashMap};
-use derive_more::IsVariant;
-use std::marker::PhantomData;
+use bevy::{
+    prelude::*,
+    render::render_resource::TextureFormat,
+    sprite::{Rect, TextureAtlas},
+    utils::{hashbrown::hash_map, HashMap, HashSet},
+};
+use derive_more::{Display, IsVariant};
+use std::{
+    marker::PhantomData,
+    path::PathBuf,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
 
+pub use self::compact::*;
 pub use self::definitions::*;
+pub use self::folder_manifest::*;
+pub use self::key::*;
+#[cfg(feature = "progress-tracking")]
+pub use self::progress::*;
+pub use self::readiness::*;
 pub use self::systems::*;
+pub use self::texture_packer::*;
+#[cfg(feature = "toml")]
+pub use self::toml_loader::*;
+pub use self::validate::*;
 
+mod background;
+mod color_key;
+mod color_space;
+mod compact;
 mod definitions;
+mod detect;
+mod folder_cache;
+mod folder_manifest;
+mod glob;
+mod key;
+mod layout;
+mod mipmaps;
+mod mosaic;
+mod padding;
+mod premultiply;
+#[cfg(feature = "progress-tracking")]
+mod progress;
+mod readiness;
+mod resample;
+mod stack;
 mod systems;
+mod texture_packer;
+#[cfg(feature = "toml")]
+mod toml_loader;
+mod validate;
+
+#[cfg(feature = "test-support")]
+pub mod test_support;
 
 /// Plugin for loading and creating [TextureAtlas] from a simple definition, and providing the
 /// results in a [AtlasTextures<T>] resource.
 ///
 /// See [GenericAtlasDefinitions].
-pub struct AtlasTexturePlugin<T>(PhantomData<T>);
+///
+/// # Panics
+///
+/// When added to an app, if the [AtlasKey::variants] of `T` are empty strings, or contain the same
+/// name more than once - e.g. due to `strum` renames.
+///
+/// # Example:
+/// ```
+/// # use std::time::Duration;
+/// # use bevy::prelude::*;
+/// # use bevy_atlas_loader::*;
+/// #[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
+/// #[derive(strum::EnumVariantNames, strum::EnumString)]
+/// enum MyAtlasTextures {
+///     Pacman,
+/// }
+///
+/// let _ = AtlasTexturePlugin::<MyAtlasTextures>::default()
+///     .with_load_timeout(Duration::from_secs(10));
+/// ```
+pub struct AtlasTexturePlugin<T> {
+    load_timeout: Option<Duration>,
+    retain_source_images: bool,
+    post_process: Option<AtlasPostProcess>,
+    lenient: bool,
+    stable_handles: bool,
+    atlas_scale: f32,
+    require_all_variants: bool,
+    prefetch: bool,
+    optional_variants: HashSet<String>,
+    _marker: PhantomData<T>,
+}
+
+impl<T> AtlasTexturePlugin<T> {
+    /// Fails with [ResourceError::LoadTimeout] if the assets of a definition haven't loaded
+    /// within `load_timeout`.
+    pub fn with_load_timeout(mut self, load_timeout: Duration) -> Self {
+        self.load_timeout = Some(load_timeout);
+        self
+    }
+
+    /// Keeps the source images of the atlases alive, making them available through
+    /// [AtlasTextures::source_image]. Off by default, allowing the source images to be unloaded
+    /// once the atlases are created.
+    pub fn with_retain_source_images(mut self, retain_source_images: bool) -> Self {
+        self.retain_source_images = retain_source_images;
+        self
+    }
+
+    /// Calls `post_process` with the key and the [TextureAtlas] of each created atlas, before the
+    /// atlas is added to `Assets<TextureAtlas>` - e.g. for adding extra regions.
+    pub fn with_post_process(
+        mut self,
+        post_process: impl FnMut(&str, &mut TextureAtlas) + Send + 'static,
+    ) -> Self {
+        self.post_process = Some(AtlasPostProcess(Arc::new(Mutex::new(post_process))));
+        self
+    }
+
+    /// Creates the [AtlasTextures<T>] from the definitions that succeed, rather than failing
+    /// altogether, reporting the failed definitions through [ResourceStatus::PartiallyCreated].
+    pub fn with_lenient(mut self, lenient: bool) -> Self {
+        self.lenient = lenient;
+        self
+    }
+
+    /// Updates the existing [TextureAtlas] assets in place when the definitions are reloaded,
+    /// rather than adding new ones - keeping any `Handle<TextureAtlas>` valid, so sprites get the
+    /// new layout automatically.
+    ///
+    /// Sprites keep their index though, which may point to another region (or none at all) if the
+    /// layout of the reloaded atlas differs.
+    pub fn with_stable_handles(mut self, stable_handles: bool) -> Self {
+        self.stable_handles = stable_handles;
+        self
+    }
+
+    /// Requires a definition for every variant of `T`, failing if any is missing. On by default.
+    ///
+    /// When off, only the definitions present are created, treating `T` as a superset of them -
+    /// use [AtlasTextures::try_handle] or [AtlasTextures::contains] for variants that may be
+    /// missing.
+    pub fn with_require_all_variants(mut self, require_all_variants: bool) -> Self {
+        self.require_all_variants = require_all_variants;
+        self
+    }
+
+    /// Scales the tile sizes, paddings and positions of the definitions by `atlas_scale`, rounded
+    /// to whole pixels - e.g. `2.0` for using definitions made for @1x art with @2x images.
+    ///
+    /// Only applies to definitions laying out the regions themselves, i.e. grids, manual
+    /// regions and frames. Nine-slice borders are not scaled.
+    ///
+    /// # Panics
+    ///
+    /// If `atlas_scale` isn't a positive, finite number.
+    pub fn with_atlas_scale(mut self, atlas_scale: f32) -> Self {
+        assert!(
+            atlas_scale.is_finite() && atlas_scale > 0.,
+            "atlas scale should be positive, not {}",
+            atlas_scale
+        );
+        self.atlas_scale = atlas_scale;
+        self
+    }
+
+    /// Starts loading the assets of every definition at once, rather than one definition at a
+    /// time - overlapping their I/O to reduce the total time spent loading.
+    ///
+    /// The atlases are still only created once all of them have loaded, but definitions of a
+    /// higher `priority` no longer get their assets first.
+    pub fn with_prefetch(mut self, prefetch: bool) -> Self {
+        self.prefetch = prefetch;
+        self
+    }
+
+    /// Marks `variants` of `T` as optional, so a missing definition for any of them doesn't fail
+    /// the others - e.g. for content packs that may or may not be installed.
+    ///
+    /// Missing optional variants are left out of the [AtlasTextures<T>], see
+    /// [AtlasTextures::try_handle]. The other variants are still required, unless
+    /// [with_require_all_variants](Self::with_require_all_variants) is turned off.
+    pub fn with_optional_variants(mut self, variants: impl IntoIterator<Item = T>) -> Self
+    where
+        T: AtlasKey,
+    {
+        self.optional_variants.extend(
+            variants
+                .into_iter()
+                .map(|variant| variant.to_key().into_owned()),
+        );
+        self
+    }
+}
 
 impl<T> Plugin for AtlasTexturePlugin<T>
 where
-    T: Send + Sync + Eq + std::str::FromStr + std::hash::Hash + strum::VariantNames + 'static,
-    <T as std::str::FromStr>::Err: std::fmt::Debug,
+    T: AtlasKey + Clone + Send + Sync + Eq + std::hash::Hash + 'static,
 {
     fn build(&self, app: &mut App) {
-        app.add_system(process_atlas_definitions::<T>)
-            .add_asset::<GenericAtlasDefinitions>()
-            .add_event::<AtlasTexturesEvent<T>>();
+        let variants = T::variants();
+        for (index, variant) in variants.iter().enumerate() {
+            assert!(
+                !variant.is_empty(),
+                "AtlasKey {} has a variant with an empty name",
+                std::any::type_name::<T>()
+            );
+            assert!(
+                !variants[..index].contains(variant),
+                "AtlasKey {} has several variants named {:?}",
+                std::any::type_name::<T>(),
+                variant
+            );
+        }
+
+        app.insert_resource(AtlasTextureSettings::<T> {
+            load_timeout: self.load_timeout,
+            retain_source_images: self.retain_source_images,
+            post_process: self.post_process.clone(),
+            lenient: self.lenient,
+            stable_handles: self.stable_handles,
+            atlas_scale: self.atlas_scale,
+            require_all_variants: self.require_all_variants,
+            prefetch: self.prefetch,
+            optional_variants: self.optional_variants.clone(),
+            _marker: PhantomData::default(),
+        })
+        .register_type::<AtlasDefinition>()
+        .register_type::<GridAtlasDefinition>()
+        .register_type::<GridByBoundsAtlasDefinition>()
+        .register_type::<PatchAtlasDefinition>()
+        .register_type::<FramesAtlasDefinition>()
+        .register_type::<FolderAtlasDefinition>()
+        .register_type::<FolderManifestAtlasDefinition>()
+        .register_type::<TexturePackerAtlasDefinition>()
+        .register_type::<ArrayAtlasDefinition>()
+        .register_type::<PagedAtlasDefinition>()
+        .register_type::<AutoDetectAtlasDefinition>()
+        .register_type::<GlobAtlasDefinition>()
+        .register_type::<MosaicAtlasDefinition>()
+        .register_type::<FractionalGridAtlasDefinition>()
+        .register_type::<AtlasRegion>()
+        .register_type::<DefinitionOptions>()
+        .register_type::<ImageOptions>()
+        .register_type::<PackOptions>()
+        .register_type::<NineSliceBorders>()
+        .register_type::<AnimationDefinition>()
+        .insert_resource(AtlasReadiness::<T>::default())
+        .add_system(process_atlas_definitions::<T>)
+        .add_system(readiness::update_atlas_readiness::<T>)
+        .add_asset::<GenericAtlasDefinitions>()
+        .add_asset::<FolderManifest>()
+        .init_asset_loader::<FolderManifestLoader>()
+        .add_asset::<TexturePackerSheet>()
+        .init_asset_loader::<TexturePackerSheetLoader>()
+        .add_event::<AtlasTexturesEvent<T>>();
+        #[cfg(feature = "toml")]
+        app.init_asset_loader::<AtlasDefinitionsTomlLoader>();
     }
 }
 
 impl<T> Default for AtlasTexturePlugin<T> {
     fn default() -> Self {
-        Self(Default::default())
+        Self {
+            load_timeout: None,
+            retain_source_images: false,
+            post_process: None,
+            lenient: false,
+            stable_handles: false,
+            atlas_scale: 1.,
+            require_all_variants: true,
+            prefetch: false,
+            optional_variants: HashSet::default(),
+            _marker: PhantomData::default(),
+        }
+    }
+}
+
+/// Resource with the settings of the [AtlasTexturePlugin<T>] for some `T`.
+#[derive(Debug)]
+pub struct AtlasTextureSettings<T> {
+    /// Time allowed for the assets of a single definition to load.
+    pub load_timeout: Option<Duration>,
+    /// Whether the source images of the atlases are kept alive.
+    pub retain_source_images: bool,
+    pub(crate) post_process: Option<AtlasPostProcess>,
+    /// Whether failing definitions are left out, rather than failing all of them.
+    pub lenient: bool,
+    /// Whether reloaded atlases update the existing [TextureAtlas] assets in place.
+    pub stable_handles: bool,
+    /// Factor the layouts of the definitions are scaled by.
+    pub atlas_scale: f32,
+    /// Whether a definition is required for every variant of `T`.
+    pub require_all_variants: bool,
+    /// Whether the assets of every definition start loading at once.
+    pub prefetch: bool,
+    /// Keys of the variants of `T` allowed to be missing a definition.
+    pub optional_variants: HashSet<String>,
+    _marker: PhantomData<T>,
+}
+
+/// Hook given to [AtlasTexturePlugin::with_post_process].
+#[derive(Clone)]
+pub(crate) struct AtlasPostProcess(Arc<Mutex<dyn FnMut(&str, &mut TextureAtlas) + Send>>);
+
+impl AtlasPostProcess {
+    pub(crate) fn apply(&self, key: &str, atlas: &mut TextureAtlas) {
+        let mut post_process = self.0.lock().expect("post process hook should not panic");
+        post_process(key, atlas);
+    }
+}
+
+impl std::fmt::Debug for AtlasPostProcess {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("AtlasPostProcess").finish()
     }
 }
 
@@ -60,36 +358,127 @@ impl<T> Default for AtlasTexturePlugin<T> {
 /// }
 ///
 /// fn setup_game(mut commands: Commands, atlases: Res<AtlasTextures<MyAtlasTextures>>) {
-///     commands
-///         .spawn_bundle(SpriteSheetBundle {
-///             sprite: TextureAtlasSprite {
-///                 index: 0,
-///                 custom_size: Some(Vec2::new(32.0, 32.0)),
-///                 ..Default::default()
-///             },
-///             texture_atlas: atlases.handle(MyAtlasTextures::Pacman),
-///             ..Default::default()
-///         });
+///     if let Some(bundle) =
+///         atlases.default_sprite_bundle(MyAtlasTextures::Pacman, Some(Vec2::new(32.0, 32.0)))
+///     {
+///         commands.spawn_bundle(bundle);
+///     }
 /// }
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct AtlasTextures<T>(HashMap<T, CreatedAtlas>)
 where
     T: Eq + std::hash::Hash;
 
+impl<T> Default for AtlasTextures<T>
+where
+    T: Eq + std::hash::Hash,
+{
+    fn default() -> Self {
+        Self(HashMap::default())
+    }
+}
+
 #[derive(Debug, Default, Clone)]
 struct CreatedAtlas {
     handle: Handle<TextureAtlas>,
-    len: usize,
+    texture: Handle<Image>,
+    rects: Vec<Rect>,
+    animations: HashMap<String, AnimationDefinition>,
+    indices: HashMap<String, usize>,
+    nine_patch: Option<NineSliceBorders>,
+    source_images: Vec<Handle<Image>>,
+    texture_array: Option<Handle<Image>>,
+    folder_layout: Option<Vec<(PathBuf, Rect)>>,
+    pages: Vec<(Handle<TextureAtlas>, usize)>,
+    index_offset: usize,
+    default_index: usize,
 }
 
+/// Marker component for entities using the [TextureAtlas] of some `T`.
+///
+/// Add [sync_atlas_sprite_handles<T>] to keep their `Handle<TextureAtlas>` updated whenever the
+/// [AtlasTextures<T>] are (re)created.
+#[derive(Debug, Component, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct UsesAtlasTexture<T>(pub T);
+
 /// Event sent whenever the plugin has (re)created the defined [AtlasTextures<T>] for some `T`
 /// (or failed in doing so!).
-#[derive(Debug, Clone, Copy)]
-pub struct AtlasTexturesEvent<T>(ResourceStatus, PhantomData<T>);
+///
+/// Before the event for the whole batch, an event with [ResourceStatus::AtlasCreated] is sent
+/// for every atlas of the created [AtlasTextures<T>], carrying its `T` and handle.
+#[derive(Debug, Clone)]
+pub struct AtlasTexturesEvent<T> {
+    status: ResourceStatus,
+    error: Option<ResourceError>,
+    failures: Vec<(String, ResourceError)>,
+    atlas: Option<(T, Handle<TextureAtlas>)>,
+}
 
 impl<T> AtlasTexturesEvent<T> {
+    pub(crate) fn created() -> Self {
+        Self {
+            status: ResourceStatus::Created,
+            error: None,
+            failures: Vec::new(),
+            atlas: None,
+        }
+    }
+
+    pub(crate) fn reloading() -> Self {
+        Self {
+            status: ResourceStatus::Reloading,
+            error: None,
+            failures: Vec::new(),
+            atlas: None,
+        }
+    }
+
+    pub(crate) fn failed(error: ResourceError) -> Self {
+        Self {
+            status: ResourceStatus::Failed,
+            error: Some(error),
+            failures: Vec::new(),
+            atlas: None,
+        }
+    }
+
+    pub(crate) fn partially_created(failures: Vec<(String, ResourceError)>) -> Self {
+        Self {
+            status: ResourceStatus::PartiallyCreated,
+            error: None,
+            failures,
+            atlas: None,
+        }
+    }
+
+    pub(crate) fn atlas_created(index: T, handle: Handle<TextureAtlas>) -> Self {
+        Self {
+            status: ResourceStatus::AtlasCreated,
+            error: None,
+            failures: Vec::new(),
+            atlas: Some((index, handle)),
+        }
+    }
+
     pub fn status(&self) -> ResourceStatus {
-        self.0
+        self.status
+    }
+
+    /// Returns the reason for a [ResourceStatus::Failed] status.
+    pub fn error(&self) -> Option<&ResourceError> {
+        self.error.as_ref()
+    }
+
+    /// Returns the keys of the definitions left out by a [ResourceStatus::PartiallyCreated]
+    /// status, along with the reason.
+    pub fn failures(&self) -> &[(String, ResourceError)] {
+        &self.failures
+    }
+
+    /// Returns the `T` and (weak) handle of the atlas for a [ResourceStatus::AtlasCreated]
+    /// status.
+    pub fn atlas(&self) -> Option<(&T, &Handle<TextureAtlas>)> {
+        self.atlas.as_ref().map(|(index, handle)| (index, handle))
     }
 }
 
@@ -97,8 +486,95 @@ impl<T> AtlasTexturesEvent<T> {
 pub enum ResourceStatus {
     Created,
     Failed,
+    /// The definitions changed, and the [AtlasTextures<T>] are being recreated.
+    Reloading,
+    /// In lenient mode, the [AtlasTextures<T>] were created without the definitions that failed.
+    PartiallyCreated,
+    /// A single atlas of the [AtlasTextures<T>] was created, see [AtlasTexturesEvent::atlas].
+    AtlasCreated,
+}
+
+/// Reason for failing to create the [AtlasTextures<T>] resource.
+#[derive(Debug, PartialEq, Eq, Clone, Display, IsVariant)]
+pub enum ResourceError {
+    /// No definition is present for the named variant of `T`.
+    #[display(fmt = "missing definition for variant {:?}", _0)]
+    MissingDefinition(String),
+    /// Building the [TextureAtlas] failed, with the message from the builder.
+    #[display(fmt = "failed building atlas: {}", _0)]
+    AtlasBuildFailed(String),
+    /// The grid, as defined, is larger than the image it should be placed upon.
+    #[display(fmt = "grid of size {} exceeds image of size {}", expected, actual)]
+    GridExceedsImage { expected: UVec2, actual: UVec2 },
+    /// An asset needed by a definition failed to load.
+    #[display(fmt = "failed loading {:?}", _0)]
+    LoadFailed(PathBuf),
+    /// A color key can't be applied to images of the given format.
+    #[display(fmt = "color key not supported for texture format {:?}", _0)]
+    UnsupportedTextureFormat(TextureFormat),
+    /// The assets of a definition didn't load within the configured timeout.
+    #[display(fmt = "timed out loading {:?}", _0)]
+    LoadTimeout(PathBuf),
+    /// The bounds of a grid don't fit a whole number of tiles.
+    #[display(
+        fmt = "grid bounds {} to {} don't fit a whole number of tiles of size {}",
+        first,
+        last,
+        tile_size
+    )]
+    InvalidGridBounds {
+        first: UVec2,
+        last: UVec2,
+        tile_size: UVec2,
+    },
+    /// The images of a definition combining several sheets don't share the same format.
+    #[display(
+        fmt = "texture format {:?} differs from {:?} of the first sheet",
+        actual,
+        expected
+    )]
+    MismatchedTextureFormats {
+        expected: TextureFormat,
+        actual: TextureFormat,
+    },
+    /// The images of an array definition don't share the same size.
+    #[display(
+        fmt = "layer of size {} differs from {} of the first layer",
+        actual,
+        expected
+    )]
+    MismatchedLayerSizes { expected: UVec2, actual: UVec2 },
+    /// A patch definition lists the same position more than once.
+    #[display(fmt = "region at {} is listed more than once", _0)]
+    DuplicateRegion(UVec2),
+    /// The definitions asset was unloaded before the atlases were created, e.g. while being
+    /// hot-reloaded.
+    #[display(fmt = "definitions were unloaded before the atlases were created")]
+    DefinitionsUnloaded,
+    /// A grid computing its columns or rows from the image doesn't fit a whole number of tiles.
+    #[display(
+        fmt = "image of size {} doesn't fit a whole number of tiles of size {}",
+        image_size,
+        tile_size
+    )]
+    UnevenGrid { image_size: UVec2, tile_size: UVec2 },
+    /// The folder of a folder definition holds no images, e.g. due to a mistyped path.
+    #[display(fmt = "folder {:?} holds no images", _0)]
+    EmptyFolder(PathBuf),
+    /// An image of an auto-detect definition holds more regions than allowed.
+    #[display(fmt = "image holds more than {} regions", _0)]
+    TooManyRegions(usize),
+    /// A region of a patch, frames or TexturePacker definition extends past the image it should
+    /// be placed upon.
+    #[display(fmt = "region at {} exceeds image of size {}", position, image_size)]
+    RegionOutOfBounds { position: UVec2, image_size: UVec2 },
+    /// A mosaic definition places more than one image into the same cell.
+    #[display(fmt = "cell {} is assigned more than once", _0)]
+    DuplicateCell(UVec2),
 }
 
+impl std::error::Error for ResourceError {}
+
 impl<T> AtlasTextures<T>
 where
     T: Eq + std::hash::Hash,
@@ -108,17 +584,315 @@ where
         self.0[index.borrow()].handle.clone_weak()
     }
 
+    /// Returns a cloned [TextureAtlas] handle for a specific `T`, if it was created.
+    pub fn try_handle<B: std::borrow::Borrow<T>>(&self, index: B) -> Option<Handle<TextureAtlas>> {
+        self.0
+            .get(index.borrow())
+            .map(|atlas| atlas.handle.clone_weak())
+    }
+
+    /// Returns whether a [TextureAtlas] was created for a specific `T`.
+    ///
+    /// Only `false` for disabled definitions, or those left out in lenient mode.
+    pub fn contains<B: std::borrow::Borrow<T>>(&self, index: B) -> bool {
+        self.0.contains_key(index.borrow())
+    }
+
+    /// Returns whether no [TextureAtlas] was created at all.
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
     /// Returns the total number of [TextureAtlas] index' for a specific `T`.
     ///
     /// Saves you from a lookup into `Asset<TextureAtlas>`.
-    #[allow(clippy::len_without_is_empty)]
     pub fn len<B: std::borrow::Borrow<T>>(&self, index: B) -> usize {
-        self.0[index.borrow()].len
+        self.0[index.borrow()].rects.len()
+    }
+
+    /// Returns the region, in pixels, of the [TextureAtlas] index `index` for a specific `T`.
+    ///
+    /// Saves you from a lookup into `Asset<TextureAtlas>`. For paged atlases the region is within
+    /// the page of the index, see [page_and_index](Self::page_and_index).
+    pub fn tile_rect<B: std::borrow::Borrow<T>>(&self, t: B, index: usize) -> Option<Rect> {
+        self.0.get(t.borrow())?.rects.get(index).copied()
+    }
+
+    /// Returns a named [AnimationDefinition] for a specific `T`, if defined.
+    pub fn animation<B: std::borrow::Borrow<T>>(
+        &self,
+        index: B,
+        name: &str,
+    ) -> Option<&AnimationDefinition> {
+        self.0.get(index.borrow())?.animations.get(name)
+    }
+
+    /// Returns the [TextureAtlas] index named `name` for a specific `T`, if defined.
+    ///
+    /// Names come from the `remap` of the definition, or from the source itself, e.g. the frame
+    /// names of a TexturePacker sheet. Prefer these over hardcoded indexes, as they survive edits
+    /// to the layout of the image.
+    pub fn index_of<B: std::borrow::Borrow<T>>(&self, index: B, name: &str) -> Option<usize> {
+        self.0.get(index.borrow())?.indices.get(name).copied()
+    }
+
+    /// Returns both the [TextureAtlas] index named `name` and its region, in pixels, for a
+    /// specific `T` - e.g. for setting up custom UVs.
+    ///
+    /// Returns `None` if no atlas was created for `t`, or `name` isn't defined.
+    pub fn lookup<B: std::borrow::Borrow<T>>(&self, t: B, name: &str) -> Option<(usize, Rect)> {
+        let atlas = self.0.get(t.borrow())?;
+        let index = *atlas.indices.get(name)?;
+        Some((index, *atlas.rects.get(index)?))
+    }
+
+    /// Returns the nine-slice borders of the [TextureAtlas] index `index` for a specific `T`, if
+    /// defined.
+    pub fn nine_slice<B: std::borrow::Borrow<T>>(
+        &self,
+        t: B,
+        index: usize,
+    ) -> Option<NineSliceBorders> {
+        let atlas = self.0.get(t.borrow())?;
+        atlas.nine_patch.filter(|_| index < atlas.rects.len())
+    }
+
+    /// Returns the image the [TextureAtlas] of a specific `T` was created from, if retained.
+    ///
+    /// Requires [AtlasTexturePlugin::with_retain_source_images]. For folder style atlases this is
+    /// the first of the [source_images](Self::source_images).
+    pub fn source_image<B: std::borrow::Borrow<T>>(&self, t: B) -> Option<&Handle<Image>> {
+        self.0.get(t.borrow())?.source_images.first()
+    }
+
+    /// Returns all images the [TextureAtlas] of a specific `T` was created from, if retained.
+    ///
+    /// Returns `None` if no atlas was created for `t`.
+    pub fn source_images<B: std::borrow::Borrow<T>>(&self, t: B) -> Option<&[Handle<Image>]> {
+        Some(self.0.get(t.borrow())?.source_images.as_slice())
+    }
+
+    /// Returns the path and region of every image packed into the atlas of a specific `T`, in the
+    /// order of their index - for folder style atlases, e.g. for inspecting or caching the
+    /// packing.
+    ///
+    /// Images without a path, such as generated ones, are left out.
+    pub fn folder_layout<B: std::borrow::Borrow<T>>(&self, t: B) -> Option<&[(PathBuf, Rect)]> {
+        self.0.get(t.borrow())?.folder_layout.as_deref()
+    }
+
+    /// Returns the texture array of a specific `T`, for definitions creating one, see
+    /// [ArrayAtlasDefinition].
+    pub fn texture_array<B: std::borrow::Borrow<T>>(&self, t: B) -> Option<Handle<Image>> {
+        self.0
+            .get(t.borrow())?
+            .texture_array
+            .as_ref()
+            .map(Handle::clone_weak)
+    }
+
+    /// Returns the index shown by sprites of a specific `T` not asking for a specific one, e.g.
+    /// the idle frame - `0` unless defined otherwise.
+    ///
+    /// Returns `None` if no atlas was created for `t`.
+    pub fn default_index<B: std::borrow::Borrow<T>>(&self, t: B) -> Option<usize> {
+        Some(self.0.get(t.borrow())?.default_index)
+    }
+
+    /// Returns the index of the first region of a specific `T` in its [TextureAtlas] - only
+    /// non-zero for grids sharing an atlas with others, see [GridAtlasDefinition::combine].
+    ///
+    /// Indexes of the [AtlasTextures<T>] are relative to the regions of `T`, while sprites index
+    /// the shared atlas - so add the offset when setting the index of a sprite.
+    ///
+    /// Returns `None` if no atlas was created for `t`.
+    pub fn index_offset<B: std::borrow::Borrow<T>>(&self, t: B) -> Option<usize> {
+        Some(self.0.get(t.borrow())?.index_offset)
+    }
+
+    /// Returns the [TextureAtlas] handles of every page of a specific `T`, in order - see
+    /// [PagedAtlasDefinition]. Other definitions consist of a single page.
+    ///
+    /// Returns `None` if no atlas was created for `t`.
+    pub fn pages<B: std::borrow::Borrow<T>>(&self, t: B) -> Option<Vec<Handle<TextureAtlas>>> {
+        Some(
+            self.0
+                .get(t.borrow())?
+                .pages
+                .iter()
+                .map(|(page, _)| page.clone_weak())
+                .collect(),
+        )
+    }
+
+    /// Returns the page holding the continuous `index` of a specific `T`, along with the index
+    /// into the [TextureAtlas] of that page.
+    ///
+    /// Returns `None` if no atlas was created for `t`, or `index` is outside of all pages.
+    pub fn page_and_index<B: std::borrow::Borrow<T>>(
+        &self,
+        t: B,
+        index: usize,
+    ) -> Option<(usize, usize)> {
+        let mut index = index;
+        for (page, (_, len)) in self.0.get(t.borrow())?.pages.iter().enumerate() {
+            if index < *len {
+                return Some((page, index));
+            }
+            index -= len;
+        }
+        None
+    }
+
+    /// Returns a [SpriteSheetBundle] showing the [TextureAtlas] index `index` of a specific `T`,
+    /// optionally at a `custom_size`.
+    ///
+    /// Picks the page holding `index` for paged atlases, and adds the
+    /// [index_offset](Self::index_offset) for combined ones.
+    ///
+    /// Returns `None` if no atlas was created for `t`.
+    pub fn sprite_bundle<B: std::borrow::Borrow<T>>(
+        &self,
+        t: B,
+        index: usize,
+        custom_size: Option<Vec2>,
+    ) -> Option<SpriteSheetBundle> {
+        let t = t.borrow();
+        let atlas = self.0.get(t)?;
+        let (page, index) = self.page_and_index(t, index).unwrap_or((0, index));
+        Some(SpriteSheetBundle {
+            sprite: TextureAtlasSprite {
+                index: atlas.index_offset + index,
+                custom_size,
+                ..Default::default()
+            },
+            texture_atlas: atlas.pages[page].0.clone_weak(),
+            ..Default::default()
+        })
+    }
+
+    /// Returns a [SpriteSheetBundle] showing the [default_index](Self::default_index) of a
+    /// specific `T`, see [sprite_bundle](Self::sprite_bundle).
+    ///
+    /// Returns `None` if no atlas was created for `t`.
+    pub fn default_sprite_bundle<B: std::borrow::Borrow<T>>(
+        &self,
+        t: B,
+        custom_size: Option<Vec2>,
+    ) -> Option<SpriteSheetBundle> {
+        let t = t.borrow();
+        self.sprite_bundle(t, self.default_index(t)?, custom_size)
+    }
+
+    /// Returns a [SpriteSheetBundle] showing the [TextureAtlas] index named `name` of a specific
+    /// `T`, see [index_of](Self::index_of) and [sprite_bundle](Self::sprite_bundle).
+    ///
+    /// Returns `None` if no atlas was created for `t`, or `name` isn't defined.
+    pub fn sprite_bundle_named<B: std::borrow::Borrow<T>>(
+        &self,
+        t: B,
+        name: &str,
+        custom_size: Option<Vec2>,
+    ) -> Option<SpriteSheetBundle> {
+        let t = t.borrow();
+        let index = *self.0.get(t)?.indices.get(name)?;
+        self.sprite_bundle(t, index, custom_size)
+    }
+
+    /// Returns an estimate of the GPU memory, in bytes, used by the images of all created atlas',
+    /// e.g. for deciding whether to load optional high resolution atlas'.
+    ///
+    /// Images shared by several atlas' are only counted once. Texture arrays are counted as well.
+    pub fn estimated_bytes(&self, images: &Assets<Image>) -> usize {
+        self.0
+            .values()
+            .flat_map(|atlas| std::iter::once(&atlas.texture).chain(&atlas.texture_array))
+            .map(|image| image.id)
+            .collect::<HashSet<_>>()
+            .into_iter()
+            .filter_map(|id| images.get(id))
+            .map(|image| image.data.len())
+            .sum()
+    }
+
+    /// Returns the `T` whose [TextureAtlas] is `handle`, e.g. for a system only having the handle
+    /// of a sprite.
+    ///
+    /// Grids combined into a shared atlas, see [GridAtlasDefinition::combine], all have the same
+    /// handle - for these an arbitrary `T` of the group is returned. Tell them apart by comparing
+    /// the index of the sprite against the [index_offset](Self::index_offset) and
+    /// [len](Self::len) of each `T`.
+    pub fn variant_of(&self, handle: &Handle<TextureAtlas>) -> Option<&T> {
+        self.0
+            .iter()
+            .find(|(_, atlas)| atlas.handle.id == handle.id)
+            .map(|(t, _)| t)
+    }
+
+    /// Iterates all created [TextureAtlas] handles, along with their `T`.
+    pub fn iter(&self) -> AtlasTexturesIter<'_, T> {
+        AtlasTexturesIter(self.0.iter())
+    }
+
+    /// Converts the atlases to another keying, e.g. for unifying the atlases of several `T` in a
+    /// single map keyed by name.
+    ///
+    /// If `f` maps several `T` to the same `U`, only one of their atlases is kept.
+    pub fn map_keys<U, F>(self, f: F) -> AtlasTextures<U>
+    where
+        U: Eq + std::hash::Hash,
+        F: Fn(T) -> U,
+    {
+        AtlasTextures(self.0.into_iter().map(|(t, atlas)| (f(t), atlas)).collect())
+    }
+}
+
+impl<'a, T> IntoIterator for &'a AtlasTextures<T>
+where
+    T: Eq + std::hash::Hash,
+{
+    type Item = (&'a T, Handle<TextureAtlas>);
+    type IntoIter = AtlasTexturesIter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// Iterator over the [TextureAtlas] handles of [AtlasTextures<T>], see [AtlasTextures::iter].
+pub struct AtlasTexturesIter<'a, T>(hash_map::Iter<'a, T, CreatedAtlas>);
+
+impl<'a, T> Iterator for AtlasTexturesIter<'a, T> {
+    type Item = (&'a T, Handle<TextureAtlas>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0
+            .next()
+            .map(|(index, atlas)| (index, atlas.handle.clone_weak()))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.0.size_hint()
+    }
+}
+
+impl<'a, T> ExactSizeIterator for AtlasTexturesIter<'a, T> {}
+
+impl<T> AtlasTextures<T>
+where
+    T: AtlasKey + Eq + std::hash::Hash,
+{
+    /// Returns a cloned [TextureAtlas] handle for the `T` named by `name`.
+    ///
+    /// Returns `None` if `name` doesn't convert into a `T`.
+    pub fn handle_by_name(&self, name: &str) -> Option<Handle<TextureAtlas>> {
+        let index = T::from_key(name)?;
+        self.0.get(&index).map(|atlas| atlas.handle.clone_weak())
     }
 }
 
 impl<T> AtlasTexturesEvent<T> {
     pub fn state(&self) -> ResourceStatus {
-        self.0
+        self.status
     }
 }
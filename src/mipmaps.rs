@@ -0,0 +1,154 @@
+//! Generation of mipmaps, reducing the shimmering of sprites scaled down.
+
+use bevy::{prelude::*, render::render_resource::TextureFormat};
+
+use crate::ResourceError;
+
+/// Appends the mip levels of `image` down to a single pixel, each averaging 2x2 pixels of the
+/// level above.
+///
+/// Images already having mip levels, such as those loaded from KTX2 files, are left as they
+/// are. Colors are averaged as stored, even for sRGB formats.
+pub(crate) fn generate_mipmaps(image: &mut Image) -> Result<(), ResourceError> {
+    // compressed formats can't be averaged, but may well come with mip levels of their own
+    if image.texture_descriptor.mip_level_count > 1 {
+        return Ok(());
+    }
+    match image.texture_descriptor.format {
+        TextureFormat::Rgba8Unorm
+        | TextureFormat::Rgba8UnormSrgb
+        | TextureFormat::Bgra8Unorm
+        | TextureFormat::Bgra8UnormSrgb => {}
+        format => return Err(ResourceError::UnsupportedTextureFormat(format)),
+    }
+
+    let mut size = image.size().as_uvec2();
+    let mut level = image.data.clone();
+    let mut mip_level_count = 1;
+    while size.max_element() > 1 {
+        let next_size = (size / 2).max(UVec2::ONE);
+        level = downsample(&level, size, next_size);
+        image.data.extend_from_slice(&level);
+        size = next_size;
+        mip_level_count += 1;
+    }
+    image.texture_descriptor.mip_level_count = mip_level_count;
+    Ok(())
+}
+
+/// Averages the 2x2 pixels of the RGBA `level` of size `size` covered by each pixel of the level
+/// below. Odd rows and columns are folded into the last pixel.
+fn downsample(level: &[u8], size: UVec2, next_size: UVec2) -> Vec<u8> {
+    let (width, height) = (size.x as usize, size.y as usize);
+    let mut data = Vec::with_capacity(next_size.x as usize * next_size.y as usize * 4);
+    for y in 0..next_size.y as usize {
+        let rows = [(y * 2).min(height - 1), (y * 2 + 1).min(height - 1)];
+        for x in 0..next_size.x as usize {
+            let columns = [(x * 2).min(width - 1), (x * 2 + 1).min(width - 1)];
+            for channel in 0..4 {
+                let sum = rows
+                    .iter()
+                    .flat_map(|row| columns.iter().map(move |column| (row, column)))
+                    .map(|(row, column)| level[(row * width + column) * 4 + channel] as u32)
+                    .sum::<u32>();
+                data.push(((sum + 2) / 4) as u8);
+            }
+        }
+    }
+    data
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy::render::render_resource::{Extent3d, TextureDimension};
+
+    fn image(width: u32, height: u32, data: Vec<u8>, format: TextureFormat) -> Image {
+        Image::new(
+            Extent3d {
+                width,
+                height,
+                depth_or_array_layers: 1,
+            },
+            TextureDimension::D2,
+            data,
+            format,
+        )
+    }
+
+    #[test]
+    fn levels_average_the_level_above() {
+        let mut image = image(
+            2,
+            2,
+            [
+                [0, 0, 0, 255],
+                [100, 0, 0, 255],
+                [0, 200, 0, 255],
+                [0, 0, 40, 255],
+            ]
+            .concat(),
+            TextureFormat::Rgba8UnormSrgb,
+        );
+
+        generate_mipmaps(&mut image).unwrap();
+
+        assert_eq!(image.texture_descriptor.mip_level_count, 2);
+        assert_eq!(image.data[16..], [25, 50, 10, 255]);
+    }
+
+    #[test]
+    fn levels_go_down_to_a_single_pixel() {
+        let mut image = image(5, 2, vec![255; 5 * 2 * 4], TextureFormat::Bgra8Unorm);
+
+        generate_mipmaps(&mut image).unwrap();
+
+        // 5x2, 2x1 and 1x1
+        assert_eq!(image.texture_descriptor.mip_level_count, 3);
+        assert_eq!(image.data.len(), (10 + 2 + 1) * 4);
+        assert!(image.data.iter().all(|&value| value == 255));
+    }
+
+    #[test]
+    fn existing_levels_are_kept() {
+        let mut image = image(2, 1, vec![7; 12], TextureFormat::Rgba8UnormSrgb);
+        image.texture_descriptor.mip_level_count = 2;
+
+        generate_mipmaps(&mut image).unwrap();
+
+        assert_eq!(image.texture_descriptor.mip_level_count, 2);
+        assert_eq!(image.data, vec![7; 12]);
+    }
+
+    #[test]
+    fn existing_levels_of_other_formats_are_kept() {
+        // e.g. a KTX2 image, compressed in blocks of 4x4 pixels
+        let mut image = Image {
+            data: vec![7; 16 + 16],
+            ..Default::default()
+        };
+        image.texture_descriptor.size = Extent3d {
+            width: 8,
+            height: 4,
+            depth_or_array_layers: 1,
+        };
+        image.texture_descriptor.format = TextureFormat::Bc1RgbaUnormSrgb;
+        image.texture_descriptor.mip_level_count = 3;
+
+        assert_eq!(generate_mipmaps(&mut image), Ok(()));
+        assert_eq!(image.texture_descriptor.mip_level_count, 3);
+        assert_eq!(image.data, vec![7; 16 + 16]);
+    }
+
+    #[test]
+    fn other_formats_are_unsupported() {
+        let mut image = image(1, 1, vec![0; 2], TextureFormat::Rg8Unorm);
+
+        assert_eq!(
+            generate_mipmaps(&mut image),
+            Err(ResourceError::UnsupportedTextureFormat(
+                TextureFormat::Rg8Unorm
+            ))
+        );
+    }
+}
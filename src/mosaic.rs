@@ -0,0 +1,142 @@
+//! Mosaics of images, placing each of several images into a cell of a grid.
+
+use bevy::{prelude::*, render::render_resource::Extent3d, sprite::Rect};
+
+use crate::ResourceError;
+
+/// Returns the first cell listed more than once, if any.
+pub(crate) fn duplicate_cell(cells: impl IntoIterator<Item = UVec2>) -> Option<UVec2> {
+    let mut seen = Vec::new();
+    cells.into_iter().find(|cell| {
+        let duplicate = seen.contains(cell);
+        seen.push(*cell);
+        duplicate
+    })
+}
+
+/// Places each image at the top-left of its cell, in a grid of cells as large as the largest
+/// image.
+///
+/// Returns the image of the mosaic, along with the region of each image in the order of `cells`.
+/// All images must share the format of the first.
+pub(crate) fn mosaic_images(
+    cells: &[(UVec2, &Image)],
+) -> Result<(Image, Vec<Rect>), ResourceError> {
+    let (_, first) = cells.first().expect("at least one cell to place");
+    let format = first.texture_descriptor.format;
+    if let Some((_, image)) = cells
+        .iter()
+        .find(|(_, image)| image.texture_descriptor.format != format)
+    {
+        return Err(ResourceError::MismatchedTextureFormats {
+            expected: format,
+            actual: image.texture_descriptor.format,
+        });
+    }
+    if let Some(cell) = duplicate_cell(cells.iter().map(|(cell, _)| *cell)) {
+        return Err(ResourceError::DuplicateCell(cell));
+    }
+
+    let cell_size = cells
+        .iter()
+        .map(|(_, image)| image.size().as_uvec2())
+        .fold(UVec2::ZERO, UVec2::max);
+    let grid_size = cells
+        .iter()
+        .map(|(cell, _)| *cell + UVec2::ONE)
+        .fold(UVec2::ZERO, UVec2::max);
+    let mosaic_size = grid_size * cell_size;
+    let pixel_size = format.describe().block_size as usize;
+    let mosaic_row_size = mosaic_size.x as usize * pixel_size;
+
+    let mut data = vec![0; mosaic_row_size * mosaic_size.y as usize];
+    let mut rects = Vec::with_capacity(cells.len());
+    for (cell, image) in cells {
+        let size = image.size().as_uvec2();
+        let min = *cell * cell_size;
+        let row_size = size.x as usize * pixel_size;
+        // only the first mip level is kept
+        for (row, pixels) in image
+            .data
+            .chunks_exact(row_size)
+            .take(size.y as usize)
+            .enumerate()
+        {
+            let begin = (min.y as usize + row) * mosaic_row_size + min.x as usize * pixel_size;
+            data[begin..begin + row_size].copy_from_slice(pixels);
+        }
+        rects.push(Rect {
+            min: min.as_vec2(),
+            max: (min + size).as_vec2(),
+        });
+    }
+
+    let mut mosaic = (*first).clone();
+    mosaic.texture_descriptor.size = Extent3d {
+        width: mosaic_size.x,
+        height: mosaic_size.y,
+        depth_or_array_layers: 1,
+    };
+    mosaic.texture_descriptor.mip_level_count = 1;
+    mosaic.data = data;
+    Ok((mosaic, rects))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy::render::render_resource::{TextureDimension, TextureFormat};
+
+    fn image(width: u32, height: u32, data: Vec<u8>, format: TextureFormat) -> Image {
+        Image::new(
+            Extent3d {
+                width,
+                height,
+                depth_or_array_layers: 1,
+            },
+            TextureDimension::D2,
+            data,
+            format,
+        )
+    }
+
+    #[test]
+    fn places_images_in_their_cells() {
+        let wide = image(2, 1, vec![1, 2], TextureFormat::R8Unorm);
+        let small = image(1, 1, vec![3], TextureFormat::R8Unorm);
+
+        let (mosaic, rects) =
+            mosaic_images(&[(UVec2::new(1, 1), &wide), (UVec2::new(0, 0), &small)]).unwrap();
+
+        assert_eq!(mosaic.size(), Vec2::new(4., 2.));
+        assert_eq!(mosaic.data, vec![3, 0, 0, 0, 0, 0, 1, 2]);
+        assert_eq!(rects[0].min, Vec2::new(2., 1.));
+        assert_eq!(rects[0].max, Vec2::new(4., 2.));
+        assert_eq!(rects[1].min, Vec2::ZERO);
+        assert_eq!(rects[1].max, Vec2::ONE);
+    }
+
+    #[test]
+    fn overlapping_cells_fail() {
+        let icon = image(1, 1, vec![1], TextureFormat::R8Unorm);
+
+        assert_eq!(
+            mosaic_images(&[(UVec2::new(2, 0), &icon), (UVec2::new(2, 0), &icon)]).err(),
+            Some(ResourceError::DuplicateCell(UVec2::new(2, 0)))
+        );
+    }
+
+    #[test]
+    fn mismatched_formats_fail() {
+        let first = image(1, 1, vec![1], TextureFormat::R8Unorm);
+        let second = image(1, 1, vec![1, 2, 3, 4], TextureFormat::Rgba8UnormSrgb);
+
+        assert_eq!(
+            mosaic_images(&[(UVec2::ZERO, &first), (UVec2::X, &second)]).err(),
+            Some(ResourceError::MismatchedTextureFormats {
+                expected: TextureFormat::R8Unorm,
+                actual: TextureFormat::Rgba8UnormSrgb,
+            })
+        );
+    }
+}
@@ -0,0 +1,59 @@
+//! Padding of images, keeping packed regions from bleeding into each other when sampled.
+
+use bevy::{prelude::*, render::render_resource::Extent3d};
+
+/// Surrounds `image` by a transparent border of `padding` pixels.
+pub(crate) fn pad_image(image: &Image, padding: u32) -> Image {
+    let pixel_size = image.texture_descriptor.format.describe().block_size as usize;
+    let size = image.size().as_uvec2();
+    let padded_size = size + UVec2::splat(2 * padding);
+    let padded_row_size = padded_size.x as usize * pixel_size;
+    let row_size = size.x as usize * pixel_size;
+
+    let mut data = vec![0; padded_row_size * padded_size.y as usize];
+    // only the first mip level is kept
+    for (row, pixels) in image
+        .data
+        .chunks_exact(row_size)
+        .take(size.y as usize)
+        .enumerate()
+    {
+        let begin = (row + padding as usize) * padded_row_size + padding as usize * pixel_size;
+        data[begin..begin + row_size].copy_from_slice(pixels);
+    }
+
+    let mut padded = image.clone();
+    padded.texture_descriptor.size = Extent3d {
+        width: padded_size.x,
+        height: padded_size.y,
+        depth_or_array_layers: 1,
+    };
+    padded.texture_descriptor.mip_level_count = 1;
+    padded.data = data;
+    padded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy::render::render_resource::{TextureDimension, TextureFormat};
+
+    #[test]
+    fn surrounds_image_by_transparent_border() {
+        let image = Image::new(
+            Extent3d {
+                width: 2,
+                height: 1,
+                depth_or_array_layers: 1,
+            },
+            TextureDimension::D2,
+            vec![1, 2],
+            TextureFormat::R8Unorm,
+        );
+
+        let padded = pad_image(&image, 1);
+
+        assert_eq!(padded.size(), Vec2::new(4., 3.));
+        assert_eq!(padded.data, vec![0, 0, 0, 0, 0, 1, 2, 0, 0, 0, 0, 0]);
+    }
+}
@@ -0,0 +1,69 @@
+//! Premultiplied alpha, for materials blending with premultiplied colors.
+
+use bevy::{prelude::*, render::render_resource::TextureFormat};
+
+use crate::ResourceError;
+
+/// Multiplies the color channels of every pixel of `image` by its alpha.
+pub(crate) fn premultiply_alpha(image: &mut Image) -> Result<(), ResourceError> {
+    match image.texture_descriptor.format {
+        TextureFormat::Rgba8Unorm
+        | TextureFormat::Rgba8UnormSrgb
+        | TextureFormat::Bgra8Unorm
+        | TextureFormat::Bgra8UnormSrgb => {}
+        format => return Err(ResourceError::UnsupportedTextureFormat(format)),
+    }
+    image.data.chunks_exact_mut(4).for_each(|pixel| {
+        let alpha = pixel[3] as u16;
+        for channel in &mut pixel[..3] {
+            *channel = ((*channel as u16 * alpha + 127) / 255) as u8;
+        }
+    });
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy::render::render_resource::{Extent3d, TextureDimension};
+
+    fn image(data: Vec<u8>, format: TextureFormat) -> Image {
+        Image::new(
+            Extent3d {
+                width: data.len() as u32 / 4,
+                height: 1,
+                depth_or_array_layers: 1,
+            },
+            TextureDimension::D2,
+            data,
+            format,
+        )
+    }
+
+    #[test]
+    fn colors_are_multiplied_by_alpha() {
+        let mut image = image(
+            vec![255, 128, 0, 255, 255, 128, 0, 128, 255, 128, 0, 0],
+            TextureFormat::Rgba8UnormSrgb,
+        );
+
+        premultiply_alpha(&mut image).unwrap();
+
+        assert_eq!(
+            image.data,
+            vec![255, 128, 0, 255, 128, 64, 0, 128, 0, 0, 0, 0]
+        );
+    }
+
+    #[test]
+    fn other_formats_are_unsupported() {
+        let mut image = image(vec![0; 8], TextureFormat::Rg16Uint);
+
+        assert_eq!(
+            premultiply_alpha(&mut image),
+            Err(ResourceError::UnsupportedTextureFormat(
+                TextureFormat::Rg16Uint
+            ))
+        );
+    }
+}
@@ -0,0 +1,68 @@
+//! Progress of creating the atlases, for loading states tracked by [iyes_progress] - such as
+//! those of `bevy_asset_loader` with its `progress_tracking` feature.
+
+use bevy::prelude::*;
+use iyes_progress::Progress;
+
+use crate::{DefinitionStatus, GenericAtlasDefinitions, TypedAtlasDefinition};
+
+/// Reports the progress of creating the [AtlasTextures<T>](crate::AtlasTextures<T>), so a loading
+/// state tracked by [iyes_progress] only continues once they're created.
+///
+/// Each definition counts once created, and the [AtlasTextures<T>](crate::AtlasTextures<T>)
+/// once all of them are. Failing to create them leaves the loading state waiting, see
+/// [AtlasTexturesEvent<T>](crate::AtlasTexturesEvent<T>) for the error.
+///
+/// # Example:
+/// ```rust
+/// # use bevy::prelude::*;
+/// # use bevy_atlas_loader::*;
+/// use iyes_progress::{ProgressPlugin, ProgressSystem};
+///
+/// #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+/// enum GameState {
+///     Loading,
+///     Running,
+/// }
+///
+/// #[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
+/// #[derive(strum::EnumVariantNames, strum::EnumString)]
+/// enum MyAtlasTextures {
+///     Pacman,
+/// }
+///
+/// App::new()
+///     .add_state(GameState::Loading)
+///     .add_plugin(ProgressPlugin::new(GameState::Loading).continue_to(GameState::Running))
+///     .add_system_set(
+///         SystemSet::on_update(GameState::Loading)
+///             .with_system(atlas_textures_progress::<MyAtlasTextures>.track_progress()),
+///     );
+/// ```
+pub fn atlas_textures_progress<T>(
+    definition_handle: Option<Res<TypedAtlasDefinition<T>>>,
+    atlas_definitions: Res<Assets<GenericAtlasDefinitions>>,
+) -> Progress
+where
+    T: Send + Sync + 'static,
+{
+    let definition_handle = match definition_handle {
+        Some(definition_handle) => definition_handle,
+        None => return false.into(),
+    };
+    if definition_handle.state.is_done() {
+        return true.into();
+    }
+    let (done, total) = definition_handle
+        .definitions_status(&atlas_definitions)
+        .fold((0, 0), |(done, total), (_, status)| match status {
+            DefinitionStatus::Created => (done + 1, total + 1),
+            DefinitionStatus::None | DefinitionStatus::Loading => (done, total + 1),
+            DefinitionStatus::Failed | DefinitionStatus::Disabled => (done, total),
+        });
+    // the atlases themselves are still to be created
+    Progress {
+        done,
+        total: total + 1,
+    }
+}
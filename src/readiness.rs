@@ -0,0 +1,131 @@
+//! Readiness of the atlases as a [Future], for code outside of the Bevy schedule.
+
+use bevy::prelude::*;
+use std::{
+    future::Future,
+    marker::PhantomData,
+    pin::Pin,
+    sync::{Arc, Mutex},
+    task::{Context, Poll, Waker},
+};
+
+use crate::{AtlasTexturesEvent, ResourceError, ResourceStatus};
+
+/// Resource for awaiting the [AtlasTextures<T>](crate::AtlasTextures<T>) of some `T`, e.g. from
+/// an async asset orchestration layer not structured as Bevy systems.
+///
+/// Clone the resource out of the [World] and [wait](Self::wait) on it from anywhere - it
+/// complements the [AtlasTexturesEvent<T>] for code that can't read events.
+///
+/// # Example:
+/// ```rust
+/// # use bevy::prelude::*;
+/// # use bevy_atlas_loader::*;
+/// # #[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
+/// # #[derive(strum::EnumVariantNames, strum::EnumString)]
+/// # enum MyAtlasTextures { Pacman }
+/// async fn spawn_level(readiness: AtlasReadiness<MyAtlasTextures>) -> Result<(), ResourceError> {
+///     readiness.wait().await?;
+///     // the atlases are created, and the level can be spawned
+///     Ok(())
+/// }
+/// ```
+pub struct AtlasReadiness<T> {
+    shared: Arc<Mutex<Readiness>>,
+    _marker: PhantomData<fn() -> T>,
+}
+
+#[derive(Debug, Default)]
+struct Readiness {
+    outcome: Option<Result<(), ResourceError>>,
+    wakers: Vec<Waker>,
+}
+
+impl<T> AtlasReadiness<T> {
+    /// Returns a future completing once the atlases are created, or failed being created.
+    ///
+    /// Completes right away if they already are. While the atlases are being recreated, e.g.
+    /// due to the definitions being hot-reloaded, the future waits for the recreated atlases.
+    /// Atlases left out in lenient mode count as created.
+    pub fn wait(&self) -> AtlasReady {
+        AtlasReady(self.shared.clone())
+    }
+
+    /// Returns the outcome of creating the atlases, or `None` while they're being created.
+    pub fn outcome(&self) -> Option<Result<(), ResourceError>> {
+        self.shared.lock().unwrap().outcome.clone()
+    }
+
+    fn complete(&self, outcome: Option<Result<(), ResourceError>>) {
+        let mut readiness = self.shared.lock().unwrap();
+        readiness.outcome = outcome;
+        if readiness.outcome.is_some() {
+            readiness.wakers.drain(..).for_each(Waker::wake);
+        }
+    }
+}
+
+impl<T> Default for AtlasReadiness<T> {
+    fn default() -> Self {
+        Self {
+            shared: Arc::default(),
+            _marker: PhantomData::default(),
+        }
+    }
+}
+
+impl<T> Clone for AtlasReadiness<T> {
+    fn clone(&self) -> Self {
+        Self {
+            shared: self.shared.clone(),
+            _marker: PhantomData::default(),
+        }
+    }
+}
+
+/// Future returned by [AtlasReadiness::wait].
+#[derive(Debug)]
+pub struct AtlasReady(Arc<Mutex<Readiness>>);
+
+impl Future for AtlasReady {
+    type Output = Result<(), ResourceError>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let mut readiness = self.0.lock().unwrap();
+        match &readiness.outcome {
+            Some(outcome) => Poll::Ready(outcome.clone()),
+            None => {
+                if !readiness
+                    .wakers
+                    .iter()
+                    .any(|waker| waker.will_wake(cx.waker()))
+                {
+                    readiness.wakers.push(cx.waker().clone());
+                }
+                Poll::Pending
+            }
+        }
+    }
+}
+
+/// Completes the futures waiting on the [AtlasReadiness<T>], as the atlases are created.
+pub(crate) fn update_atlas_readiness<T>(
+    readiness: Res<AtlasReadiness<T>>,
+    mut events: EventReader<AtlasTexturesEvent<T>>,
+) where
+    T: Send + Sync + 'static,
+{
+    for event in events.iter() {
+        match event.status() {
+            ResourceStatus::Created | ResourceStatus::PartiallyCreated => {
+                readiness.complete(Some(Ok(())))
+            }
+            ResourceStatus::Failed => readiness.complete(Some(Err(event
+                .error()
+                .cloned()
+                .expect("failed events carry an error")))),
+            ResourceStatus::Reloading => readiness.complete(None),
+            ResourceStatus::AtlasCreated => {}
+        }
+    }
+}
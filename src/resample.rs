@@ -0,0 +1,72 @@
+//! Resampling of images, e.g. for shipping art at a higher resolution than used in-game.
+
+use bevy::{prelude::*, render::render_resource::Extent3d};
+
+/// Resizes `image` to `size` using nearest-neighbour sampling.
+///
+/// Nearest-neighbour keeps pixel art crisp and is exact for integer factors like `0.5` or `2.0`.
+/// Other factors may drop or duplicate rows and columns unevenly, and downscaling doesn't
+/// average the pixels - so fine details such as thin lines may disappear.
+pub(crate) fn resize_nearest(image: &Image, size: UVec2) -> Image {
+    let pixel_size = image.texture_descriptor.format.describe().block_size as usize;
+    let source_size = image.size().as_uvec2();
+    let source_width = source_size.x as usize;
+    let mut data = Vec::with_capacity(size.x as usize * size.y as usize * pixel_size);
+    for y in 0..size.y {
+        let source_y = (y as u64 * source_size.y as u64 / size.y as u64) as usize;
+        for x in 0..size.x {
+            let source_x = (x as u64 * source_size.x as u64 / size.x as u64) as usize;
+            let begin = (source_y * source_width + source_x) * pixel_size;
+            data.extend_from_slice(&image.data[begin..begin + pixel_size]);
+        }
+    }
+
+    let mut resized = image.clone();
+    resized.texture_descriptor.size = Extent3d {
+        width: size.x,
+        height: size.y,
+        depth_or_array_layers: 1,
+    };
+    // mip levels of the source no longer match, and are dropped
+    resized.texture_descriptor.mip_level_count = 1;
+    resized.data = data;
+    resized
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy::render::render_resource::{TextureDimension, TextureFormat};
+
+    fn image(size: UVec2, data: Vec<u8>) -> Image {
+        Image::new(
+            Extent3d {
+                width: size.x,
+                height: size.y,
+                depth_or_array_layers: 1,
+            },
+            TextureDimension::D2,
+            data,
+            TextureFormat::R8Unorm,
+        )
+    }
+
+    #[test]
+    fn halving_keeps_every_other_pixel() {
+        let source = image(UVec2::new(4, 2), vec![1, 2, 3, 4, 5, 6, 7, 8]);
+
+        let resized = resize_nearest(&source, UVec2::new(2, 1));
+
+        assert_eq!(resized.size(), Vec2::new(2., 1.));
+        assert_eq!(resized.data, vec![1, 3]);
+    }
+
+    #[test]
+    fn doubling_repeats_pixels() {
+        let source = image(UVec2::new(2, 1), vec![1, 2]);
+
+        let resized = resize_nearest(&source, UVec2::new(4, 2));
+
+        assert_eq!(resized.data, vec![1, 1, 2, 2, 1, 1, 2, 2]);
+    }
+}
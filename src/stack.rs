@@ -0,0 +1,104 @@
+//! Stacking of images, combining several sheets into the single image of an atlas.
+
+use bevy::{prelude::*, render::render_resource::Extent3d};
+
+use crate::ResourceError;
+
+/// Stacks `images` on top of each other, left aligned, in a single image as wide as the widest.
+///
+/// All images must share the format of the first.
+pub(crate) fn stack_images(images: &[&Image]) -> Result<Image, ResourceError> {
+    let first = images.first().expect("at least one image to stack");
+    let format = first.texture_descriptor.format;
+    if let Some(image) = images
+        .iter()
+        .find(|image| image.texture_descriptor.format != format)
+    {
+        return Err(ResourceError::MismatchedTextureFormats {
+            expected: format,
+            actual: image.texture_descriptor.format,
+        });
+    }
+
+    let pixel_size = format.describe().block_size as usize;
+    let sizes = images
+        .iter()
+        .map(|image| image.size().as_uvec2())
+        .collect::<Vec<_>>();
+    let stacked_size = UVec2::new(
+        sizes.iter().map(|size| size.x).max().unwrap_or_default(),
+        sizes.iter().map(|size| size.y).sum(),
+    );
+    let stacked_row_size = stacked_size.x as usize * pixel_size;
+
+    let mut data = vec![0; stacked_row_size * stacked_size.y as usize];
+    let mut first_row = 0;
+    for (image, size) in images.iter().zip(&sizes) {
+        let row_size = size.x as usize * pixel_size;
+        // only the first mip level is kept
+        for (row, pixels) in image
+            .data
+            .chunks_exact(row_size)
+            .take(size.y as usize)
+            .enumerate()
+        {
+            let begin = (first_row + row) * stacked_row_size;
+            data[begin..begin + row_size].copy_from_slice(pixels);
+        }
+        first_row += size.y as usize;
+    }
+
+    let mut stacked = (*first).clone();
+    stacked.texture_descriptor.size = Extent3d {
+        width: stacked_size.x,
+        height: stacked_size.y,
+        depth_or_array_layers: 1,
+    };
+    stacked.texture_descriptor.mip_level_count = 1;
+    stacked.data = data;
+    Ok(stacked)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy::render::render_resource::{TextureDimension, TextureFormat};
+
+    fn image(width: u32, height: u32, data: Vec<u8>, format: TextureFormat) -> Image {
+        Image::new(
+            Extent3d {
+                width,
+                height,
+                depth_or_array_layers: 1,
+            },
+            TextureDimension::D2,
+            data,
+            format,
+        )
+    }
+
+    #[test]
+    fn stacks_images_left_aligned() {
+        let top = image(2, 1, vec![1, 2], TextureFormat::R8Unorm);
+        let bottom = image(1, 2, vec![3, 4], TextureFormat::R8Unorm);
+
+        let stacked = stack_images(&[&top, &bottom]).unwrap();
+
+        assert_eq!(stacked.size(), Vec2::new(2., 3.));
+        assert_eq!(stacked.data, vec![1, 2, 3, 0, 4, 0]);
+    }
+
+    #[test]
+    fn mismatched_formats_fail() {
+        let top = image(1, 1, vec![1], TextureFormat::R8Unorm);
+        let bottom = image(1, 1, vec![1, 2, 3, 4], TextureFormat::Rgba8UnormSrgb);
+
+        assert_eq!(
+            stack_images(&[&top, &bottom]).err(),
+            Some(ResourceError::MismatchedTextureFormats {
+                expected: TextureFormat::R8Unorm,
+                actual: TextureFormat::Rgba8UnormSrgb,
+            })
+        );
+    }
+}
@@ -1,12 +1,34 @@
-use bevy::{asset::LoadState, prelude::*, sprite::TextureAtlas};
-use std::{any::type_name, marker::PhantomData};
-use strum::VariantNames;
+use bevy::{
+    asset::{AssetServerSettings, HandleId, LoadState},
+    ecs::system::{BoxedSystem, System, SystemParam},
+    prelude::*,
+    render::render_resource::Extent3d,
+    sprite::{Rect, TextureAtlas},
+    utils::{HashMap, HashSet},
+};
+use std::{
+    any::type_name,
+    cmp::Reverse,
+    marker::PhantomData,
+    ops::Range,
+    path::{Path, PathBuf},
+    time::Duration,
+};
 
 use crate::{
-    AtlasDefinition, AtlasTextures, AtlasTexturesEvent, CreatedAtlas, DefinitionProcessState,
-    FolderAtlasDefinition, GenericAtlasDefinitions, GetTextureAtlas, GridAtlasDefinition,
-    MultiTextureProcessState, PatchAtlasDefinition, ResourceStatus, SingleTextureProcessState,
-    TypedAtlasDefinition,
+    background, color_key, color_space, detect,
+    folder_cache::{self, FolderAtlasLayout},
+    glob, is_requested, layout, mipmaps, mosaic, padding, premultiply, resample, split_profile,
+    stack, ArrayAtlasDefinition, ArrayProcessState, AtlasDefinition, AtlasKey, AtlasPostProcess,
+    AtlasTextureSettings, AtlasTextures, AtlasTexturesEvent, AutoDetectAtlasDefinition,
+    CreatedAtlas, DefinitionProcessState, DefinitionProgress, FolderAtlasDefinition,
+    FolderManifest, FolderManifestAtlasDefinition, FractionalGridAtlasDefinition,
+    FramesAtlasDefinition, GenericAtlasDefinitions, GetTextureAtlas, GlobAtlasDefinition,
+    GridAtlasDefinition, GridByBoundsAtlasDefinition, GridProcessState, ImageOptions,
+    ManifestProcessState, MosaicAtlasDefinition, MultiTextureProcessState, PagedAtlasDefinition,
+    PatchAtlasDefinition, ResourceError, ResourceStatus, SheetProcessState,
+    SingleTextureProcessState, TexturePackerAtlasDefinition, TexturePackerSheet,
+    TypedAtlasDefinition, UsesAtlasTexture,
 };
 
 #[allow(unused)]
@@ -33,257 +55,1939 @@ where
     }
 }
 
-#[allow(clippy::type_complexity, clippy::too_many_arguments)]
+/// Run condition, true on the frames the [AtlasTextures<T>] are created - including when created
+/// without the definitions that failed, in lenient mode.
+///
+/// # Example:
+/// ```rust
+/// # use bevy::prelude::*;
+/// # use bevy_atlas_loader::*;
+/// use iyes_loopless::prelude::*;
+///
+/// #[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
+/// #[derive(strum::EnumVariantNames, strum::EnumString)]
+/// enum MyAtlasTextures {
+///     Pacman,
+/// }
+///
+/// fn spawn_sprites() {}
+///
+/// App::new().add_system(spawn_sprites.run_if(on_atlas_created::<MyAtlasTextures>));
+/// ```
+pub fn on_atlas_created<T>(atlas_texture_events: EventReader<AtlasTexturesEvent<T>>) -> bool
+where
+    T: Send + Sync + 'static,
+{
+    any_event(atlas_texture_events, |status| {
+        status.is_created() || status.is_partially_created()
+    })
+}
+
+/// Run condition, true on the frames creating the [AtlasTextures<T>] fails - see
+/// [on_atlas_created].
+pub fn on_atlas_failed<T>(atlas_texture_events: EventReader<AtlasTexturesEvent<T>>) -> bool
+where
+    T: Send + Sync + 'static,
+{
+    any_event(atlas_texture_events, ResourceStatus::is_failed)
+}
+
+/// Whether any of the events has a status matching `predicate`, reading all of them so the next
+/// frame only sees new ones.
+fn any_event<T>(
+    mut atlas_texture_events: EventReader<AtlasTexturesEvent<T>>,
+    predicate: impl Fn(&ResourceStatus) -> bool,
+) -> bool
+where
+    T: Send + Sync + 'static,
+{
+    atlas_texture_events
+        .iter()
+        .fold(false, |found, event| found | predicate(&event.status()))
+}
+
+/// Updates the `Handle<TextureAtlas>` of all entities marked with [UsesAtlasTexture<T>], whenever
+/// the [AtlasTextures<T>] are (re)created - be it in full, or partially in lenient mode.
+///
+/// This system is opt-in, and must be added by the app.
+pub fn sync_atlas_sprite_handles<T>(
+    mut atlas_texture_events: EventReader<AtlasTexturesEvent<T>>,
+    atlas_textures: Option<Res<AtlasTextures<T>>>,
+    mut query: Query<(&UsesAtlasTexture<T>, &mut Handle<TextureAtlas>)>,
+) where
+    T: Eq + std::hash::Hash + Send + Sync + 'static,
+{
+    if !atlas_texture_events.iter().any(|event| {
+        let status = event.status();
+        status.is_created() || status.is_partially_created()
+    }) {
+        return;
+    }
+    if let Some(atlas_textures) = atlas_textures {
+        for (UsesAtlasTexture(index), mut handle) in query.iter_mut() {
+            if let Some(atlas_handle) = atlas_textures.try_handle(index) {
+                *handle = atlas_handle;
+            }
+        }
+    }
+}
+
+/// Assets the atlases are created from and into, grouped for systems processing definitions - see
+/// [process_atlas_definitions].
+///
+/// # Example:
+/// ```rust
+/// # use bevy::prelude::*;
+/// # use bevy_atlas_loader::*;
+/// fn count_atlases(assets: AtlasAssets) {
+///     info!("{} atlases", assets.texture_atlases.len());
+/// }
+/// ```
+#[derive(SystemParam)]
+pub struct AtlasAssets<'w, 's> {
+    pub asset_server: Res<'w, AssetServer>,
+    pub asset_server_settings: Res<'w, AssetServerSettings>,
+    pub atlas_definitions: ResMut<'w, Assets<GenericAtlasDefinitions>>,
+    pub texture_atlases: ResMut<'w, Assets<TextureAtlas>>,
+    pub texture_images: ResMut<'w, Assets<Image>>,
+    pub folder_manifests: Res<'w, Assets<FolderManifest>>,
+    pub texture_packer_sheets: Res<'w, Assets<TexturePackerSheet>>,
+    #[system_param(ignore)]
+    _marker: PhantomData<&'s ()>,
+}
+
+#[allow(clippy::too_many_arguments)]
 pub fn process_atlas_definitions<T>(
     definition_handle: Option<ResMut<TypedAtlasDefinition<T>>>,
     mut commands: Commands,
-    asset_server: Res<AssetServer>,
-    mut atlas_definitions: ResMut<Assets<GenericAtlasDefinitions>>,
-    mut texture_atlases: ResMut<Assets<TextureAtlas>>,
-    mut texture_images: ResMut<Assets<Image>>,
+    settings: Res<AtlasTextureSettings<T>>,
+    time: Res<Time>,
+    assets: AtlasAssets,
     atlas_definition_events: EventReader<AssetEvent<GenericAtlasDefinitions>>,
     mut atlas_texture_event: EventWriter<AtlasTexturesEvent<T>>,
+    atlas_textures: Option<Res<AtlasTextures<T>>>,
 ) where
-    T: VariantNames + std::str::FromStr,
-    T: Eq + std::hash::Hash + Send + Sync + 'static,
-    <T as std::str::FromStr>::Err: std::fmt::Debug,
+    T: AtlasKey + Clone + Eq + std::hash::Hash + Send + Sync + 'static,
 {
+    let AtlasAssets {
+        asset_server,
+        asset_server_settings,
+        mut atlas_definitions,
+        mut texture_atlases,
+        mut texture_images,
+        folder_manifests,
+        texture_packer_sheets,
+        ..
+    } = assets;
     if let Some(mut definition_handle) = definition_handle {
+        if definition_handle.reload_pending {
+            definition_handle.reload_pending = false;
+            atlas_texture_event.send(AtlasTexturesEvent::<T>::reloading());
+        }
         match definition_handle.state {
             DefinitionProcessState::Loading => {
-                if match &definition_handle.definitions {
-                    crate::DefinitionsType::Direct(_) => true,
-                    crate::DefinitionsType::Indirect(handle) => {
-                        asset_server.get_load_state(handle) == LoadState::Loaded
-                    }
-                } {
+                if definitions_loaded(
+                    &mut definition_handle.definitions,
+                    &asset_server,
+                    &atlas_definitions,
+                ) {
+                    definition_handle.rebuild_pending = false;
+                    reuse_unchanged_definitions(&mut definition_handle, &mut atlas_definitions);
+                    definition_handle.definition_dirs = if definition_handle.relative_paths {
+                        definition_dirs(
+                            &definition_handle.definitions,
+                            &asset_server,
+                            &atlas_definitions,
+                        )
+                    } else {
+                        HashMap::default()
+                    };
                     debug!(
                         T = type_name::<T>(),
                         "Verifying all AtlasDefinitions<T> are present."
                     );
                     let atlas_definitions = match &definition_handle.definitions {
-                        crate::DefinitionsType::Direct(definitions) => definitions.as_ref(),
-                        crate::DefinitionsType::Indirect(handle) => atlas_definitions
-                            .get(handle.id)
-                            .expect("AtlasDefinitions asset should be present."),
+                        crate::DefinitionsType::Direct(definitions) => Some(definitions.as_ref()),
+                        crate::DefinitionsType::Indirect(handle) => {
+                            atlas_definitions.get(handle.id)
+                        }
+                        crate::DefinitionsType::Directory { merged, .. } => Some(merged.as_ref()),
                     };
-                    definition_handle.state = T::VARIANTS
-                        .iter()
-                        .filter(|&&variant| !atlas_definitions.contains_key(variant))
-                        .fold(DefinitionProcessState::Processing, |_, &variant| {
-                            error!(
+                    let atlas_definitions = match atlas_definitions {
+                        Some(atlas_definitions) => atlas_definitions,
+                        None => {
+                            warn!(
                                 T = type_name::<T>(),
-                                Variant = variant,
-                                variant,
-                                "Missing AtlasDefinition<T> for variant."
+                                "AtlasDefinitions<T> was unloaded while loading."
                             );
-                            let event_writer = &mut atlas_texture_event;
-                            event_writer.send(AtlasTexturesEvent::<T>(
-                                ResourceStatus::Failed,
-                                PhantomData::default(),
+                            atlas_texture_event.send(AtlasTexturesEvent::<T>::failed(
+                                ResourceError::DefinitionsUnloaded,
                             ));
-                            DefinitionProcessState::Failed
-                        });
+                            definition_handle.state = DefinitionProcessState::Failed;
+                            return;
+                        }
+                    };
+                    for key in atlas_definitions
+                        .keys()
+                        .filter(|key| T::from_key(split_profile(key).0).is_none())
+                    {
+                        warn!(
+                            T = type_name::<T>(),
+                            key = key.as_str(),
+                            variants = ?T::variants(),
+                            "AtlasDefinition<T> does not match any variant - it will be ignored."
+                        );
+                    }
+                    let requested = definition_handle.requested.as_ref();
+                    let selected =
+                        atlas_definitions.selected_keys(definition_handle.profile(), requested);
+                    let selected_variants = selected
+                        .iter()
+                        .map(|key| split_profile(key).0)
+                        .collect::<HashSet<_>>();
+                    let missing: Vec<&'static str> = T::variants()
+                        .iter()
+                        .copied()
+                        .filter(|&variant| {
+                            settings.require_all_variants
+                                && !settings.optional_variants.contains(variant)
+                                && is_requested(requested, variant)
+                                && !selected_variants.contains(variant)
+                        })
+                        .collect();
+                    definition_handle.state = if settings.lenient {
+                        definition_handle.failures = missing
+                            .into_iter()
+                            .map(|variant| {
+                                warn!(
+                                    T = type_name::<T>(),
+                                    variant,
+                                    "Missing AtlasDefinition<T> for variant. Leaving it out."
+                                );
+                                (
+                                    variant.to_string(),
+                                    ResourceError::MissingDefinition(variant.to_string()),
+                                )
+                            })
+                            .collect();
+                        DefinitionProcessState::Processing
+                    } else {
+                        missing.into_iter().fold(
+                            DefinitionProcessState::Processing,
+                            |_, variant| {
+                                error!(
+                                    T = type_name::<T>(),
+                                    Variant = variant,
+                                    variant,
+                                    "Missing AtlasDefinition<T> for variant."
+                                );
+                                let error = ResourceError::MissingDefinition(variant.to_string());
+                                definition_handle
+                                    .failures
+                                    .push((variant.to_string(), error.clone()));
+                                let event_writer = &mut atlas_texture_event;
+                                event_writer.send(AtlasTexturesEvent::<T>::failed(error));
+                                DefinitionProcessState::Failed
+                            },
+                        )
+                    };
                 }
             }
             DefinitionProcessState::Processing => {
+                let _span =
+                    debug_span!("process_atlas_definitions", T = type_name::<T>()).entered();
                 let definition_handle = &mut *definition_handle;
                 let atlas_definitions = match definition_handle.definitions {
-                    crate::DefinitionsType::Direct(ref mut definitions) => definitions.as_mut(),
-                    crate::DefinitionsType::Indirect(ref mut handle) => atlas_definitions
-                        .get_mut(handle.id)
-                        .expect("AtlasDefinitions asset should be present."),
+                    crate::DefinitionsType::Direct(ref mut definitions) => {
+                        Some(definitions.as_mut())
+                    }
+                    crate::DefinitionsType::Indirect(ref mut handle) => {
+                        atlas_definitions.get_mut(handle.id)
+                    }
+                    crate::DefinitionsType::Directory { ref mut merged, .. } => {
+                        Some(merged.as_mut())
+                    }
+                };
+                let atlas_definitions = match atlas_definitions {
+                    Some(atlas_definitions) => atlas_definitions,
+                    None => {
+                        warn!(
+                            T = type_name::<T>(),
+                            "AtlasDefinitions<T> was unloaded while processing."
+                        );
+                        atlas_texture_event.send(AtlasTexturesEvent::<T>::failed(
+                            ResourceError::DefinitionsUnloaded,
+                        ));
+                        definition_handle.state = DefinitionProcessState::Failed;
+                        return;
+                    }
                 };
-                definition_handle.state = process_generic_atlas_definitions(
+                let selected = atlas_definitions.selected_keys(
+                    definition_handle.profile.as_deref(),
+                    definition_handle.requested.as_ref(),
+                );
+                // handles of the atlases being reloaded, updated in place
+                let previous_handles = match (&atlas_textures, settings.stable_handles) {
+                    (Some(atlas_textures), true) => atlas_textures
+                        .iter()
+                        .map(|(t, handle)| (t.to_key().into_owned(), handle.id))
+                        .collect(),
+                    _ => HashMap::default(),
+                };
+                let result = process_generic_atlas_definitions(
                     atlas_definitions,
+                    &definition_handle.base_path,
+                    &definition_handle.definition_dirs,
                     &asset_server,
+                    &asset_server_settings,
                     &mut texture_atlases,
                     &mut texture_images,
-                );
+                    &folder_manifests,
+                    &texture_packer_sheets,
+                    settings.retain_source_images,
+                    settings.atlas_scale,
+                    settings.post_process.as_ref(),
+                    &previous_handles,
+                    &selected,
+                    &mut definition_handle.failures,
+                    settings.lenient,
+                    settings.prefetch,
+                )
+                .and_then(|state| match (state, settings.load_timeout) {
+                    (DefinitionProcessState::Processing, Some(load_timeout)) => {
+                        check_load_timeout(
+                            &mut definition_handle.pending_since,
+                            atlas_definitions,
+                            &definition_handle.base_path,
+                            &definition_handle.definition_dirs,
+                            load_timeout,
+                            time.seconds_since_startup(),
+                            &selected,
+                            &mut definition_handle.failures,
+                            settings.lenient,
+                        )?;
+                        Ok(state)
+                    }
+                    _ => Ok(state),
+                });
+                definition_handle.state = match result {
+                    Ok(state) => state,
+                    Err(error) => {
+                        error!(
+                            T = type_name::<T>(),
+                            error = %error,
+                            "Failed creating AtlasTexture<T>."
+                        );
+                        atlas_texture_event.send(AtlasTexturesEvent::<T>::failed(error));
+                        DefinitionProcessState::Failed
+                    }
+                };
                 if definition_handle.state == DefinitionProcessState::Finalizing {
+                    let combined = match combine_atlases(
+                        atlas_definitions,
+                        &selected,
+                        &mut texture_atlases,
+                        &mut texture_images,
+                    ) {
+                        Ok(combined) => combined,
+                        Err(error) => {
+                            error!(
+                                T = type_name::<T>(),
+                                error = %error,
+                                "Failed combining AtlasTexture<T>."
+                            );
+                            atlas_texture_event.send(AtlasTexturesEvent::<T>::failed(error));
+                            definition_handle.state = DefinitionProcessState::Failed;
+                            return;
+                        }
+                    };
                     info!(T = type_name::<T>(), "AtlasTexture<T> created for all T.");
-                    let map = atlas_definitions.iter().map(|(key, definition)| {
-                        (
-                            key.clone(),
-                            definition
-                                .texture_atlas()
-                                .cloned()
-                                .expect("Atlas not created, though all definitions are present."),
-                        )
+                    let map = atlas_definitions.iter().filter_map(|(name, definition)| {
+                        if !definition.is_enabled() || !selected.contains(name) {
+                            return None;
+                        }
+                        let key = T::from_key(split_profile(name).0)?;
+                        // regions of every page, or of the shared atlas for combined definitions
+                        let pages = match combined.get(name) {
+                            Some((shared, regions)) => vec![(shared.clone(), regions.clone())],
+                            None => definition
+                                .pages()
+                                .into_iter()
+                                .map(|page| {
+                                    let len = texture_atlases.get(page).unwrap().textures.len();
+                                    (page.clone(), 0..len)
+                                })
+                                .collect(),
+                        };
+                        // in lenient mode, failed definitions are left out
+                        let (handle, first_regions) = pages.first().cloned()?;
+                        let atlas = texture_atlases.get(&handle).unwrap();
+                        let rects = pages
+                            .iter()
+                            .flat_map(|(page, regions)| {
+                                texture_atlases.get(page).unwrap().textures[regions.clone()]
+                                    .iter()
+                                    .copied()
+                            })
+                            .collect::<Vec<_>>();
+                        let texture = atlas.texture.clone_weak();
+                        let mut indices = definition.indices();
+                        indices.retain(|index_name, index| {
+                            let valid = *index < rects.len();
+                            if !valid {
+                                warn!(
+                                    T = type_name::<T>(),
+                                    key = name.as_str(),
+                                    "Named index {} ({}) is outside the atlas. Ignoring.",
+                                    index_name,
+                                    index
+                                );
+                            }
+                            valid
+                        });
+                        let mut default_index = definition.default_index();
+                        if default_index >= rects.len() {
+                            warn!(
+                                T = type_name::<T>(),
+                                key = name.as_str(),
+                                "Default index {} is outside the atlas. Using 0.",
+                                default_index
+                            );
+                            default_index = 0;
+                        }
+                        Some((
+                            key,
+                            CreatedAtlas {
+                                handle,
+                                texture,
+                                rects,
+                                animations: definition.animations().clone(),
+                                indices,
+                                nine_patch: definition.nine_patch(),
+                                source_images: definition.source_images().to_vec(),
+                                texture_array: definition.texture_array().cloned(),
+                                folder_layout: packed_layout(atlas, &asset_server),
+                                pages: pages
+                                    .iter()
+                                    .map(|(page, regions)| (page.clone(), regions.len()))
+                                    .collect(),
+                                index_offset: first_regions.start,
+                                default_index,
+                            },
+                        ))
                     });
-                    commands.insert_resource(AtlasTextures::<T>(
-                        map.map(|(key, handle)| {
-                            let key = T::from_str(&key).unwrap();
-                            let len = texture_atlases.get(&handle).unwrap().len();
-                            (key, CreatedAtlas { handle, len })
+                    commands.insert_resource(AtlasTextures::<T>(map.collect()));
+                    definition_handle.created = atlas_definitions
+                        .iter()
+                        .filter(|(name, definition)| {
+                            selected.contains(*name) && definition.texture_atlas().is_some()
                         })
-                        .collect(),
-                    ));
+                        .map(|(name, definition)| (name.clone(), definition.clone()))
+                        .collect();
                 }
             }
             DefinitionProcessState::Finalizing => {
                 let mut event_writer = atlas_texture_event;
-                event_writer.send(AtlasTexturesEvent::<T>(
-                    ResourceStatus::Created,
-                    PhantomData::default(),
-                ));
+                if let Some(atlas_textures) = &atlas_textures {
+                    for (index, handle) in atlas_textures.iter() {
+                        event_writer.send(AtlasTexturesEvent::<T>::atlas_created(
+                            index.clone(),
+                            handle,
+                        ));
+                    }
+                }
+                if definition_handle.failures.is_empty() {
+                    event_writer.send(AtlasTexturesEvent::<T>::created());
+                } else {
+                    event_writer.send(AtlasTexturesEvent::<T>::partially_created(
+                        definition_handle.failures.clone(),
+                    ));
+                }
                 definition_handle.state = DefinitionProcessState::Done
             }
-            DefinitionProcessState::Done | DefinitionProcessState::Failed => {
+            DefinitionProcessState::Done | DefinitionProcessState::Failed
+                if definition_handle.rebuild_pending =>
+            {
+                debug!(
+                    T = type_name::<T>(),
+                    "Other AtlasDefinitions<T> selected. Creating them."
+                );
+                definition_handle.state = DefinitionProcessState::Loading;
+                definition_handle.pending_since = None;
+                definition_handle.failures.clear();
+            }
+            DefinitionProcessState::Done
+            | DefinitionProcessState::Failed
+            | DefinitionProcessState::Unloaded => {
                 let mut event_reader = atlas_definition_events;
                 for ev in event_reader.iter() {
                     match ev {
-                        AssetEvent::Created { handle } | AssetEvent::Modified { handle } => {
-                            match &definition_handle.definitions {
-                                crate::DefinitionsType::Indirect(h) if h == handle => {
-                                    warn!(
-                                        T = type_name::<T>(),
-                                        "AtlasDefinitions<T> has changed. Recreating atlas."
-                                    );
-                                    definition_handle.state = DefinitionProcessState::Loading;
-                                }
-                                _ => {}
-                            }
+                        AssetEvent::Created { handle } | AssetEvent::Modified { handle }
+                            if definition_handle.definitions.is_loaded_from(handle) =>
+                        {
+                            warn!(
+                                T = type_name::<T>(),
+                                "AtlasDefinitions<T> has changed. Recreating atlas."
+                            );
+                            definition_handle.state = DefinitionProcessState::Loading;
+                            definition_handle.pending_since = None;
+                            definition_handle.failures.clear();
+                            atlas_texture_event.send(AtlasTexturesEvent::<T>::reloading());
                         }
-                        AssetEvent::Removed { .. } => {
-                            error!("AtlasDefinitions should never be removed!")
+                        AssetEvent::Removed { handle }
+                            if definition_handle.definitions.is_loaded_from(handle)
+                                && !definition_handle.state.is_unloaded() =>
+                        {
+                            warn!(
+                                T = type_name::<T>(),
+                                "AtlasDefinitions<T> was removed. Removing AtlasTextures<T>."
+                            );
+                            if let crate::DefinitionsType::Directory { merged, .. } =
+                                &mut definition_handle.definitions
+                            {
+                                **merged = GenericAtlasDefinitions::default();
+                            }
+                            definition_handle.state = DefinitionProcessState::Unloaded;
+                            definition_handle.created.clear();
+                            commands.remove_resource::<AtlasTextures<T>>();
                         }
+                        _ => {}
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Advances the creation of the atlases of `T` as far as it goes right away, rather than a step
+/// per frame - e.g. from an exclusive system, right after replacing the definitions with
+/// [TypedAtlasDefinition::replace_from_ron].
+///
+/// Images still loading are waited on by [process_atlas_definitions] in the following frames, as
+/// usual. Returns whether the atlases are created.
+///
+/// # Example:
+/// ```rust
+/// # use bevy::prelude::*;
+/// # use bevy_atlas_loader::*;
+/// #[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
+/// #[derive(strum::EnumVariantNames, strum::EnumString)]
+/// enum MyAtlasTextures {
+///     Pacman,
+/// }
+///
+/// fn reload_atlases(world: &mut World) {
+///     if !rebuild_now::<MyAtlasTextures>(world) {
+///         info!("Waiting for images.");
+///     }
+/// }
+/// ```
+pub fn rebuild_now<T>(world: &mut World) -> bool
+where
+    T: AtlasKey + Clone + Eq + std::hash::Hash + Send + Sync + 'static,
+{
+    let mut system = match world.remove_resource::<RebuildSystem<T>>() {
+        Some(RebuildSystem(system, _)) => system,
+        None => {
+            let mut system: BoxedSystem =
+                Box::new(IntoSystem::into_system(process_atlas_definitions::<T>));
+            system.initialize(world);
+            system
+        }
+    };
+    let mut progress = unsettled_progress::<T>(world);
+    while progress.is_some() {
+        system.run((), world);
+        system.apply_buffers(world);
+        let next = unsettled_progress::<T>(world);
+        if next == progress {
+            break;
+        }
+        progress = next;
+    }
+    world.insert_resource(RebuildSystem::<T>(system, PhantomData));
+    world
+        .get_resource::<TypedAtlasDefinition<T>>()
+        .map_or(false, |definition_handle| definition_handle.state.is_done())
+}
+
+/// The [process_atlas_definitions] system run by [rebuild_now], kept across calls - so its event
+/// readers don't read the events of earlier calls again.
+struct RebuildSystem<T>(BoxedSystem, PhantomData<T>);
+
+/// Progress of the definitions of `T`, or `None` once there's nothing left to process - leaving
+/// changes to the definitions asset for [process_atlas_definitions] to pick up.
+fn unsettled_progress<T>(world: &World) -> Option<DefinitionProgress>
+where
+    T: Send + Sync + 'static,
+{
+    let definition_handle = world.get_resource::<TypedAtlasDefinition<T>>()?;
+    if definition_handle.is_settled() {
+        return None;
+    }
+    Some(definition_handle.progress(world.get_resource::<Assets<GenericAtlasDefinitions>>()?))
+}
+
+/// Lets the definitions that didn't change since the current atlases were created reuse their
+/// atlas, e.g. when only some definitions of a hot-reloaded file changed.
+fn reuse_unchanged_definitions<T>(
+    definition_handle: &mut TypedAtlasDefinition<T>,
+    atlas_definitions: &mut Assets<GenericAtlasDefinitions>,
+) {
+    let created = &definition_handle.created;
+    let definitions = match definition_handle.definitions {
+        crate::DefinitionsType::Direct(ref mut definitions) => Some(definitions.as_mut()),
+        crate::DefinitionsType::Indirect(ref handle) => atlas_definitions.get_mut(handle.id),
+        crate::DefinitionsType::Directory { ref mut merged, .. } => Some(merged.as_mut()),
+    };
+    for (key, definition) in definitions
+        .into_iter()
+        .flat_map(|definitions| definitions.iter_mut())
+    {
+        if let Some(previous) = created.get(key) {
+            if definition.reuse_unchanged(previous) {
+                debug!(
+                    key = key.as_str(),
+                    "AtlasDefinition unchanged. Reusing its atlas."
+                );
+            }
+        }
+    }
+}
+
+/// Whether the definitions are loaded, merging the definitions of a directory once they are.
+fn definitions_loaded(
+    definitions: &mut crate::DefinitionsType,
+    asset_server: &AssetServer,
+    atlas_definitions: &Assets<GenericAtlasDefinitions>,
+) -> bool {
+    match definitions {
+        crate::DefinitionsType::Direct(_) => true,
+        crate::DefinitionsType::Indirect(handle) => {
+            asset_server.get_load_state(handle) == LoadState::Loaded
+        }
+        crate::DefinitionsType::Directory { handles, merged } => {
+            if asset_server.get_group_load_state(handles.iter().map(|handle| handle.id))
+                != LoadState::Loaded
+            {
+                return false;
+            }
+            let mut sources = handles
+                .iter()
+                .filter_map(|handle| {
+                    let path = asset_server.get_handle_path(handle)?;
+                    Some((
+                        path.path().display().to_string(),
+                        atlas_definitions.get(handle)?,
+                    ))
+                })
+                .collect::<Vec<_>>();
+            sources.sort_by(|(a, _), (b, _)| a.cmp(b));
+            **merged = GenericAtlasDefinitions::merge(sources);
+            true
+        }
+    }
+}
+
+/// Maps the key of every definition loaded from a file to the directory of that file.
+///
+/// For keys defined by several files, the first file (by path) is used, as when merging.
+fn definition_dirs(
+    definitions: &crate::DefinitionsType,
+    asset_server: &AssetServer,
+    atlas_definitions: &Assets<GenericAtlasDefinitions>,
+) -> HashMap<String, PathBuf> {
+    let handles = match definitions {
+        crate::DefinitionsType::Direct(_) => return HashMap::default(),
+        crate::DefinitionsType::Indirect(handle) => std::slice::from_ref(handle),
+        crate::DefinitionsType::Directory { handles, .. } => handles.as_slice(),
+    };
+    let mut sources = handles
+        .iter()
+        .filter_map(|handle| {
+            let path = asset_server.get_handle_path(handle)?;
+            Some((path.path().to_path_buf(), atlas_definitions.get(handle)?))
+        })
+        .collect::<Vec<_>>();
+    sources.sort_by_key(|(path, _)| path.display().to_string());
+    let mut dirs = HashMap::default();
+    for (path, definitions) in sources {
+        let dir = path.parent().unwrap_or_else(|| Path::new(""));
+        for key in definitions.keys() {
+            dirs.entry(key.clone()).or_insert_with(|| dir.to_path_buf());
+        }
+    }
+    dirs
+}
+
+/// Resolves the base path of the definition `key`, relative to the directory of the file
+/// defining it when known.
+fn resolve_base_path(
+    base_path: &Path,
+    definition_dirs: &HashMap<String, PathBuf>,
+    key: &str,
+) -> PathBuf {
+    match definition_dirs.get(key) {
+        Some(dir) => dir.join(base_path),
+        None => base_path.to_path_buf(),
+    }
+}
+
+/// Fails if the first definition still loading has been so for longer than `load_timeout`.
+///
+/// The definition is added to the `failures`, and only left out in `lenient` mode.
+#[allow(clippy::too_many_arguments)]
+fn check_load_timeout(
+    pending_since: &mut Option<(String, f64)>,
+    atlas_definitions: &GenericAtlasDefinitions,
+    base_path: &Path,
+    definition_dirs: &HashMap<String, PathBuf>,
+    load_timeout: Duration,
+    now: f64,
+    selected: &HashSet<String>,
+    failures: &mut Vec<(String, ResourceError)>,
+    lenient: bool,
+) -> Result<(), ResourceError> {
+    let has_failed = |key: &String| failures.iter().any(|(failed, _)| failed == key);
+    let pending = atlas_definitions
+        .iter()
+        .find(|(key, cfg)| cfg.is_pending() && selected.contains(key) && !has_failed(key));
+    if let Some((key, cfg)) = pending {
+        match pending_since {
+            Some((pending_key, since)) if pending_key == key => {
+                if Duration::from_secs_f64(now - *since) > load_timeout {
+                    let error = ResourceError::LoadTimeout(
+                        resolve_base_path(base_path, definition_dirs, key).join(cfg.source_path()),
+                    );
+                    failures.push((key.clone(), error.clone()));
+                    if !lenient {
+                        return Err(error);
                     }
+                    warn!(key = key.as_str(), error = %error, "Leaving out AtlasDefinition.");
+                    *pending_since = None;
                 }
             }
+            _ => *pending_since = Some((key.clone(), now)),
         }
     }
+    Ok(())
 }
 
+#[allow(clippy::too_many_arguments)]
 fn process_generic_atlas_definitions(
     atlas_definitions: &mut GenericAtlasDefinitions,
+    base_path: &Path,
+    definition_dirs: &HashMap<String, PathBuf>,
     asset_server: &AssetServer,
+    asset_server_settings: &AssetServerSettings,
     texture_atlases: &mut Assets<TextureAtlas>,
     texture_images: &mut Assets<Image>,
-) -> DefinitionProcessState {
-    if atlas_definitions.iter_mut().all(|(_key, cfg)| match cfg {
-        AtlasDefinition::Grid(grid_definition) => {
-            process_grid_atlas_definition(grid_definition, asset_server, texture_atlases)
+    folder_manifests: &Assets<FolderManifest>,
+    texture_packer_sheets: &Assets<TexturePackerSheet>,
+    retain_source_images: bool,
+    atlas_scale: f32,
+    post_process: Option<&AtlasPostProcess>,
+    previous_handles: &HashMap<String, HandleId>,
+    selected: &HashSet<String>,
+    failures: &mut Vec<(String, ResourceError)>,
+    lenient: bool,
+    prefetch: bool,
+) -> Result<DefinitionProcessState, ResourceError> {
+    let mut definitions = atlas_definitions
+        .iter_mut()
+        .filter(|(key, cfg)| cfg.is_enabled() && selected.contains(*key))
+        .collect::<Vec<_>>();
+    // unless prefetching, definitions are loaded one at a time, so the higher priorities are
+    // ready first
+    definitions.sort_by_key(|(_, cfg)| Reverse(cfg.priority()));
+    let mut waiting = false;
+    for (key, cfg) in definitions {
+        if failures.iter().any(|(failed, _)| failed == key) {
+            continue;
         }
-        AtlasDefinition::Manual(patch_definition) => {
-            process_patch_atlas_definition(patch_definition, asset_server, texture_atlases)
+        let _span =
+            debug_span!("atlas_definition", key = key.as_str(), kind = %cfg.kind()).entered();
+        let base_path = &resolve_base_path(base_path, definition_dirs, key);
+        let texture_atlases = &mut AtlasSink {
+            texture_atlases: &mut *texture_atlases,
+            post_process,
+            key,
+            previous_handle: previous_handles.get(key).copied(),
+        };
+        let created = match cfg {
+            AtlasDefinition::Grid(grid_definition) => process_grid_atlas_definition(
+                grid_definition,
+                base_path,
+                asset_server,
+                texture_atlases,
+                texture_images,
+                retain_source_images,
+                atlas_scale,
+            ),
+            AtlasDefinition::GridByBounds(bounds_definition) => {
+                process_grid_by_bounds_atlas_definition(
+                    bounds_definition,
+                    base_path,
+                    asset_server,
+                    texture_atlases,
+                    texture_images,
+                    retain_source_images,
+                    atlas_scale,
+                )
+            }
+            AtlasDefinition::Manual(patch_definition) => process_patch_atlas_definition(
+                patch_definition,
+                base_path,
+                asset_server,
+                texture_atlases,
+                texture_images,
+                retain_source_images,
+                atlas_scale,
+            ),
+            AtlasDefinition::Frames(frames_definition) => process_frames_atlas_definition(
+                frames_definition,
+                base_path,
+                asset_server,
+                texture_atlases,
+                texture_images,
+                retain_source_images,
+                atlas_scale,
+            ),
+            AtlasDefinition::Folder(folder_definition) => process_folder_atlas_definition(
+                folder_definition,
+                base_path,
+                asset_server,
+                asset_server_settings,
+                texture_atlases,
+                texture_images,
+                retain_source_images,
+            ),
+            AtlasDefinition::FolderManifest(manifest_definition) => {
+                process_folder_manifest_atlas_definition(
+                    manifest_definition,
+                    base_path,
+                    asset_server,
+                    texture_atlases,
+                    texture_images,
+                    folder_manifests,
+                    retain_source_images,
+                )
+            }
+            AtlasDefinition::TexturePacker(texture_packer_definition) => {
+                process_texture_packer_atlas_definition(
+                    texture_packer_definition,
+                    base_path,
+                    asset_server,
+                    texture_atlases,
+                    texture_images,
+                    texture_packer_sheets,
+                    retain_source_images,
+                )
+            }
+            AtlasDefinition::Array(array_definition) => process_array_atlas_definition(
+                array_definition,
+                base_path,
+                asset_server,
+                texture_atlases,
+                texture_images,
+                retain_source_images,
+            ),
+            AtlasDefinition::Paged(paged_definition) => process_paged_atlas_definition(
+                paged_definition,
+                base_path,
+                asset_server,
+                texture_atlases,
+                texture_images,
+                retain_source_images,
+                atlas_scale,
+            ),
+            AtlasDefinition::Glob(glob_definition) => process_glob_atlas_definition(
+                glob_definition,
+                base_path,
+                asset_server,
+                asset_server_settings,
+                texture_atlases,
+                texture_images,
+                retain_source_images,
+            ),
+            AtlasDefinition::AutoDetect(auto_detect_definition) => {
+                process_auto_detect_atlas_definition(
+                    auto_detect_definition,
+                    base_path,
+                    asset_server,
+                    texture_atlases,
+                    texture_images,
+                    retain_source_images,
+                )
+            }
+            AtlasDefinition::Mosaic(mosaic_definition) => process_mosaic_atlas_definition(
+                mosaic_definition,
+                base_path,
+                asset_server,
+                texture_atlases,
+                texture_images,
+                retain_source_images,
+            ),
+            AtlasDefinition::FractionalGrid(grid_definition) => {
+                process_fractional_grid_atlas_definition(
+                    grid_definition,
+                    base_path,
+                    asset_server,
+                    texture_atlases,
+                    texture_images,
+                    retain_source_images,
+                    atlas_scale,
+                )
+            }
+        };
+        let created = match created {
+            Ok(created) => created,
+            Err(error) => {
+                failures.push((key.clone(), error.clone()));
+                if !lenient {
+                    return Err(error);
+                }
+                warn!(error = %error, "Leaving out AtlasDefinition.");
+                continue;
+            }
+        };
+        if !created {
+            trace!("Waiting for the assets of the definition.");
+            if !prefetch {
+                return Ok(DefinitionProcessState::Processing);
+            }
+            waiting = true;
         }
-        AtlasDefinition::Folder(folder_definition) => process_folder_atlas_definition(
-            folder_definition,
-            asset_server,
-            texture_atlases,
-            texture_images,
-        ),
-    }) {
-        DefinitionProcessState::Finalizing
-    } else {
+    }
+    Ok(if waiting {
         DefinitionProcessState::Processing
+    } else {
+        DefinitionProcessState::Finalizing
+    })
+}
+
+/// Packs the atlases of the grids sharing a `combine` group into a single atlas per group, by
+/// stacking their images in the order of their keys.
+///
+/// Returns the shared atlas of every combined definition, along with the range of its regions.
+fn combine_atlases(
+    atlas_definitions: &GenericAtlasDefinitions,
+    selected: &HashSet<String>,
+    texture_atlases: &mut Assets<TextureAtlas>,
+    texture_images: &mut Assets<Image>,
+) -> Result<HashMap<String, (Handle<TextureAtlas>, Range<usize>)>, ResourceError> {
+    let mut groups = HashMap::<&str, Vec<(&String, &Handle<TextureAtlas>)>>::default();
+    for (name, definition) in atlas_definitions.iter() {
+        if !definition.is_enabled() || !selected.contains(name) {
+            continue;
+        }
+        // in lenient mode, failed definitions are left out
+        if let (Some(group), Some(handle)) = (definition.combine(), definition.texture_atlas()) {
+            groups.entry(group).or_default().push((name, handle));
+        }
+    }
+
+    let mut combined = HashMap::default();
+    for (group, mut members) in groups {
+        members.sort_by_key(|(name, _)| *name);
+        let atlases = members
+            .iter()
+            .map(|(_, handle)| texture_atlases.get(*handle).unwrap())
+            .collect::<Vec<_>>();
+        let images = atlases
+            .iter()
+            .map(|atlas| {
+                texture_images
+                    .get(&atlas.texture)
+                    .expect("Image asset should be present.")
+            })
+            .collect::<Vec<_>>();
+        let stacked = stack::stack_images(&images)?;
+        let mut rects = Vec::new();
+        let mut regions = Vec::new();
+        let mut top = 0.;
+        for (atlas, image) in atlases.iter().zip(&images) {
+            let offset = Vec2::new(0., top);
+            let first = rects.len();
+            rects.extend(atlas.textures.iter().map(|rect| Rect {
+                min: rect.min + offset,
+                max: rect.max + offset,
+            }));
+            regions.push(first..rects.len());
+            top += image.size().y;
+        }
+        debug!(
+            group,
+            sheets = members.len(),
+            "Combined grids into a single atlas."
+        );
+        let image_size = stacked.size().as_uvec2();
+        let atlas = atlas_from_rects(texture_images.add(stacked), image_size, rects);
+        let handle = texture_atlases.add(atlas);
+        for ((name, _), regions) in members.into_iter().zip(regions) {
+            combined.insert(name.clone(), (handle.clone(), regions));
+        }
+    }
+    Ok(combined)
+}
+
+/// Adds the atlases created for the definition `key`, post-processing them first.
+///
+/// With a `previous_handle`, the atlas being reloaded is updated in place instead.
+struct AtlasSink<'a> {
+    texture_atlases: &'a mut Assets<TextureAtlas>,
+    post_process: Option<&'a AtlasPostProcess>,
+    key: &'a str,
+    /// Handle of the atlas being reloaded, if it should be updated in place.
+    previous_handle: Option<HandleId>,
+}
+
+impl AtlasSink<'_> {
+    fn add(&mut self, mut atlas: TextureAtlas) -> Handle<TextureAtlas> {
+        if let Some(post_process) = self.post_process {
+            post_process.apply(self.key, &mut atlas);
+        }
+        match self.previous_handle {
+            Some(handle) => self.texture_atlases.set(handle, atlas),
+            None => self.texture_atlases.add(atlas),
+        }
     }
 }
 
 fn process_grid_atlas_definition(
     grid_definition: &mut GridAtlasDefinition,
+    base_path: &Path,
     asset_server: &AssetServer,
-    texture_atlases: &mut Assets<TextureAtlas>,
-) -> bool {
+    texture_atlases: &mut AtlasSink<'_>,
+    texture_images: &mut Assets<Image>,
+    retain_source_images: bool,
+    atlas_scale: f32,
+) -> Result<bool, ResourceError> {
+    match &grid_definition.state {
+        GridProcessState::None => {
+            let texture = asset_server.load_untyped(base_path.join(&grid_definition.texture));
+            grid_definition.state = if grid_definition.textures.is_empty() {
+                GridProcessState::LoadingTexture(texture)
+            } else {
+                GridProcessState::LoadingTextures(
+                    std::iter::once(texture)
+                        .chain(
+                            grid_definition
+                                .textures
+                                .iter()
+                                .map(|texture| asset_server.load_untyped(base_path.join(texture))),
+                        )
+                        .collect(),
+                )
+            };
+            Ok(false)
+        }
+        GridProcessState::LoadingTexture(handle) => {
+            let mut image = handle.clone().typed::<Image>();
+            if let Some(image_size) = loaded_image_size(&image, texture_images) {
+                if let Some(color_key) = grid_definition.color_key {
+                    image = apply_color_key(&image, color_key, texture_images)?;
+                }
+                let rects = layout::grid_rects(
+                    grid_definition,
+                    layout::unscaled_size(image_size, atlas_scale),
+                )?;
+                let rects = layout::scale_rects(rects, atlas_scale);
+                let mut atlas = atlas_from_rects(image, image_size, rects);
+                if let Some(scale) = grid_definition.scale {
+                    atlas = scale_atlas(atlas, scale, texture_images);
+                }
+                finish_image(&grid_definition.image, &mut atlas.texture, texture_images)?;
+                grid_definition.state = GridProcessState::AtlasCreated(
+                    texture_atlases.add(atlas),
+                    source_images([handle], retain_source_images),
+                );
+            }
+            Ok(false)
+        }
+        GridProcessState::LoadingTextures(handles) => {
+            let images = handles
+                .iter()
+                .map(|handle| handle.clone().typed::<Image>())
+                .collect::<Vec<_>>();
+            if images
+                .iter()
+                .any(|image| loaded_image_size(image, texture_images).is_none())
+            {
+                return Ok(false);
+            }
+            let sheets = images
+                .iter()
+                .map(|image| {
+                    texture_images
+                        .get(image)
+                        .expect("Image asset should be present.")
+                })
+                .collect::<Vec<_>>();
+            let sheet_sizes = sheets
+                .iter()
+                .map(|sheet| layout::unscaled_size(sheet.size().as_uvec2(), atlas_scale))
+                .collect::<Vec<_>>();
+            let rects = layout::stacked_grid_rects(grid_definition, &sheet_sizes)?;
+            let rects = layout::scale_rects(rects, atlas_scale);
+            let mut stacked = stack::stack_images(&sheets)?;
+            if let Some(color_key) = grid_definition.color_key {
+                color_key::apply_color_key(&mut stacked, color_key)?;
+            }
+            let image_size = stacked.size().as_uvec2();
+            let mut atlas = atlas_from_rects(texture_images.add(stacked), image_size, rects);
+            if let Some(scale) = grid_definition.scale {
+                atlas = scale_atlas(atlas, scale, texture_images);
+            }
+            finish_image(&grid_definition.image, &mut atlas.texture, texture_images)?;
+            grid_definition.state = GridProcessState::AtlasCreated(
+                texture_atlases.add(atlas),
+                source_images(handles, retain_source_images),
+            );
+            Ok(false)
+        }
+        GridProcessState::AtlasCreated(..) => Ok(true),
+    }
+}
+
+fn process_grid_by_bounds_atlas_definition(
+    bounds_definition: &mut GridByBoundsAtlasDefinition,
+    base_path: &Path,
+    asset_server: &AssetServer,
+    texture_atlases: &mut AtlasSink<'_>,
+    texture_images: &mut Assets<Image>,
+    retain_source_images: bool,
+    atlas_scale: f32,
+) -> Result<bool, ResourceError> {
+    match &bounds_definition.state {
+        SingleTextureProcessState::None => {
+            bounds_definition.state = SingleTextureProcessState::LoadingTexture(
+                asset_server.load_untyped(base_path.join(&bounds_definition.texture)),
+            );
+            Ok(false)
+        }
+        SingleTextureProcessState::LoadingTexture(handle) => {
+            let image = handle.clone().typed::<Image>();
+            if let Some(image_size) = loaded_image_size(&image, texture_images) {
+                let rects = layout::grid_by_bounds_rects(
+                    bounds_definition,
+                    layout::unscaled_size(image_size, atlas_scale),
+                )?;
+                let rects = layout::scale_rects(rects, atlas_scale);
+                let mut atlas = atlas_from_rects(image, image_size, rects);
+                finish_image(&bounds_definition.image, &mut atlas.texture, texture_images)?;
+                bounds_definition.state = SingleTextureProcessState::AtlasCreated(
+                    texture_atlases.add(atlas),
+                    source_images([handle], retain_source_images),
+                );
+            }
+            Ok(false)
+        }
+        SingleTextureProcessState::AtlasCreated(..) => Ok(true),
+    }
+}
+
+fn process_fractional_grid_atlas_definition(
+    grid_definition: &mut FractionalGridAtlasDefinition,
+    base_path: &Path,
+    asset_server: &AssetServer,
+    texture_atlases: &mut AtlasSink<'_>,
+    texture_images: &mut Assets<Image>,
+    retain_source_images: bool,
+    atlas_scale: f32,
+) -> Result<bool, ResourceError> {
     match &grid_definition.state {
         SingleTextureProcessState::None => {
             grid_definition.state = SingleTextureProcessState::LoadingTexture(
-                asset_server.load_untyped(grid_definition.texture.as_path()),
+                asset_server.load_untyped(base_path.join(&grid_definition.texture)),
             );
-            false
+            Ok(false)
         }
         SingleTextureProcessState::LoadingTexture(handle) => {
             let image = handle.clone().typed::<Image>();
-            if asset_server.get_load_state(&image) == LoadState::Loaded {
-                let atlas = TextureAtlas::from_grid_with_padding(
-                    image,
-                    Vec2::new(
-                        grid_definition.tile_size.0 as f32,
-                        grid_definition.tile_size.1 as f32,
-                    ),
-                    grid_definition.columns,
-                    grid_definition.rows,
-                    match grid_definition.padding {
-                        Some((x, y)) => Vec2::new(x as f32, y as f32),
-                        None => Vec2::ZERO,
-                    },
+            if let Some(image_size) = loaded_image_size(&image, texture_images) {
+                // the regions are scaled as they are, keeping them between pixels
+                let rects = layout::fractional_grid_rects(
+                    grid_definition,
+                    image_size.as_vec2() / atlas_scale,
+                )?
+                .into_iter()
+                .map(|rect| Rect {
+                    min: rect.min * atlas_scale,
+                    max: rect.max * atlas_scale,
+                })
+                .collect();
+                let mut atlas = atlas_from_rects(image, image_size, rects);
+                finish_image(&grid_definition.image, &mut atlas.texture, texture_images)?;
+                grid_definition.state = SingleTextureProcessState::AtlasCreated(
+                    texture_atlases.add(atlas),
+                    source_images([handle], retain_source_images),
                 );
-                grid_definition.state =
-                    SingleTextureProcessState::AtlasCreated(texture_atlases.add(atlas));
             }
-            false
+            Ok(false)
         }
-        SingleTextureProcessState::AtlasCreated(_) => true,
+        SingleTextureProcessState::AtlasCreated(..) => Ok(true),
     }
 }
 
 fn process_patch_atlas_definition(
     patch_definition: &mut PatchAtlasDefinition,
+    base_path: &Path,
     asset_server: &AssetServer,
-    texture_atlases: &mut Assets<TextureAtlas>,
-) -> bool {
+    texture_atlases: &mut AtlasSink<'_>,
+    texture_images: &mut Assets<Image>,
+    retain_source_images: bool,
+    atlas_scale: f32,
+) -> Result<bool, ResourceError> {
     match &patch_definition.state {
         SingleTextureProcessState::None => {
             patch_definition.state = SingleTextureProcessState::LoadingTexture(
-                asset_server.load_untyped(patch_definition.texture.as_path()),
+                asset_server.load_untyped(base_path.join(&patch_definition.texture)),
+            );
+            Ok(false)
+        }
+        SingleTextureProcessState::LoadingTexture(handle) => {
+            let mut image = handle.clone().typed::<Image>();
+            if let Some(image_size) = loaded_image_size(&image, texture_images) {
+                if let Some(color_key) = patch_definition.color_key {
+                    image = apply_color_key(&image, color_key, texture_images)?;
+                }
+                let rects = layout::patch_rects(
+                    patch_definition,
+                    layout::unscaled_size(image_size, atlas_scale),
+                )?;
+                let rects = layout::scale_rects(rects, atlas_scale);
+                let mut atlas = atlas_from_rects(image, image_size, rects);
+                if let Some(scale) = patch_definition.scale {
+                    atlas = scale_atlas(atlas, scale, texture_images);
+                }
+                finish_image(&patch_definition.image, &mut atlas.texture, texture_images)?;
+                patch_definition.state = SingleTextureProcessState::AtlasCreated(
+                    texture_atlases.add(atlas),
+                    source_images([handle], retain_source_images),
+                );
+            }
+            Ok(false)
+        }
+        SingleTextureProcessState::AtlasCreated(..) => Ok(true),
+    }
+}
+
+fn process_frames_atlas_definition(
+    frames_definition: &mut FramesAtlasDefinition,
+    base_path: &Path,
+    asset_server: &AssetServer,
+    texture_atlases: &mut AtlasSink<'_>,
+    texture_images: &mut Assets<Image>,
+    retain_source_images: bool,
+    atlas_scale: f32,
+) -> Result<bool, ResourceError> {
+    match &frames_definition.state {
+        SingleTextureProcessState::None => {
+            frames_definition.state = SingleTextureProcessState::LoadingTexture(
+                asset_server.load_untyped(base_path.join(&frames_definition.texture)),
             );
-            false
+            Ok(false)
         }
         SingleTextureProcessState::LoadingTexture(handle) => {
             let image = handle.clone().typed::<Image>();
-            if asset_server.get_load_state(&image) == LoadState::Loaded {
-                let mut atlas = TextureAtlas::new_empty(
-                    image,
-                    Vec2::new(
-                        patch_definition.width as f32,
-                        patch_definition.height as f32,
-                    ),
+            if let Some(image_size) = loaded_image_size(&image, texture_images) {
+                let rects = layout::frames_rects(
+                    frames_definition,
+                    layout::unscaled_size(image_size, atlas_scale),
+                )?;
+                let mut atlas =
+                    atlas_from_rects(image, image_size, layout::scale_rects(rects, atlas_scale));
+                finish_image(&frames_definition.image, &mut atlas.texture, texture_images)?;
+                frames_definition.state = SingleTextureProcessState::AtlasCreated(
+                    texture_atlases.add(atlas),
+                    source_images([handle], retain_source_images),
                 );
-                for &(x, y) in patch_definition.positions.iter() {
-                    atlas.add_texture(bevy::sprite::Rect {
-                        min: Vec2::new(x as f32, y as f32),
-                        max: Vec2::new(
-                            (x + patch_definition.width) as f32,
-                            (y + patch_definition.height) as f32,
-                        ),
-                    });
-                }
-                patch_definition.state =
-                    SingleTextureProcessState::AtlasCreated(texture_atlases.add(atlas));
             }
-            false
+            Ok(false)
         }
-        SingleTextureProcessState::AtlasCreated(_) => true,
+        SingleTextureProcessState::AtlasCreated(..) => Ok(true),
     }
 }
 
 fn process_folder_atlas_definition(
     folder_definition: &mut FolderAtlasDefinition,
+    base_path: &Path,
     asset_server: &AssetServer,
-    texture_atlases: &mut Assets<TextureAtlas>,
+    asset_server_settings: &AssetServerSettings,
+    texture_atlases: &mut AtlasSink<'_>,
     texture_images: &mut Assets<Image>,
-) -> bool {
+    retain_source_images: bool,
+) -> Result<bool, ResourceError> {
     match &folder_definition.state {
         MultiTextureProcessState::None => {
-            folder_definition.state = MultiTextureProcessState::LoadingTextures(
-                asset_server
-                    .load_folder(folder_definition.path.as_path())
-                    .expect("path must exist and be a folder"),
+            let path = base_path.join(&folder_definition.path);
+            let handles = asset_server
+                .load_folder(&path)
+                .map_err(|_| ResourceError::LoadFailed(path.clone()))?;
+            if handles.is_empty() {
+                return Err(ResourceError::EmptyFolder(path));
+            }
+            folder_definition.state = MultiTextureProcessState::LoadingTextures(handles);
+            Ok(false)
+        }
+        MultiTextureProcessState::LoadingTextures(handles) => {
+            if !textures_loaded(handles, asset_server, texture_images)? {
+                return Ok(false);
+            }
+            let cache = folder_definition.cache.as_deref();
+            let cached_atlas = cache
+                .and_then(|cache| folder_cache::load(asset_server_settings, cache))
+                .and_then(|layout| layout.create_atlas(handles, asset_server, texture_images));
+            let mut atlas = match cached_atlas {
+                Some(atlas) => {
+                    debug!(path = ?folder_definition.path, "Folder atlas created from cache.");
+                    atlas
+                }
+                None => {
+                    let atlas = pack_textures(
+                        handles,
+                        folder_definition.packing.output_padding,
+                        texture_images,
+                    )?;
+                    if let Some(cache) = cache {
+                        if let Some(layout) = FolderAtlasLayout::from_atlas(&atlas, asset_server) {
+                            folder_cache::save(asset_server_settings, cache, &layout);
+                        }
+                    }
+                    atlas
+                }
+            };
+            if let Some(background) = folder_definition.packing.background {
+                fill_background(&atlas, background, texture_images)?;
+            }
+            finish_image(&folder_definition.image, &mut atlas.texture, texture_images)?;
+            folder_definition.state = MultiTextureProcessState::AtlasCreated(
+                texture_atlases.add(atlas),
+                source_images(handles, retain_source_images),
+            );
+            Ok(false)
+        }
+        MultiTextureProcessState::AtlasCreated(..) => Ok(true),
+    }
+}
+
+fn process_glob_atlas_definition(
+    glob_definition: &mut GlobAtlasDefinition,
+    base_path: &Path,
+    asset_server: &AssetServer,
+    asset_server_settings: &AssetServerSettings,
+    texture_atlases: &mut AtlasSink<'_>,
+    texture_images: &mut Assets<Image>,
+    retain_source_images: bool,
+) -> Result<bool, ResourceError> {
+    match &glob_definition.state {
+        MultiTextureProcessState::None => {
+            let pattern = base_path.join(&glob_definition.pattern);
+            let paths = glob::expand(asset_server_settings, &pattern)?;
+            if paths.is_empty() {
+                return Err(ResourceError::EmptyFolder(pattern));
+            }
+            glob_definition.state = MultiTextureProcessState::LoadingTextures(
+                paths
+                    .iter()
+                    .map(|path| asset_server.load_untyped(path.as_path()))
+                    .collect(),
             );
-            false
+            Ok(false)
         }
         MultiTextureProcessState::LoadingTextures(handles) => {
-            let mut texture_atlas_builder = TextureAtlasBuilder::default();
-            for handle in handles {
-                let texture = texture_images.get(handle.id).unwrap();
-                texture_atlas_builder.add_texture(handle.clone().typed::<Image>(), texture);
+            if !textures_loaded(handles, asset_server, texture_images)? {
+                return Ok(false);
+            }
+            let mut atlas = pack_textures(
+                handles,
+                glob_definition.packing.output_padding,
+                texture_images,
+            )?;
+            if let Some(background) = glob_definition.packing.background {
+                fill_background(&atlas, background, texture_images)?;
+            }
+            finish_image(&glob_definition.image, &mut atlas.texture, texture_images)?;
+            glob_definition.state = MultiTextureProcessState::AtlasCreated(
+                texture_atlases.add(atlas),
+                source_images(handles, retain_source_images),
+            );
+            Ok(false)
+        }
+        MultiTextureProcessState::AtlasCreated(..) => Ok(true),
+    }
+}
+
+fn process_mosaic_atlas_definition(
+    mosaic_definition: &mut MosaicAtlasDefinition,
+    base_path: &Path,
+    asset_server: &AssetServer,
+    texture_atlases: &mut AtlasSink<'_>,
+    texture_images: &mut Assets<Image>,
+    retain_source_images: bool,
+) -> Result<bool, ResourceError> {
+    match &mosaic_definition.state {
+        MultiTextureProcessState::None => {
+            if mosaic_definition.cells.is_empty() {
+                return Err(ResourceError::AtlasBuildFailed(String::from(
+                    "mosaic without cells",
+                )));
+            }
+            // failing before loading any of the images
+            if let Some(cell) =
+                mosaic::duplicate_cell(mosaic_definition.cells.iter().map(|(cell, _)| *cell))
+            {
+                return Err(ResourceError::DuplicateCell(cell));
+            }
+            mosaic_definition.state = MultiTextureProcessState::LoadingTextures(
+                mosaic_definition
+                    .cells
+                    .iter()
+                    .map(|(_, image)| asset_server.load_untyped(base_path.join(image)))
+                    .collect(),
+            );
+            Ok(false)
+        }
+        MultiTextureProcessState::LoadingTextures(handles) => {
+            if !textures_loaded(handles, asset_server, texture_images)? {
+                return Ok(false);
+            }
+            let cells = mosaic_definition
+                .cells
+                .iter()
+                .zip(handles)
+                .map(|((cell, _), handle)| {
+                    let image = texture_images
+                        .get(handle.id)
+                        .expect("Image asset should be present.");
+                    (*cell, image)
+                })
+                .collect::<Vec<_>>();
+            let (image, rects) = mosaic::mosaic_images(&cells)?;
+            let image_size = image.size().as_uvec2();
+            let mut atlas = atlas_from_rects(texture_images.add(image), image_size, rects);
+            finish_image(&mosaic_definition.image, &mut atlas.texture, texture_images)?;
+            mosaic_definition.state = MultiTextureProcessState::AtlasCreated(
+                texture_atlases.add(atlas),
+                source_images(handles, retain_source_images),
+            );
+            Ok(false)
+        }
+        MultiTextureProcessState::AtlasCreated(..) => Ok(true),
+    }
+}
+
+fn process_folder_manifest_atlas_definition(
+    manifest_definition: &mut FolderManifestAtlasDefinition,
+    base_path: &Path,
+    asset_server: &AssetServer,
+    texture_atlases: &mut AtlasSink<'_>,
+    texture_images: &mut Assets<Image>,
+    folder_manifests: &Assets<FolderManifest>,
+    retain_source_images: bool,
+) -> Result<bool, ResourceError> {
+    match &manifest_definition.state {
+        ManifestProcessState::None => {
+            manifest_definition.state = ManifestProcessState::LoadingManifest(
+                asset_server.load(base_path.join(&manifest_definition.manifest)),
+            );
+            Ok(false)
+        }
+        ManifestProcessState::LoadingManifest(manifest_handle) => {
+            match asset_server.get_load_state(manifest_handle) {
+                LoadState::Loaded => {
+                    let manifest = folder_manifests
+                        .get(manifest_handle)
+                        .expect("FolderManifest asset should be present.");
+                    manifest_definition.state = ManifestProcessState::LoadingTextures(
+                        manifest
+                            .paths
+                            .iter()
+                            .map(|path| asset_server.load_untyped(path.as_path()))
+                            .collect(),
+                    );
+                }
+                LoadState::Failed => {
+                    return Err(ResourceError::LoadFailed(
+                        base_path.join(&manifest_definition.manifest),
+                    ));
+                }
+                _ => {}
+            }
+            Ok(false)
+        }
+        ManifestProcessState::LoadingTextures(handles) => {
+            if !textures_loaded(handles, asset_server, texture_images)? {
+                return Ok(false);
+            }
+            let mut atlas = pack_textures(
+                handles,
+                manifest_definition.packing.output_padding,
+                texture_images,
+            )?;
+            if let Some(background) = manifest_definition.packing.background {
+                fill_background(&atlas, background, texture_images)?;
+            }
+            finish_image(
+                &manifest_definition.image,
+                &mut atlas.texture,
+                texture_images,
+            )?;
+            manifest_definition.state = ManifestProcessState::AtlasCreated(
+                texture_atlases.add(atlas),
+                source_images(handles, retain_source_images),
+            );
+            Ok(false)
+        }
+        ManifestProcessState::AtlasCreated(..) => Ok(true),
+    }
+}
+
+fn process_texture_packer_atlas_definition(
+    texture_packer_definition: &mut TexturePackerAtlasDefinition,
+    base_path: &Path,
+    asset_server: &AssetServer,
+    texture_atlases: &mut AtlasSink<'_>,
+    texture_images: &mut Assets<Image>,
+    texture_packer_sheets: &Assets<TexturePackerSheet>,
+    retain_source_images: bool,
+) -> Result<bool, ResourceError> {
+    match &texture_packer_definition.state {
+        SheetProcessState::None => {
+            texture_packer_definition.state = SheetProcessState::LoadingSheet(
+                asset_server.load(base_path.join(&texture_packer_definition.sheet)),
+            );
+            Ok(false)
+        }
+        SheetProcessState::LoadingSheet(sheet_handle) => {
+            match asset_server.get_load_state(sheet_handle) {
+                LoadState::Loaded => {
+                    let sheet = texture_packer_sheets
+                        .get(sheet_handle)
+                        .expect("TexturePackerSheet asset should be present.");
+                    texture_packer_definition.state = SheetProcessState::LoadingTexture(
+                        sheet_handle.clone(),
+                        asset_server.load_untyped(sheet.image.as_path()),
+                    );
+                }
+                LoadState::Failed => {
+                    return Err(ResourceError::LoadFailed(
+                        base_path.join(&texture_packer_definition.sheet),
+                    ));
+                }
+                _ => {}
+            }
+            Ok(false)
+        }
+        SheetProcessState::LoadingTexture(sheet_handle, handle) => {
+            let image = handle.clone().typed::<Image>();
+            if let Some(image_size) = loaded_image_size(&image, texture_images) {
+                let sheet = texture_packer_sheets
+                    .get(sheet_handle)
+                    .expect("TexturePackerSheet asset should be present.");
+                let indices = sheet
+                    .frames
+                    .iter()
+                    .enumerate()
+                    .map(|(index, frame)| (frame.name.clone(), index))
+                    .collect();
+                let mut atlas = atlas_from_rects(image, image_size, sheet.rects(image_size)?);
+                if let Some(scale) = texture_packer_definition.scale {
+                    atlas = scale_atlas(atlas, scale, texture_images);
+                }
+                finish_image(
+                    &texture_packer_definition.image,
+                    &mut atlas.texture,
+                    texture_images,
+                )?;
+                texture_packer_definition.state = SheetProcessState::AtlasCreated(
+                    texture_atlases.add(atlas),
+                    indices,
+                    source_images([handle], retain_source_images),
+                );
+            }
+            Ok(false)
+        }
+        SheetProcessState::AtlasCreated(..) => Ok(true),
+    }
+}
+
+fn process_array_atlas_definition(
+    array_definition: &mut ArrayAtlasDefinition,
+    base_path: &Path,
+    asset_server: &AssetServer,
+    texture_atlases: &mut AtlasSink<'_>,
+    texture_images: &mut Assets<Image>,
+    retain_source_images: bool,
+) -> Result<bool, ResourceError> {
+    match &array_definition.state {
+        ArrayProcessState::None => {
+            if array_definition.layers.is_empty() {
+                return Err(ResourceError::AtlasBuildFailed(String::from(
+                    "texture array without layers",
+                )));
             }
-            let atlas = texture_atlas_builder.finish(texture_images).unwrap();
-            folder_definition.state =
-                MultiTextureProcessState::AtlasCreated(texture_atlases.add(atlas));
-            false
+            array_definition.state = ArrayProcessState::LoadingTextures(
+                array_definition
+                    .layers
+                    .iter()
+                    .map(|layer| asset_server.load_untyped(base_path.join(layer)))
+                    .collect(),
+            );
+            Ok(false)
+        }
+        ArrayProcessState::LoadingTextures(handles) => {
+            let images = handles
+                .iter()
+                .map(|handle| handle.clone().typed::<Image>())
+                .collect::<Vec<_>>();
+            if images
+                .iter()
+                .any(|image| loaded_image_size(image, texture_images).is_none())
+            {
+                return Ok(false);
+            }
+            let layers = images
+                .iter()
+                .map(|image| {
+                    texture_images
+                        .get(image)
+                        .expect("Image asset should be present.")
+                })
+                .collect::<Vec<_>>();
+            let layer_size = layers[0].size().as_uvec2();
+            if let Some(layer) = layers
+                .iter()
+                .find(|layer| layer.size().as_uvec2() != layer_size)
+            {
+                return Err(ResourceError::MismatchedLayerSizes {
+                    expected: layer_size,
+                    actual: layer.size().as_uvec2(),
+                });
+            }
+            // the layers of an array are laid out just like images stacked on top of each other
+            let stacked = stack::stack_images(&layers)?;
+            let mut array = stacked.clone();
+            array.texture_descriptor.size = Extent3d {
+                width: layer_size.x,
+                height: layer_size.y,
+                depth_or_array_layers: layers.len() as u32,
+            };
+            let rects = layout::array_rects(layer_size, layers.len());
+            let image_size = stacked.size().as_uvec2();
+            let mut atlas = atlas_from_rects(texture_images.add(stacked), image_size, rects);
+            let mut array = texture_images.add(array);
+            finish_image(&array_definition.image, &mut atlas.texture, texture_images)?;
+            // mip levels aren't generated for the layers of the array
+            let array_options = ImageOptions {
+                generate_mipmaps: false,
+                ..array_definition.image.clone()
+            };
+            finish_image(&array_options, &mut array, texture_images)?;
+            array_definition.state = ArrayProcessState::AtlasCreated(
+                texture_atlases.add(atlas),
+                array,
+                source_images(handles, retain_source_images),
+            );
+            Ok(false)
         }
-        MultiTextureProcessState::AtlasCreated(_) => true,
+        ArrayProcessState::AtlasCreated(..) => Ok(true),
+    }
+}
+
+fn process_paged_atlas_definition(
+    paged_definition: &mut PagedAtlasDefinition,
+    base_path: &Path,
+    asset_server: &AssetServer,
+    texture_atlases: &mut AtlasSink<'_>,
+    texture_images: &mut Assets<Image>,
+    retain_source_images: bool,
+    atlas_scale: f32,
+) -> Result<bool, ResourceError> {
+    if paged_definition.pages.is_empty() {
+        return Err(ResourceError::AtlasBuildFailed(String::from(
+            "paged atlas without pages",
+        )));
+    }
+    let mut created = true;
+    for (page_index, page) in paged_definition.pages.iter_mut().enumerate() {
+        // only the first page takes the place of a reloaded atlas
+        let page_atlases = &mut AtlasSink {
+            texture_atlases: &mut *texture_atlases.texture_atlases,
+            post_process: texture_atlases.post_process,
+            key: texture_atlases.key,
+            previous_handle: texture_atlases.previous_handle.filter(|_| page_index == 0),
+        };
+        created &= process_grid_atlas_definition(
+            page,
+            base_path,
+            asset_server,
+            page_atlases,
+            texture_images,
+            retain_source_images,
+            atlas_scale,
+        )?;
+    }
+    Ok(created)
+}
+
+fn process_auto_detect_atlas_definition(
+    auto_detect_definition: &mut AutoDetectAtlasDefinition,
+    base_path: &Path,
+    asset_server: &AssetServer,
+    texture_atlases: &mut AtlasSink<'_>,
+    texture_images: &mut Assets<Image>,
+    retain_source_images: bool,
+) -> Result<bool, ResourceError> {
+    match &auto_detect_definition.state {
+        SingleTextureProcessState::None => {
+            auto_detect_definition.state = SingleTextureProcessState::LoadingTexture(
+                asset_server.load_untyped(base_path.join(&auto_detect_definition.texture)),
+            );
+            Ok(false)
+        }
+        SingleTextureProcessState::LoadingTexture(handle) => {
+            let image = handle.clone().typed::<Image>();
+            if let Some(image_size) = loaded_image_size(&image, texture_images) {
+                let rects = detect::detect_regions(
+                    texture_images
+                        .get(&image)
+                        .expect("Image asset should be present."),
+                    auto_detect_definition.alpha_threshold,
+                    auto_detect_definition.max_regions,
+                )?;
+                let mut atlas = atlas_from_rects(image, image_size, rects);
+                finish_image(
+                    &auto_detect_definition.image,
+                    &mut atlas.texture,
+                    texture_images,
+                )?;
+                auto_detect_definition.state = SingleTextureProcessState::AtlasCreated(
+                    texture_atlases.add(atlas),
+                    source_images([handle], retain_source_images),
+                );
+            }
+            Ok(false)
+        }
+        SingleTextureProcessState::AtlasCreated(..) => Ok(true),
+    }
+}
+
+/// Typed handles of the source images, if they should be kept alive along with the atlas.
+fn source_images<'a>(
+    handles: impl IntoIterator<Item = &'a HandleUntyped>,
+    retain_source_images: bool,
+) -> Vec<Handle<Image>> {
+    if !retain_source_images {
+        return Vec::new();
+    }
+    handles
+        .into_iter()
+        .map(|handle| handle.clone().typed())
+        .collect()
+}
+
+/// Returns the size of the image, once it's loaded.
+///
+/// Images added directly to `Assets<Image>` are never loaded by the [AssetServer], so only the
+/// presence of the image is checked.
+fn loaded_image_size(image: &Handle<Image>, texture_images: &Assets<Image>) -> Option<UVec2> {
+    texture_images
+        .get(image)
+        .map(|texture| texture.size().as_uvec2())
+}
+
+/// Whether all images of a packed atlas are present, failing if any of them failed loading.
+///
+/// Images load at different times, so each is checked individually rather than waiting on the
+/// group. Images added as assets directly are never loaded by the [AssetServer], so only their
+/// presence is checked.
+fn textures_loaded(
+    handles: &[HandleUntyped],
+    asset_server: &AssetServer,
+    texture_images: &Assets<Image>,
+) -> Result<bool, ResourceError> {
+    if let Some(failed) = handles
+        .iter()
+        .find(|handle| asset_server.get_load_state(handle.id) == LoadState::Failed)
+    {
+        return Err(ResourceError::LoadFailed(
+            asset_server
+                .get_handle_path(failed)
+                .map(|path| path.path().to_path_buf())
+                .unwrap_or_default(),
+        ));
+    }
+    Ok(handles
+        .iter()
+        .all(|handle| texture_images.get(handle.id).is_some()))
+}
+
+/// Collects the path and region of every image packed into `atlas`, in the order of their index.
+///
+/// Returns `None` for atlases that weren't packed.
+fn packed_layout(atlas: &TextureAtlas, asset_server: &AssetServer) -> Option<Vec<(PathBuf, Rect)>> {
+    let mut layout = atlas
+        .texture_handles
+        .as_ref()?
+        .iter()
+        .filter_map(|(handle, &index)| {
+            let path = asset_server.get_handle_path(handle)?.path().to_path_buf();
+            Some((index, (path, *atlas.textures.get(index)?)))
+        })
+        .collect::<Vec<_>>();
+    layout.sort_by_key(|&(index, _)| index);
+    Some(layout.into_iter().map(|(_, entry)| entry).collect())
+}
+
+/// Packs loaded images into a new atlas, with `output_padding` pixels between the regions.
+fn pack_textures(
+    handles: &[HandleUntyped],
+    output_padding: u32,
+    texture_images: &mut Assets<Image>,
+) -> Result<TextureAtlas, ResourceError> {
+    let mut texture_atlas_builder = TextureAtlasBuilder::default();
+    // the builder doesn't support padding, so padded copies of the images are packed instead
+    let mut padded_handles = HashMap::default();
+    for handle in handles {
+        let handle = handle.clone().typed::<Image>();
+        if output_padding == 0 {
+            let texture = texture_images
+                .get(&handle)
+                .expect("Image asset should be present.");
+            texture_atlas_builder.add_texture(handle, texture);
+        } else {
+            let padded = padding::pad_image(
+                texture_images
+                    .get(&handle)
+                    .expect("Image asset should be present."),
+                output_padding,
+            );
+            let padded_handle = texture_images.add(padded);
+            let texture = texture_images.get(&padded_handle).unwrap();
+            texture_atlas_builder.add_texture(padded_handle.clone(), texture);
+            padded_handles.insert(padded_handle, handle);
+        }
+    }
+    let result = texture_atlas_builder.finish(texture_images);
+    for padded_handle in padded_handles.keys() {
+        texture_images.remove(padded_handle);
+    }
+    let mut atlas = result.map_err(|err| ResourceError::AtlasBuildFailed(err.to_string()))?;
+
+    if output_padding > 0 {
+        let inset = Vec2::splat(output_padding as f32);
+        for rect in atlas.textures.iter_mut() {
+            rect.min += inset;
+            rect.max -= inset;
+        }
+        atlas.texture_handles = atlas.texture_handles.map(|texture_handles| {
+            texture_handles
+                .into_iter()
+                .map(|(padded_handle, index)| (padded_handles[&padded_handle].clone(), index))
+                .collect()
+        });
+    }
+    Ok(atlas)
+}
+
+/// Applies the [ImageOptions] of a definition to the image of its atlas.
+///
+/// The options are applied to a copy of the image, replacing `image` - as it may well be a loaded
+/// image, used by other definitions or reused when the atlas is created again.
+fn finish_image(
+    options: &ImageOptions,
+    image: &mut Handle<Image>,
+    texture_images: &mut Assets<Image>,
+) -> Result<(), ResourceError> {
+    if options.is_default() {
+        return Ok(());
+    }
+    let mut texture = texture_images
+        .get(&*image)
+        .expect("Image asset should be present.")
+        .clone();
+    if options.linear {
+        texture.texture_descriptor.format =
+            color_space::linear_format(texture.texture_descriptor.format);
+    }
+    if options.premultiply_alpha {
+        premultiply::premultiply_alpha(&mut texture)?;
+    }
+    if options.generate_mipmaps {
+        mipmaps::generate_mipmaps(&mut texture)?;
+    }
+    if let Some(address_mode) = options.address_mode {
+        let address_mode = address_mode.into();
+        texture.sampler_descriptor.address_mode_u = address_mode;
+        texture.sampler_descriptor.address_mode_v = address_mode;
+        texture.sampler_descriptor.address_mode_w = address_mode;
+    }
+    *image = texture_images.add(texture);
+    Ok(())
+}
+
+/// Fills the space of a packed atlas not covered by any region with `background`.
+fn fill_background(
+    atlas: &TextureAtlas,
+    background: Color,
+    texture_images: &mut Assets<Image>,
+) -> Result<(), ResourceError> {
+    let texture = texture_images
+        .get_mut(&atlas.texture)
+        .expect("Image asset should be present.");
+    background::fill_background(texture, &atlas.textures, background)
+}
+
+/// Makes the pixels of a loaded image matching `color_key` transparent.
+///
+/// The color key is applied to a copy of the image, as the loaded image may be used by other
+/// definitions without a color key.
+fn apply_color_key(
+    image: &Handle<Image>,
+    color_key: [u8; 3],
+    texture_images: &mut Assets<Image>,
+) -> Result<Handle<Image>, ResourceError> {
+    let mut texture = texture_images
+        .get(image)
+        .expect("Image asset should be present.")
+        .clone();
+    color_key::apply_color_key(&mut texture, color_key)?;
+    Ok(texture_images.add(texture))
+}
+
+fn atlas_from_rects(texture: Handle<Image>, size: UVec2, rects: Vec<Rect>) -> TextureAtlas {
+    TextureAtlas {
+        texture,
+        size: size.as_vec2(),
+        textures: rects,
+        texture_handles: None,
+    }
+}
+
+/// Resamples the image of the atlas by `scale` into a new image, scaling the regions to match.
+fn scale_atlas(
+    atlas: TextureAtlas,
+    scale: f32,
+    texture_images: &mut Assets<Image>,
+) -> TextureAtlas {
+    let size = (atlas.size * scale).round().max(Vec2::ONE);
+    let texture = texture_images
+        .get(&atlas.texture)
+        .expect("Image asset should be present.");
+    let texture = resample::resize_nearest(texture, size.as_uvec2());
+    TextureAtlas {
+        texture: texture_images.add(texture),
+        size,
+        textures: atlas
+            .textures
+            .iter()
+            .map(|rect| Rect {
+                min: (rect.min * scale).round(),
+                max: (rect.max * scale).round(),
+            })
+            .collect(),
+        texture_handles: None,
     }
 }
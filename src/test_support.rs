@@ -1,3 +1,5 @@
+//! Support for testing the atlas integration, without a window or a GPU.
+
 use bevy::{
     asset::AssetPlugin,
     core_pipeline::CorePipelinePlugin,
@@ -8,7 +10,12 @@ use bevy::{
     MinimalPlugins,
 };
 
-pub(crate) fn minimal_bevy_app() -> App {
+/// Returns a headless [App] with the plugins needed by [crate::AtlasTexturePlugin].
+///
+/// Rendering is disabled through `WgpuSettings { backends: None }`, so the app runs fine on
+/// machines without a GPU. Add the [crate::AtlasTexturePlugin] and run `app.update()` until the
+/// atlases are created.
+pub fn minimal_app() -> App {
     let mut app = App::default();
     app.add_plugins(MinimalPlugins)
         .insert_resource(WgpuSettings {
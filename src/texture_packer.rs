@@ -0,0 +1,285 @@
+//! Support for sprite sheets exported by [TexturePacker](https://www.codeandweb.com/texturepacker)
+//! in the JSON formats, with `frames` either as a hash or as an array.
+
+use bevy::{
+    asset::{AssetLoader, LoadContext, LoadedAsset},
+    prelude::*,
+    reflect::TypeUuid,
+    sprite::Rect,
+    utils::BoxedFuture,
+};
+use serde::{
+    de::{MapAccess, Visitor},
+    Deserialize, Deserializer,
+};
+use std::{
+    fmt,
+    path::{Path, PathBuf},
+};
+
+use crate::ResourceError;
+
+/// Sprite sheet exported by TexturePacker, loaded as an asset from files ending in
+/// `.tpsheet.json`.
+#[derive(Debug, TypeUuid)]
+#[uuid = "4b3d0e2c-5f0a-4c43-9c0e-5a4a8f1f9f3b"]
+pub struct TexturePackerSheet {
+    /// Path of the sheet image, relative to the asset folder.
+    pub image: PathBuf,
+    /// Named regions of the sheet, in the order they were exported.
+    pub frames: Vec<TexturePackerFrame>,
+}
+
+/// Named region of a [TexturePackerSheet].
+#[derive(Debug, Clone, PartialEq)]
+pub struct TexturePackerFrame {
+    pub name: String,
+    pub position: UVec2,
+    pub size: UVec2,
+}
+
+impl TexturePackerSheet {
+    /// Parses the JSON of a sheet located at `path`, resolving the image relative to it.
+    pub fn from_json(bytes: &[u8], path: &Path) -> anyhow::Result<Self> {
+        let sheet: SheetFile = serde_json::from_slice(bytes)?;
+        let frames = match sheet.frames {
+            SheetFrames::Array(frames) => frames
+                .into_iter()
+                .map(|frame| (frame.filename, frame.data))
+                .collect(),
+            SheetFrames::Hash(HashFrames(frames)) => frames,
+        };
+        let frames = frames
+            .into_iter()
+            .map(|(name, data)| {
+                if data.rotated {
+                    anyhow::bail!("rotated frame {:?} is not supported", name);
+                }
+                Ok(TexturePackerFrame {
+                    name,
+                    position: UVec2::new(data.frame.x, data.frame.y),
+                    size: UVec2::new(data.frame.w, data.frame.h),
+                })
+            })
+            .collect::<anyhow::Result<_>>()?;
+        Ok(Self {
+            image: path
+                .parent()
+                .unwrap_or_else(|| Path::new(""))
+                .join(sheet.meta.image),
+            frames,
+        })
+    }
+
+    /// Regions of the frames, in order, inside the sheet image of size `image_size`.
+    ///
+    /// Fails if a frame extends past the image, e.g. when the sheet doesn't match its image.
+    pub(crate) fn rects(&self, image_size: UVec2) -> Result<Vec<Rect>, ResourceError> {
+        self.frames
+            .iter()
+            .map(|frame| {
+                let position = frame.position;
+                match (
+                    position.x.checked_add(frame.size.x),
+                    position.y.checked_add(frame.size.y),
+                ) {
+                    (Some(right), Some(bottom))
+                        if right <= image_size.x && bottom <= image_size.y =>
+                    {
+                        Ok(Rect {
+                            min: position.as_vec2(),
+                            max: Vec2::new(right as f32, bottom as f32),
+                        })
+                    }
+                    _ => Err(ResourceError::RegionOutOfBounds {
+                        position,
+                        image_size,
+                    }),
+                }
+            })
+            .collect()
+    }
+}
+
+/// [AssetLoader] for [TexturePackerSheet].
+#[derive(Debug, Default)]
+pub struct TexturePackerSheetLoader;
+
+impl AssetLoader for TexturePackerSheetLoader {
+    fn load<'a>(
+        &'a self,
+        bytes: &'a [u8],
+        load_context: &'a mut LoadContext,
+    ) -> BoxedFuture<'a, anyhow::Result<()>> {
+        Box::pin(async move {
+            let sheet = TexturePackerSheet::from_json(bytes, load_context.path())?;
+            load_context.set_default_asset(LoadedAsset::new(sheet));
+            Ok(())
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["tpsheet.json"]
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct SheetFile {
+    frames: SheetFrames,
+    meta: SheetMeta,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum SheetFrames {
+    Array(Vec<ArrayFrame>),
+    Hash(HashFrames),
+}
+
+#[derive(Debug, Deserialize)]
+struct ArrayFrame {
+    filename: String,
+    #[serde(flatten)]
+    data: FrameData,
+}
+
+#[derive(Debug, Deserialize)]
+struct FrameData {
+    frame: FrameRect,
+    #[serde(default)]
+    rotated: bool,
+}
+
+#[derive(Debug, Deserialize)]
+struct FrameRect {
+    x: u32,
+    y: u32,
+    w: u32,
+    h: u32,
+}
+
+#[derive(Debug, Deserialize)]
+struct SheetMeta {
+    image: PathBuf,
+}
+
+/// Frames of the hash format, kept in the order of the file.
+#[derive(Debug)]
+struct HashFrames(Vec<(String, FrameData)>);
+
+impl<'de> Deserialize<'de> for HashFrames {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct HashFramesVisitor;
+
+        impl<'de> Visitor<'de> for HashFramesVisitor {
+            type Value = HashFrames;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str("a map of frames")
+            }
+
+            fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+                let mut frames = Vec::with_capacity(map.size_hint().unwrap_or_default());
+                while let Some(frame) = map.next_entry()? {
+                    frames.push(frame);
+                }
+                Ok(HashFrames(frames))
+            }
+        }
+
+        deserializer.deserialize_map(HashFramesVisitor)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    type Result = anyhow::Result<()>;
+
+    fn expected_frames() -> Vec<TexturePackerFrame> {
+        ["pacman-open.png", "pacman-half.png", "pacman-closed.png"]
+            .into_iter()
+            .zip([65, 86, 107])
+            .map(|(name, x)| TexturePackerFrame {
+                name: name.to_string(),
+                position: UVec2::new(x, 86),
+                size: UVec2::new(19, 19),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn hash_format() -> Result {
+        let sheet = TexturePackerSheet::from_json(
+            include_bytes!("../assets/pacman.tpsheet.json"),
+            Path::new("sheets/pacman.tpsheet.json"),
+        )?;
+
+        assert_eq!(sheet.image, Path::new("sheets/Pac-Man.png"));
+        assert_eq!(sheet.frames, expected_frames());
+        Ok(())
+    }
+
+    #[test]
+    fn array_format() -> Result {
+        let sheet = TexturePackerSheet::from_json(
+            include_bytes!("../assets/pacman-array.tpsheet.json"),
+            Path::new("pacman-array.tpsheet.json"),
+        )?;
+
+        assert_eq!(sheet.image, Path::new("Pac-Man.png"));
+        assert_eq!(sheet.frames, expected_frames());
+        Ok(())
+    }
+
+    #[test]
+    fn frames_inside_the_image_become_regions() {
+        let sheet = TexturePackerSheet {
+            image: PathBuf::from("Pac-Man.png"),
+            frames: expected_frames(),
+        };
+
+        let rects = sheet.rects(UVec2::new(380, 231)).unwrap();
+
+        assert_eq!(rects.len(), 3);
+        assert_eq!(rects[2].min, Vec2::new(107., 86.));
+        assert_eq!(rects[2].max, Vec2::new(126., 105.));
+    }
+
+    #[test]
+    fn frames_exceeding_the_image_fail() {
+        let sheet = TexturePackerSheet {
+            image: PathBuf::from("Pac-Man.png"),
+            frames: expected_frames(),
+        };
+
+        assert_eq!(
+            sheet.rects(UVec2::new(120, 231)),
+            Err(ResourceError::RegionOutOfBounds {
+                position: UVec2::new(107, 86),
+                image_size: UVec2::new(120, 231),
+            })
+        );
+    }
+
+    #[test]
+    fn rotated_frames_are_rejected() {
+        let json = indoc::indoc! {r#"
+            {
+                "frames": [
+                    {
+                        "filename": "rotated.png",
+                        "frame": { "x": 0, "y": 0, "w": 10, "h": 20 },
+                        "rotated": true
+                    }
+                ],
+                "meta": { "image": "sheet.png" }
+            }"#};
+
+        let result =
+            TexturePackerSheet::from_json(json.as_bytes(), Path::new("sheet.tpsheet.json"));
+
+        assert!(result.is_err());
+    }
+}
@@ -0,0 +1,39 @@
+//! Loading of definitions written in TOML, for projects standardizing on it for their config.
+
+use bevy::{
+    asset::{AssetLoader, LoadContext, LoadedAsset},
+    utils::BoxedFuture,
+};
+
+use crate::GenericAtlasDefinitions;
+
+/// [AssetLoader] for [GenericAtlasDefinitions] in files ending in `.atlas.toml`.
+///
+/// The definitions are written as a table per key:
+/// ```toml
+/// [Pacman]
+/// texture = "Pac-Man.png"
+/// columns = 8
+/// rows = 4
+/// tile_size = [20, 20]
+/// ```
+#[derive(Debug, Default)]
+pub struct AtlasDefinitionsTomlLoader;
+
+impl AssetLoader for AtlasDefinitionsTomlLoader {
+    fn load<'a>(
+        &'a self,
+        bytes: &'a [u8],
+        load_context: &'a mut LoadContext,
+    ) -> BoxedFuture<'a, anyhow::Result<()>> {
+        Box::pin(async move {
+            let definitions = GenericAtlasDefinitions::from_toml_bytes(bytes)?;
+            load_context.set_default_asset(LoadedAsset::new(definitions));
+            Ok(())
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["atlas.toml"]
+    }
+}
@@ -0,0 +1,303 @@
+//! Validation of definitions built in code, catching mistakes when constructing them rather than
+//! when creating their atlas.
+
+use bevy::prelude::*;
+use derive_more::Display;
+use std::path::Path;
+
+use crate::{
+    mosaic, ArrayAtlasDefinition, AtlasDefinition, AutoDetectAtlasDefinition,
+    FolderAtlasDefinition, FolderManifestAtlasDefinition, FractionalGridAtlasDefinition,
+    FramesAtlasDefinition, GlobAtlasDefinition, GridAtlasDefinition, GridByBoundsAtlasDefinition,
+    MosaicAtlasDefinition, PagedAtlasDefinition, PatchAtlasDefinition,
+    TexturePackerAtlasDefinition,
+};
+
+/// Reason for a definition being invalid, see e.g. [GridAtlasDefinition::validated].
+#[derive(Debug, PartialEq, Eq, Clone, Display)]
+pub enum DefinitionError {
+    /// The path of the texture, folder or file of the definition is empty.
+    #[display(fmt = "missing path")]
+    MissingPath,
+    /// A size, such as the size of the tiles, has a zero width or height.
+    #[display(fmt = "{} must not be zero", _0)]
+    ZeroSize(&'static str),
+    /// A list the definition consists of, such as the positions of its regions, is empty.
+    #[display(fmt = "{} must not be empty", _0)]
+    Empty(&'static str),
+    /// A mosaic places more than one image into the same cell.
+    #[display(fmt = "cell {} is assigned more than once", _0)]
+    DuplicateCell(UVec2),
+}
+
+impl std::error::Error for DefinitionError {}
+
+impl GridAtlasDefinition {
+    /// Returns the definition if valid, or the reason it isn't.
+    ///
+    /// Zero columns or rows are valid, as they're computed from the image.
+    ///
+    /// # Example:
+    /// ```rust
+    /// # use std::path::Path;
+    /// # use bevy::prelude::*;
+    /// # use bevy_atlas_loader::*;
+    /// let grid = GridAtlasDefinition::grid(Path::new("Pac-Man.png"), 8, 4, UVec2::new(20, 0));
+    /// assert_eq!(
+    ///     grid.validated().err(),
+    ///     Some(DefinitionError::ZeroSize("tile_size"))
+    /// );
+    /// ```
+    pub fn validated(self) -> Result<Self, DefinitionError> {
+        require_path(&self.texture)?;
+        require_size("tile_size", self.tile_size)?;
+        Ok(self)
+    }
+}
+
+impl GridByBoundsAtlasDefinition {
+    /// Returns the definition if valid, or the reason it isn't.
+    pub fn validated(self) -> Result<Self, DefinitionError> {
+        require_path(&self.texture)?;
+        require_size("tile_size", self.tile_size)?;
+        Ok(self)
+    }
+}
+
+impl PatchAtlasDefinition {
+    /// Returns the definition if valid, or the reason it isn't.
+    pub fn validated(self) -> Result<Self, DefinitionError> {
+        require_path(&self.texture)?;
+        require_size("width and height", UVec2::new(self.width, self.height))?;
+        require_items("positions", &self.positions)?;
+        Ok(self)
+    }
+}
+
+impl FramesAtlasDefinition {
+    /// Returns the definition if valid, or the reason it isn't.
+    pub fn validated(self) -> Result<Self, DefinitionError> {
+        require_path(&self.texture)?;
+        require_items("frames", &self.frames)?;
+        for frame in &self.frames {
+            require_size("frame size", UVec2::new(frame.w, frame.h))?;
+        }
+        Ok(self)
+    }
+}
+
+impl FolderAtlasDefinition {
+    /// Returns the definition if valid, or the reason it isn't.
+    pub fn validated(self) -> Result<Self, DefinitionError> {
+        require_path(&self.path)?;
+        Ok(self)
+    }
+}
+
+impl FolderManifestAtlasDefinition {
+    /// Returns the definition if valid, or the reason it isn't.
+    pub fn validated(self) -> Result<Self, DefinitionError> {
+        require_path(&self.manifest)?;
+        Ok(self)
+    }
+}
+
+impl TexturePackerAtlasDefinition {
+    /// Returns the definition if valid, or the reason it isn't.
+    pub fn validated(self) -> Result<Self, DefinitionError> {
+        require_path(&self.sheet)?;
+        Ok(self)
+    }
+}
+
+impl ArrayAtlasDefinition {
+    /// Returns the definition if valid, or the reason it isn't.
+    pub fn validated(self) -> Result<Self, DefinitionError> {
+        require_items("layers", &self.layers)?;
+        for layer in &self.layers {
+            require_path(layer)?;
+        }
+        Ok(self)
+    }
+}
+
+impl PagedAtlasDefinition {
+    /// Returns the definition if valid, or the reason it isn't - including the reason of the
+    /// first invalid page.
+    pub fn validated(self) -> Result<Self, DefinitionError> {
+        require_items("pages", &self.pages)?;
+        for page in &self.pages {
+            page.clone().validated()?;
+        }
+        Ok(self)
+    }
+}
+
+impl AutoDetectAtlasDefinition {
+    /// Returns the definition if valid, or the reason it isn't.
+    pub fn validated(self) -> Result<Self, DefinitionError> {
+        require_path(&self.texture)?;
+        if self.max_regions == 0 {
+            return Err(DefinitionError::ZeroSize("max_regions"));
+        }
+        Ok(self)
+    }
+}
+
+impl GlobAtlasDefinition {
+    /// Returns the definition if valid, or the reason it isn't.
+    pub fn validated(self) -> Result<Self, DefinitionError> {
+        require_path(Path::new(&self.pattern))?;
+        Ok(self)
+    }
+}
+
+impl MosaicAtlasDefinition {
+    /// Returns the definition if valid, or the reason it isn't - including cells assigned more
+    /// than one image.
+    pub fn validated(self) -> Result<Self, DefinitionError> {
+        require_items("cells", &self.cells)?;
+        for (_, image) in &self.cells {
+            require_path(image)?;
+        }
+        if let Some(cell) = mosaic::duplicate_cell(self.cells.iter().map(|(cell, _)| *cell)) {
+            return Err(DefinitionError::DuplicateCell(cell));
+        }
+        Ok(self)
+    }
+}
+
+impl FractionalGridAtlasDefinition {
+    /// Returns the definition if valid, or the reason it isn't.
+    pub fn validated(self) -> Result<Self, DefinitionError> {
+        require_path(&self.texture)?;
+        require_size(
+            "columns and rows",
+            UVec2::new(self.columns as u32, self.rows as u32),
+        )?;
+        if !self.tile_size.cmpgt(Vec2::ZERO).all() {
+            return Err(DefinitionError::ZeroSize("tile_size"));
+        }
+        Ok(self)
+    }
+}
+
+impl AtlasDefinition {
+    /// Returns the definition if valid, or the reason it isn't - see the `validated` method of
+    /// each kind of definition.
+    pub fn validated(self) -> Result<Self, DefinitionError> {
+        Ok(match self {
+            AtlasDefinition::Grid(d) => d.validated()?.into(),
+            AtlasDefinition::GridByBounds(d) => d.validated()?.into(),
+            AtlasDefinition::Manual(d) => d.validated()?.into(),
+            AtlasDefinition::Frames(d) => d.validated()?.into(),
+            AtlasDefinition::Folder(d) => d.validated()?.into(),
+            AtlasDefinition::FolderManifest(d) => d.validated()?.into(),
+            AtlasDefinition::TexturePacker(d) => d.validated()?.into(),
+            AtlasDefinition::Array(d) => d.validated()?.into(),
+            AtlasDefinition::Paged(d) => d.validated()?.into(),
+            AtlasDefinition::AutoDetect(d) => d.validated()?.into(),
+            AtlasDefinition::Glob(d) => d.validated()?.into(),
+            AtlasDefinition::Mosaic(d) => d.validated()?.into(),
+            AtlasDefinition::FractionalGrid(d) => d.validated()?.into(),
+        })
+    }
+}
+
+fn require_path(path: &Path) -> Result<(), DefinitionError> {
+    if path.as_os_str().is_empty() {
+        return Err(DefinitionError::MissingPath);
+    }
+    Ok(())
+}
+
+fn require_size(name: &'static str, size: UVec2) -> Result<(), DefinitionError> {
+    if size.cmpeq(UVec2::ZERO).any() {
+        return Err(DefinitionError::ZeroSize(name));
+    }
+    Ok(())
+}
+
+fn require_items<I>(name: &'static str, items: &[I]) -> Result<(), DefinitionError> {
+    if items.is_empty() {
+        return Err(DefinitionError::Empty(name));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn valid_definitions_are_returned() {
+        let grid = GridAtlasDefinition::grid(Path::new("Pac-Man.png"), 0, 0, UVec2::new(20, 20));
+        assert!(grid.validated().is_ok());
+        let definition = AtlasDefinition::from(FolderAtlasDefinition::folder("shapes"));
+        assert!(definition.validated().is_ok());
+    }
+
+    #[test]
+    fn zero_sizes_are_rejected() {
+        let patch = PatchAtlasDefinition {
+            texture: "Pac-Man.png".into(),
+            width: 0,
+            height: 19,
+            positions: vec![UVec2::ZERO],
+            ..Default::default()
+        };
+        assert_eq!(
+            patch.validated().err(),
+            Some(DefinitionError::ZeroSize("width and height"))
+        );
+    }
+
+    #[test]
+    fn empty_lists_are_rejected() {
+        let patch = PatchAtlasDefinition {
+            texture: "Pac-Man.png".into(),
+            width: 19,
+            height: 19,
+            ..Default::default()
+        };
+        assert_eq!(
+            patch.validated().err(),
+            Some(DefinitionError::Empty("positions"))
+        );
+        assert_eq!(
+            PagedAtlasDefinition::default().validated().err(),
+            Some(DefinitionError::Empty("pages"))
+        );
+    }
+
+    #[test]
+    fn empty_paths_are_rejected() {
+        assert_eq!(
+            FolderAtlasDefinition::folder("").validated().err(),
+            Some(DefinitionError::MissingPath)
+        );
+        let paged = PagedAtlasDefinition::pages([GridAtlasDefinition::grid(
+            Path::new(""),
+            1,
+            1,
+            UVec2::ONE,
+        )]);
+        assert_eq!(
+            AtlasDefinition::from(paged).validated().err(),
+            Some(DefinitionError::MissingPath)
+        );
+    }
+
+    #[test]
+    fn overlapping_cells_are_rejected() {
+        let mosaic = MosaicAtlasDefinition::mosaic([
+            (UVec2::new(0, 0), "health.png"),
+            (UVec2::new(1, 0), "mana.png"),
+            (UVec2::new(0, 0), "stamina.png"),
+        ]);
+        assert_eq!(
+            mosaic.validated().err(),
+            Some(DefinitionError::DuplicateCell(UVec2::ZERO))
+        );
+    }
+}
@@ -1,13 +1,14 @@
-use crate::common::minimal_bevy_app;
-use bevy::prelude::*;
+use bevy::{prelude::*, utils::HashMap};
 use bevy_atlas_loader::{
-    AtlasTexturePlugin, AtlasTextures, AtlasTexturesEvent, GenericAtlasDefinitions,
+    test_support::minimal_app, AtlasDefinition, AtlasTexturePlugin, AtlasTextures,
+    AtlasTexturesEvent, GenericAtlasDefinitions, GridAtlasDefinition, ResourceStatus,
     TypedAtlasDefinition,
 };
 use bevy_common_assets::ron::RonAssetPlugin;
-use std::sync::{atomic::AtomicBool, Arc};
-
-mod common;
+use std::{
+    path::Path,
+    sync::{atomic::AtomicBool, Arc, Mutex},
+};
 
 #[derive(Debug, PartialEq, Eq, Hash, Clone, Copy, strum::EnumVariantNames, strum::EnumString)]
 enum MyAtlasTextures {
@@ -16,7 +17,7 @@ enum MyAtlasTextures {
 
 #[test]
 fn definition_can_be_loaded_as_asset() {
-    let mut app = minimal_bevy_app();
+    let mut app = minimal_app();
     app.add_plugin(AtlasTexturePlugin::<MyAtlasTextures>::default());
 
     // add needed 3'rd party plugin for loading definition as asset
@@ -55,3 +56,318 @@ fn definition_can_be_loaded_as_asset() {
         .world
         .contains_resource::<AtlasTextures<MyAtlasTextures>>());
 }
+
+#[test]
+fn paths_can_be_relative_to_the_definition() {
+    let mut app = minimal_app();
+    app.add_plugin(AtlasTexturePlugin::<MyAtlasTextures>::default());
+    app.add_plugin(RonAssetPlugin::<GenericAtlasDefinitions>::new(&[
+        "atlasmap",
+    ]));
+
+    // "square.png" lives next to the definition, not in the asset folder
+    app.add_startup_system(move |mut cmds: Commands, assets: Res<AssetServer>| {
+        cmds.insert_resource(
+            TypedAtlasDefinition::<MyAtlasTextures>::from(
+                assets.load("relative/relative.atlasmap"),
+            )
+            .with_relative_paths(true),
+        );
+    });
+
+    // spin Bevy a few times...
+    (0..100).for_each(|_| app.update());
+
+    let resource = app
+        .world
+        .get_resource::<AtlasTextures<MyAtlasTextures>>()
+        .unwrap();
+    assert_eq!(resource.len(MyAtlasTextures::Pacman), 4);
+}
+
+#[test]
+fn modified_definition_is_reloaded() {
+    let mut app = minimal_app();
+    app.add_plugin(AtlasTexturePlugin::<MyAtlasTextures>::default());
+    app.add_plugin(RonAssetPlugin::<GenericAtlasDefinitions>::new(&[
+        "atlasmap",
+    ]));
+    app.add_startup_system(move |mut cmds: Commands, assets: Res<AssetServer>| {
+        cmds.insert_resource(TypedAtlasDefinition::<MyAtlasTextures>::from(
+            assets.load("sprite_sheets.atlasmap"),
+        ));
+    });
+
+    // spin Bevy a few times...
+    (0..100).for_each(|_| app.update());
+
+    // add system for capturing events
+    let statuses = Arc::new(Mutex::new(Vec::new()));
+    app.add_system({
+        let statuses = statuses.clone();
+        move |mut events: EventReader<AtlasTexturesEvent<MyAtlasTextures>>| {
+            statuses
+                .lock()
+                .unwrap()
+                .extend(events.iter().map(|e| e.status()));
+        }
+    });
+
+    // touch the definitions, as if changed on disk
+    let handle = app
+        .world
+        .get_resource::<AssetServer>()
+        .unwrap()
+        .get_handle::<GenericAtlasDefinitions, _>("sprite_sheets.atlasmap");
+    app.world
+        .get_resource_mut::<Assets<GenericAtlasDefinitions>>()
+        .unwrap()
+        .get_mut(&handle);
+
+    // spin Bevy a few times...
+    (0..100).for_each(|_| app.update());
+
+    // the reload is announced before the atlases are recreated
+    assert_eq!(
+        *statuses.lock().unwrap(),
+        vec![
+            ResourceStatus::Reloading,
+            ResourceStatus::AtlasCreated,
+            ResourceStatus::Created
+        ]
+    );
+}
+
+#[test]
+fn reloaded_atlases_can_keep_their_handles() {
+    let mut app = minimal_app();
+    app.add_plugin(AtlasTexturePlugin::<MyAtlasTextures>::default().with_stable_handles(true));
+    app.add_plugin(RonAssetPlugin::<GenericAtlasDefinitions>::new(&[
+        "atlasmap",
+    ]));
+    app.add_startup_system(move |mut cmds: Commands, assets: Res<AssetServer>| {
+        cmds.insert_resource(TypedAtlasDefinition::<MyAtlasTextures>::from(
+            assets.load("sprite_sheets.atlasmap"),
+        ));
+    });
+
+    // spin Bevy a few times...
+    (0..100).for_each(|_| app.update());
+    let texture_atlas_handle = app
+        .world
+        .get_resource::<AtlasTextures<MyAtlasTextures>>()
+        .unwrap()
+        .handle(MyAtlasTextures::Pacman);
+
+    // change the definitions, as if changed on disk
+    let handle = app
+        .world
+        .get_resource::<AssetServer>()
+        .unwrap()
+        .get_handle::<GenericAtlasDefinitions, _>("sprite_sheets.atlasmap");
+    app.world
+        .get_resource_mut::<Assets<GenericAtlasDefinitions>>()
+        .unwrap()
+        .set(
+            &handle,
+            GenericAtlasDefinitions::from(
+                [(
+                    String::from("Pacman"),
+                    AtlasDefinition::from(GridAtlasDefinition::grid(
+                        Path::new("Pac-Man.png"),
+                        3,
+                        3,
+                        UVec2::new(19, 19),
+                    )),
+                )]
+                .into_iter()
+                .collect::<HashMap<String, AtlasDefinition>>(),
+            ),
+        );
+
+    // spin Bevy a few times...
+    (0..100).for_each(|_| app.update());
+
+    // the same handle now has the new layout
+    let resource = app
+        .world
+        .get_resource::<AtlasTextures<MyAtlasTextures>>()
+        .unwrap();
+    assert_eq!(
+        resource.handle(MyAtlasTextures::Pacman),
+        texture_atlas_handle
+    );
+    assert_eq!(resource.len(MyAtlasTextures::Pacman), 9);
+    assert_eq!(
+        app.world
+            .get_resource::<Assets<TextureAtlas>>()
+            .unwrap()
+            .get(texture_atlas_handle)
+            .map(|atlas| atlas.len()),
+        Some(9)
+    );
+}
+
+#[test]
+fn unchanged_definitions_reuse_their_atlas() {
+    let mut app = minimal_app();
+    app.add_plugin(AtlasTexturePlugin::<MyAtlasTextures>::default());
+    app.add_plugin(RonAssetPlugin::<GenericAtlasDefinitions>::new(&[
+        "atlasmap",
+    ]));
+    app.add_startup_system(move |mut cmds: Commands, assets: Res<AssetServer>| {
+        cmds.insert_resource(TypedAtlasDefinition::<MyAtlasTextures>::from(
+            assets.load("sprite_sheets.atlasmap"),
+        ));
+    });
+
+    // spin Bevy a few times...
+    (0..100).for_each(|_| app.update());
+    let texture_atlas_handle = app
+        .world
+        .get_resource::<AtlasTextures<MyAtlasTextures>>()
+        .unwrap()
+        .handle(MyAtlasTextures::Pacman);
+
+    // re-save the definitions unchanged, as if saved again on disk
+    let handle = app
+        .world
+        .get_resource::<AssetServer>()
+        .unwrap()
+        .get_handle::<GenericAtlasDefinitions, _>("sprite_sheets.atlasmap");
+    app.world
+        .get_resource_mut::<Assets<GenericAtlasDefinitions>>()
+        .unwrap()
+        .set(
+            &handle,
+            GenericAtlasDefinitions::from_ron_bytes(include_bytes!(
+                "../assets/sprite_sheets.atlasmap"
+            ))
+            .unwrap(),
+        );
+
+    // spin Bevy a few times...
+    (0..100).for_each(|_| app.update());
+
+    // the atlas isn't created again
+    assert_eq!(
+        app.world
+            .get_resource::<AtlasTextures<MyAtlasTextures>>()
+            .unwrap()
+            .handle(MyAtlasTextures::Pacman),
+        texture_atlas_handle
+    );
+}
+
+#[test]
+fn removed_definition_removes_atlases() {
+    let mut app = minimal_app();
+    app.add_plugin(AtlasTexturePlugin::<MyAtlasTextures>::default());
+    app.add_plugin(RonAssetPlugin::<GenericAtlasDefinitions>::new(&[
+        "atlasmap",
+    ]));
+    app.add_startup_system(move |mut cmds: Commands, assets: Res<AssetServer>| {
+        cmds.insert_resource(TypedAtlasDefinition::<MyAtlasTextures>::from(
+            assets.load("sprite_sheets.atlasmap"),
+        ));
+    });
+
+    // spin Bevy a few times...
+    (0..100).for_each(|_| app.update());
+    assert!(app
+        .world
+        .get_resource::<AtlasTextures<MyAtlasTextures>>()
+        .is_some());
+
+    // remove the definitions, as if unloaded by the user
+    let handle = app
+        .world
+        .get_resource::<AssetServer>()
+        .unwrap()
+        .get_handle::<GenericAtlasDefinitions, _>("sprite_sheets.atlasmap");
+    app.world
+        .get_resource_mut::<Assets<GenericAtlasDefinitions>>()
+        .unwrap()
+        .remove(&handle);
+
+    // spin Bevy a few times...
+    (0..100).for_each(|_| app.update());
+
+    // the stale atlases are gone
+    assert!(app
+        .world
+        .get_resource::<AtlasTextures<MyAtlasTextures>>()
+        .is_none());
+}
+
+#[test]
+fn definition_unloaded_while_processing_causes_failure() {
+    let mut app = minimal_app();
+    app.add_plugin(AtlasTexturePlugin::<MyAtlasTextures>::default());
+    app.add_plugin(RonAssetPlugin::<GenericAtlasDefinitions>::new(&[
+        "atlasmap",
+    ]));
+    app.add_startup_system(move |mut cmds: Commands, assets: Res<AssetServer>| {
+        cmds.insert_resource(TypedAtlasDefinition::<MyAtlasTextures>::from(
+            assets.load("sprite_sheets.atlasmap"),
+        ));
+    });
+
+    // add system for capturing event
+    let is_unloaded = Arc::new(AtomicBool::new(false));
+    app.add_system({
+        let is_unloaded = is_unloaded.clone();
+        move |mut events: EventReader<AtlasTexturesEvent<MyAtlasTextures>>| {
+            for e in events.iter() {
+                if e.error()
+                    .map_or(false, |error| error.is_definitions_unloaded())
+                {
+                    is_unloaded.store(true, std::sync::atomic::Ordering::Release);
+                }
+            }
+        }
+    });
+
+    // spin Bevy until the image of the definition is loading
+    let is_loading = |app: &App| {
+        let atlas_definitions = app
+            .world
+            .get_resource::<Assets<GenericAtlasDefinitions>>()
+            .unwrap();
+        app.world
+            .get_resource::<TypedAtlasDefinition<MyAtlasTextures>>()
+            .map_or(false, |definition| {
+                definition
+                    .definitions_status(atlas_definitions)
+                    .any(|(_, status)| status.is_loading())
+            })
+    };
+    for _ in 0..100 {
+        app.update();
+        if is_loading(&app) {
+            break;
+        }
+    }
+    assert!(is_loading(&app));
+
+    // remove the definitions mid-flight, as if racing a hot-reload
+    let handle = app
+        .world
+        .get_resource::<AssetServer>()
+        .unwrap()
+        .get_handle::<GenericAtlasDefinitions, _>("sprite_sheets.atlasmap");
+    app.world
+        .get_resource_mut::<Assets<GenericAtlasDefinitions>>()
+        .unwrap()
+        .remove(&handle);
+
+    // spin Bevy a few times, without panicking...
+    (0..100).for_each(|_| app.update());
+
+    // event signalling the failure
+    assert!(is_unloaded.load(std::sync::atomic::Ordering::Acquire));
+    assert!(app
+        .world
+        .get_resource::<AtlasTextures<MyAtlasTextures>>()
+        .is_none());
+}
@@ -1,16 +1,28 @@
-use crate::common::minimal_bevy_app;
-use bevy::{prelude::*, utils::HashMap};
+use bevy::{
+    prelude::*,
+    render::render_resource::{self, Extent3d, TextureDimension, TextureFormat},
+    sprite::Rect,
+    utils::HashMap,
+};
 use bevy_atlas_loader::{
-    AtlasDefinition, AtlasTexturePlugin, AtlasTextures, AtlasTexturesEvent, GridAtlasDefinition,
-    TypedAtlasDefinition,
+    on_atlas_created, on_atlas_failed, rebuild_now, sync_atlas_sprite_handles,
+    test_support::minimal_app, AddressMode, ArrayAtlasDefinition, AtlasDefinition, AtlasReadiness,
+    AtlasTexturePlugin, AtlasTextures, AtlasTexturesEvent, AutoDetectAtlasDefinition,
+    DefinitionStatus, DynamicAtlasKey, DynamicAtlasTextures, FolderAtlasDefinition,
+    FolderManifestAtlasDefinition, GenericAtlasDefinitions, GlobAtlasDefinition,
+    GridAtlasDefinition, MosaicAtlasDefinition, NineSliceBorders, PagedAtlasDefinition,
+    ResourceError, TypedAtlasDefinition, UsesAtlasTexture, WithDefinitionOptions, WithImageOptions,
+    WithPackOptions,
 };
+use iyes_loopless::condition::IntoConditionalSystem;
 use std::{
-    path::Path,
-    sync::{atomic::AtomicBool, Arc},
+    future::Future,
+    path::{Path, PathBuf},
+    sync::{atomic::AtomicBool, Arc, Mutex},
+    task::{Context, Poll, Wake, Waker},
+    time::Duration,
 };
 
-mod common;
-
 #[derive(Debug, PartialEq, Eq, Hash, Clone, Copy, strum::EnumVariantNames, strum::EnumString)]
 enum MyAtlasTextures {
     Pacman,
@@ -18,7 +30,7 @@ enum MyAtlasTextures {
 
 #[test]
 fn definition_can_be_specified_manually() {
-    let mut app = minimal_bevy_app();
+    let mut app = minimal_app();
     app.add_plugin(AtlasTexturePlugin::<MyAtlasTextures>::default());
 
     // add system for adding our atlas definition
@@ -26,14 +38,12 @@ fn definition_can_be_specified_manually() {
         cmds.insert_resource(TypedAtlasDefinition::<MyAtlasTextures>::from(
             [(
                 String::from("Pacman"),
-                AtlasDefinition::from(GridAtlasDefinition {
-                    texture: Path::new("Pac-Man.png").into(),
-                    columns: 3,
-                    rows: 3,
-                    tile_size: (19, 19),
-                    padding: None,
-                    ..Default::default()
-                }),
+                AtlasDefinition::from(GridAtlasDefinition::grid(
+                    Path::new("Pac-Man.png"),
+                    3,
+                    3,
+                    UVec2::new(19, 19),
+                )),
             )]
             .into_iter()
             .collect::<HashMap<String, AtlasDefinition>>(),
@@ -48,91 +58,2481 @@ fn definition_can_be_specified_manually() {
         .world
         .get_resource::<AtlasTextures<MyAtlasTextures>>()
         .unwrap();
-    let _texture_atlas_handle = &resource.handle(MyAtlasTextures::Pacman);
+    let texture_atlas_handle = resource.handle(MyAtlasTextures::Pacman);
+
+    // ...also when only having the name of the atlas
+    assert_eq!(
+        resource.handle_by_name("Pacman"),
+        Some(texture_atlas_handle.clone())
+    );
+    assert_eq!(resource.handle_by_name("Pacmann"), None);
+
+    // ...and the other way around
+    assert_eq!(
+        resource.variant_of(&texture_atlas_handle),
+        Some(&MyAtlasTextures::Pacman)
+    );
+    assert_eq!(resource.variant_of(&Handle::default()), None);
+
+    // the regions of the atlas are available as well
+    assert!(!resource.is_empty());
+    assert!(AtlasTextures::<MyAtlasTextures>::default().is_empty());
+    assert_eq!(resource.len(MyAtlasTextures::Pacman), 9);
+    assert_eq!(
+        resource
+            .tile_rect(MyAtlasTextures::Pacman, 4)
+            .map(|rect| (rect.min, rect.max)),
+        Some((Vec2::new(19., 19.), Vec2::new(38., 38.)))
+    );
+    assert_eq!(resource.tile_rect(MyAtlasTextures::Pacman, 9), None);
+    assert_eq!(resource.nine_slice(MyAtlasTextures::Pacman, 0), None);
+    assert_eq!(resource.source_image(MyAtlasTextures::Pacman), None);
+    assert_eq!(resource.default_index(MyAtlasTextures::Pacman), Some(0));
+
+    // a single atlas makes up the only page
+    assert_eq!(
+        resource.pages(MyAtlasTextures::Pacman),
+        Some(vec![texture_atlas_handle.clone()])
+    );
+    assert_eq!(
+        resource.page_and_index(MyAtlasTextures::Pacman, 4),
+        Some((0, 4))
+    );
+    assert_eq!(resource.page_and_index(MyAtlasTextures::Pacman, 9), None);
+
+    // the 380x231 image is RGBA
+    let images = app.world.get_resource::<Assets<Image>>().unwrap();
+    assert_eq!(resource.estimated_bytes(images), 380 * 231 * 4);
+
+    // ...or by iterating all of them, even from a snapshot
+    let snapshot = resource.clone();
+    let by_name = snapshot
+        .clone()
+        .map_keys(|t| format!("{:?}", t).to_lowercase());
+    assert_eq!(
+        by_name.try_handle(String::from("pacman")),
+        Some(texture_atlas_handle.clone())
+    );
+    assert_eq!(by_name.len(String::from("pacman")), 9);
+    assert_eq!(
+        snapshot.into_iter().collect::<Vec<_>>(),
+        vec![(&MyAtlasTextures::Pacman, texture_atlas_handle)]
+    );
 }
 
 #[test]
-fn undefined_entries_causes_failure() {
-    let mut app = minimal_bevy_app();
+fn created_atlases_are_announced_individually() {
+    let mut app = minimal_app();
     app.add_plugin(AtlasTexturePlugin::<MyAtlasTextures>::default());
-
-    // an empty definition does not cut it!
     app.add_startup_system(move |mut cmds: Commands| {
         cmds.insert_resource(TypedAtlasDefinition::<MyAtlasTextures>::from(
-            [].into_iter().collect::<HashMap<String, AtlasDefinition>>(),
+            [(
+                String::from("Pacman"),
+                AtlasDefinition::from(GridAtlasDefinition::grid(
+                    Path::new("Pac-Man.png"),
+                    3,
+                    3,
+                    UVec2::new(19, 19),
+                )),
+            )]
+            .into_iter()
+            .collect::<HashMap<String, AtlasDefinition>>(),
         ));
     });
 
-    // add system for capturing event
-    let is_failed = Arc::new(AtomicBool::new(false));
+    // add system for capturing the created atlases
+    let created = Arc::new(Mutex::new(Vec::new()));
     app.add_system({
-        let is_failed = is_failed.clone();
+        let created = created.clone();
         move |mut events: EventReader<AtlasTexturesEvent<MyAtlasTextures>>| {
+            created.lock().unwrap().extend(
+                events
+                    .iter()
+                    .filter_map(|e| e.atlas())
+                    .map(|(index, handle)| (*index, handle.clone())),
+            );
+        }
+    });
+
+    // spin Bevy a few times...
+    (0..100).for_each(|_| app.update());
+
+    // the event carries the same handle as the resource
+    let resource = app
+        .world
+        .get_resource::<AtlasTextures<MyAtlasTextures>>()
+        .unwrap();
+    assert_eq!(
+        *created.lock().unwrap(),
+        vec![(
+            MyAtlasTextures::Pacman,
+            resource.handle(MyAtlasTextures::Pacman)
+        )]
+    );
+}
+
+#[test]
+fn dynamic_keys_create_whatever_is_defined() {
+    let mut app = minimal_app();
+    app.add_plugin(AtlasTexturePlugin::<DynamicAtlasKey>::default());
+
+    let definitions = |keys: &[&str]| {
+        TypedAtlasDefinition::<DynamicAtlasKey>::from(
+            keys.iter()
+                .map(|&key| {
+                    (
+                        String::from(key),
+                        AtlasDefinition::from(GridAtlasDefinition::grid(
+                            Path::new("Pac-Man.png"),
+                            3,
+                            3,
+                            UVec2::new(19, 19),
+                        )),
+                    )
+                })
+                .collect::<HashMap<String, AtlasDefinition>>(),
+        )
+    };
+
+    app.world.insert_resource(definitions(&["Pacman"]));
+    (0..100).for_each(|_| app.update());
+    let resource = app.world.get_resource::<DynamicAtlasTextures>().unwrap();
+    assert!(resource.contains(DynamicAtlasKey::from("Pacman")));
+    assert!(!resource.contains(DynamicAtlasKey::from("Ghost")));
+
+    // e.g. a mod adding further atlas'
+    app.world.insert_resource(definitions(&["Pacman", "Ghost"]));
+    (0..100).for_each(|_| app.update());
+    let resource = app.world.get_resource::<DynamicAtlasTextures>().unwrap();
+    assert!(resource.contains(DynamicAtlasKey::from("Pacman")));
+    assert!(resource.handle_by_name("Ghost").is_some());
+}
+
+#[test]
+fn variants_can_be_requested_on_demand() {
+    #[derive(
+        Debug, PartialEq, Eq, Hash, Clone, Copy, strum::EnumVariantNames, strum::EnumString,
+    )]
+    enum SceneAtlasTextures {
+        Pacman,
+        Ghost,
+        Cherry,
+    }
+
+    let mut app = minimal_app();
+    app.add_plugin(AtlasTexturePlugin::<SceneAtlasTextures>::default());
+
+    // "Cherry" is never requested, and so needn't be defined
+    app.world.insert_resource(
+        TypedAtlasDefinition::<SceneAtlasTextures>::from(
+            ["Pacman", "Ghost"]
+                .into_iter()
+                .map(|key| {
+                    (
+                        String::from(key),
+                        AtlasDefinition::from(GridAtlasDefinition::grid(
+                            Path::new("Pac-Man.png"),
+                            3,
+                            3,
+                            UVec2::new(19, 19),
+                        )),
+                    )
+                })
+                .collect::<HashMap<String, AtlasDefinition>>(),
+        )
+        .with_variants(&[SceneAtlasTextures::Pacman]),
+    );
+
+    // add system for counting the batches created
+    let created = Arc::new(std::sync::atomic::AtomicUsize::new(0));
+    app.add_system({
+        let created = created.clone();
+        move |mut events: EventReader<AtlasTexturesEvent<SceneAtlasTextures>>| {
             for e in events.iter() {
-                if e.status().is_failed() {
-                    is_failed.store(true, std::sync::atomic::Ordering::Release);
+                if e.status().is_created() {
+                    created.fetch_add(1, std::sync::atomic::Ordering::AcqRel);
                 }
             }
         }
     });
 
+    (0..100).for_each(|_| app.update());
+    assert_eq!(created.load(std::sync::atomic::Ordering::Acquire), 1);
+    let resource = app
+        .world
+        .get_resource::<AtlasTextures<SceneAtlasTextures>>()
+        .unwrap();
+    assert!(resource.contains(SceneAtlasTextures::Pacman));
+    assert!(!resource.contains(SceneAtlasTextures::Ghost));
+    let pacman = resource.handle(SceneAtlasTextures::Pacman);
+
+    // e.g. entering the next scene
+    app.world
+        .get_resource_mut::<TypedAtlasDefinition<SceneAtlasTextures>>()
+        .unwrap()
+        .request_variants(&[SceneAtlasTextures::Ghost]);
+    (0..100).for_each(|_| app.update());
+    assert_eq!(created.load(std::sync::atomic::Ordering::Acquire), 2);
+    let resource = app
+        .world
+        .get_resource::<AtlasTextures<SceneAtlasTextures>>()
+        .unwrap();
+    assert!(resource.contains(SceneAtlasTextures::Ghost));
+    // the existing atlas isn't recreated
+    assert_eq!(resource.handle(SceneAtlasTextures::Pacman), pacman);
+}
+
+#[test]
+fn profiles_replace_the_usual_definitions() {
+    let mut app = minimal_app();
+    app.add_plugin(AtlasTexturePlugin::<MyAtlasTextures>::default());
+
+    // the high-contrast profile lays out the sheet differently
+    app.world
+        .insert_resource(TypedAtlasDefinition::<MyAtlasTextures>::from(
+            [
+                (
+                    String::from("Pacman"),
+                    AtlasDefinition::from(GridAtlasDefinition::grid(
+                        Path::new("Pac-Man.png"),
+                        3,
+                        3,
+                        UVec2::new(19, 19),
+                    )),
+                ),
+                (
+                    String::from("Pacman@high-contrast"),
+                    AtlasDefinition::from(GridAtlasDefinition::grid(
+                        Path::new("Pac-Man.png"),
+                        2,
+                        2,
+                        UVec2::new(19, 19),
+                    )),
+                ),
+            ]
+            .into_iter()
+            .collect::<HashMap<String, AtlasDefinition>>(),
+        ));
+    let len = |app: &App| {
+        app.world
+            .get_resource::<AtlasTextures<MyAtlasTextures>>()
+            .unwrap()
+            .len(MyAtlasTextures::Pacman)
+    };
+    let set_profile = |app: &mut App, profile| {
+        app.world
+            .get_resource_mut::<TypedAtlasDefinition<MyAtlasTextures>>()
+            .unwrap()
+            .set_profile(profile);
+    };
+
+    (0..100).for_each(|_| app.update());
+    assert_eq!(len(&app), 9);
+
+    set_profile(&mut app, Some("high-contrast"));
+    (0..100).for_each(|_| app.update());
+    assert_eq!(len(&app), 4);
+
+    set_profile(&mut app, None);
+    (0..100).for_each(|_| app.update());
+    assert_eq!(len(&app), 9);
+}
+
+#[test]
+fn marked_entities_get_the_created_atlas() {
+    let mut app = minimal_app();
+    app.add_plugin(AtlasTexturePlugin::<MyAtlasTextures>::default())
+        .add_system(sync_atlas_sprite_handles::<MyAtlasTextures>);
+
+    app.add_startup_system(move |mut cmds: Commands| {
+        cmds.insert_resource(TypedAtlasDefinition::<MyAtlasTextures>::from(
+            [(
+                String::from("Pacman"),
+                AtlasDefinition::from(GridAtlasDefinition::grid(
+                    Path::new("Pac-Man.png"),
+                    3,
+                    3,
+                    UVec2::new(19, 19),
+                )),
+            )]
+            .into_iter()
+            .collect::<HashMap<String, AtlasDefinition>>(),
+        ));
+    });
+    let entity = app
+        .world
+        .spawn()
+        .insert(UsesAtlasTexture(MyAtlasTextures::Pacman))
+        .insert(Handle::<TextureAtlas>::default())
+        .id();
+
     // spin Bevy a few times...
     (0..100).for_each(|_| app.update());
 
-    // event signalling everything OK
-    assert!(is_failed.load(std::sync::atomic::Ordering::Acquire));
+    // the entity now refers the created atlas
+    let resource = app
+        .world
+        .get_resource::<AtlasTextures<MyAtlasTextures>>()
+        .unwrap();
+    assert_eq!(
+        app.world.get::<Handle<TextureAtlas>>(entity),
+        Some(&resource.handle(MyAtlasTextures::Pacman))
+    );
+}
 
-    // resource with the loaded TextureAtlas is NOT  available
-    assert!(!app
+#[test]
+fn marked_entities_get_the_partially_created_atlas() {
+    #[derive(
+        Debug, PartialEq, Eq, Hash, Clone, Copy, strum::EnumVariantNames, strum::EnumString,
+    )]
+    enum TwoAtlasTextures {
+        Good,
+        Bad,
+    }
+
+    let mut app = minimal_app();
+    app.add_plugin(AtlasTexturePlugin::<TwoAtlasTextures>::default().with_lenient(true))
+        .add_system(sync_atlas_sprite_handles::<TwoAtlasTextures>);
+
+    // the image is only 380 pixels wide
+    app.add_startup_system(move |mut cmds: Commands| {
+        cmds.insert_resource(TypedAtlasDefinition::<TwoAtlasTextures>::from(
+            [
+                (
+                    String::from("Good"),
+                    AtlasDefinition::from(GridAtlasDefinition::grid(
+                        Path::new("Pac-Man.png"),
+                        3,
+                        3,
+                        UVec2::new(19, 19),
+                    )),
+                ),
+                (
+                    String::from("Bad"),
+                    AtlasDefinition::from(GridAtlasDefinition::grid(
+                        Path::new("Pac-Man.png"),
+                        30,
+                        3,
+                        UVec2::new(19, 19),
+                    )),
+                ),
+            ]
+            .into_iter()
+            .collect::<HashMap<String, AtlasDefinition>>(),
+        ));
+    });
+    let good = app
         .world
-        .contains_resource::<AtlasTextures<MyAtlasTextures>>());
+        .spawn()
+        .insert(UsesAtlasTexture(TwoAtlasTextures::Good))
+        .insert(Handle::<TextureAtlas>::default())
+        .id();
+    let bad = app
+        .world
+        .spawn()
+        .insert(UsesAtlasTexture(TwoAtlasTextures::Bad))
+        .insert(Handle::<TextureAtlas>::default())
+        .id();
+
+    // spin Bevy a few times...
+    (0..100).for_each(|_| app.update());
+
+    // only the entity using the successful definition refers an atlas
+    let resource = app
+        .world
+        .get_resource::<AtlasTextures<TwoAtlasTextures>>()
+        .unwrap();
+    assert_eq!(
+        app.world.get::<Handle<TextureAtlas>>(good),
+        Some(&resource.handle(TwoAtlasTextures::Good))
+    );
+    assert_eq!(
+        app.world.get::<Handle<TextureAtlas>>(bad),
+        Some(&Handle::<TextureAtlas>::default())
+    );
 }
 
-#[ignore = "Bevy Asset Server does not see invalid paths as failures, thus we can not either!"]
 #[test]
-fn unloadable_paths_causes_failure() {
-    let mut app = minimal_bevy_app();
+fn source_images_can_be_retained() {
+    let mut app = minimal_app();
+    app.add_plugin(
+        AtlasTexturePlugin::<MyAtlasTextures>::default().with_retain_source_images(true),
+    );
+
+    app.add_startup_system(move |mut cmds: Commands| {
+        cmds.insert_resource(TypedAtlasDefinition::<MyAtlasTextures>::from(
+            [(
+                String::from("Pacman"),
+                AtlasDefinition::from(
+                    GridAtlasDefinition::grid(Path::new("Pac-Man.png"), 3, 3, UVec2::new(19, 19))
+                        .with_scale(2.0),
+                ),
+            )]
+            .into_iter()
+            .collect::<HashMap<String, AtlasDefinition>>(),
+        ));
+    });
+
+    // spin Bevy a few times...
+    (0..100).for_each(|_| app.update());
+
+    // the original image is still available, next to the resampled atlas image
+    let resource = app
+        .world
+        .get_resource::<AtlasTextures<MyAtlasTextures>>()
+        .unwrap();
+    let source_image = resource
+        .source_image(MyAtlasTextures::Pacman)
+        .cloned()
+        .unwrap();
+    assert_eq!(
+        resource
+            .source_images(MyAtlasTextures::Pacman)
+            .map(|images| images.len()),
+        Some(1)
+    );
+
+    let asset_server = app.world.get_resource::<AssetServer>().unwrap();
+    assert_eq!(
+        asset_server
+            .get_handle_path(&source_image)
+            .map(|path| path.path().to_path_buf()),
+        Some(Path::new("Pac-Man.png").to_path_buf())
+    );
+    assert!(app
+        .world
+        .get_resource::<Assets<Image>>()
+        .unwrap()
+        .contains(&source_image));
+}
+
+#[test]
+fn created_atlases_can_be_post_processed() {
+    let mut app = minimal_app();
+    app.add_plugin(
+        AtlasTexturePlugin::<MyAtlasTextures>::default().with_post_process(|key, atlas| {
+            assert_eq!(key, "Pacman");
+            atlas.add_texture(Rect {
+                min: Vec2::new(0., 0.),
+                max: Vec2::new(57., 57.),
+            });
+        }),
+    );
+
+    app.add_startup_system(move |mut cmds: Commands| {
+        cmds.insert_resource(TypedAtlasDefinition::<MyAtlasTextures>::from(
+            [(
+                String::from("Pacman"),
+                AtlasDefinition::from(GridAtlasDefinition::grid(
+                    Path::new("Pac-Man.png"),
+                    3,
+                    3,
+                    UVec2::new(19, 19),
+                )),
+            )]
+            .into_iter()
+            .collect::<HashMap<String, AtlasDefinition>>(),
+        ));
+    });
+
+    // spin Bevy a few times...
+    (0..100).for_each(|_| app.update());
+
+    // the region added by the hook follows the tiles of the grid
+    let resource = app
+        .world
+        .get_resource::<AtlasTextures<MyAtlasTextures>>()
+        .unwrap();
+    assert_eq!(resource.len(MyAtlasTextures::Pacman), 10);
+    assert_eq!(
+        resource
+            .tile_rect(MyAtlasTextures::Pacman, 9)
+            .map(|rect| rect.max),
+        Some(Vec2::new(57., 57.))
+    );
+}
+
+#[test]
+fn layouts_can_be_scaled_for_larger_art() {
+    let mut app = minimal_app();
+    app.add_plugin(AtlasTexturePlugin::<MyAtlasTextures>::default().with_atlas_scale(2.));
+
+    // the definition is made for art half the size of the image
+    app.add_startup_system(move |mut cmds: Commands| {
+        cmds.insert_resource(TypedAtlasDefinition::<MyAtlasTextures>::from(
+            [(
+                String::from("Pacman"),
+                AtlasDefinition::from(GridAtlasDefinition::grid(
+                    Path::new("Pac-Man.png"),
+                    3,
+                    3,
+                    UVec2::new(19, 19),
+                )),
+            )]
+            .into_iter()
+            .collect::<HashMap<String, AtlasDefinition>>(),
+        ));
+    });
+
+    // spin Bevy a few times...
+    (0..100).for_each(|_| app.update());
+
+    let resource = app
+        .world
+        .get_resource::<AtlasTextures<MyAtlasTextures>>()
+        .unwrap();
+    assert_eq!(
+        resource
+            .tile_rect(MyAtlasTextures::Pacman, 4)
+            .map(|rect| (rect.min, rect.max)),
+        Some((Vec2::new(38., 38.), Vec2::new(76., 76.)))
+    );
+}
+
+#[test]
+fn grid_can_span_several_sheets() {
+    let mut app = minimal_app();
     app.add_plugin(AtlasTexturePlugin::<MyAtlasTextures>::default());
 
     app.add_startup_system(move |mut cmds: Commands| {
         cmds.insert_resource(TypedAtlasDefinition::<MyAtlasTextures>::from(
             [(
                 String::from("Pacman"),
-                AtlasDefinition::from(GridAtlasDefinition {
-                    texture: Path::new("invalid-path.png").into(),
-                    columns: 3,
-                    rows: 3,
-                    tile_size: (19, 19),
-                    padding: None,
-                    ..Default::default()
-                }),
+                AtlasDefinition::from(
+                    GridAtlasDefinition::grid(Path::new("Pac-Man.png"), 3, 3, UVec2::new(19, 19))
+                        .with_texture(Path::new("Pac-Man.png")),
+                ),
             )]
             .into_iter()
             .collect::<HashMap<String, AtlasDefinition>>(),
         ));
     });
 
-    // add system for capturing event
-    let is_failed = Arc::new(AtomicBool::new(false));
-    app.add_system({
-        let is_failed = is_failed.clone();
-        move |mut events: EventReader<AtlasTexturesEvent<MyAtlasTextures>>| {
-            for e in events.iter() {
-                if e.status().is_failed() {
-                    is_failed.store(true, std::sync::atomic::Ordering::Release);
-                }
-            }
+    // spin Bevy a few times...
+    (0..100).for_each(|_| app.update());
+
+    // the tiles of the second sheet follow those of the first, placed below it
+    let resource = app
+        .world
+        .get_resource::<AtlasTextures<MyAtlasTextures>>()
+        .unwrap();
+    assert_eq!(resource.len(MyAtlasTextures::Pacman), 18);
+    assert_eq!(
+        resource
+            .tile_rect(MyAtlasTextures::Pacman, 9)
+            .map(|rect| (rect.min, rect.max)),
+        Some((Vec2::new(0., 231.), Vec2::new(19., 250.)))
+    );
+}
+
+#[test]
+fn definition_can_use_an_image_asset() {
+    let mut app = minimal_app();
+    app.add_plugin(AtlasTexturePlugin::<MyAtlasTextures>::default());
+
+    // the image is generated, rather than loaded
+    app.add_startup_system(
+        move |mut cmds: Commands, mut images: ResMut<Assets<Image>>| {
+            let image = images.add(Image::new_fill(
+                Extent3d {
+                    width: 40,
+                    height: 20,
+                    depth_or_array_layers: 1,
+                },
+                TextureDimension::D2,
+                &[255, 255, 255, 255],
+                TextureFormat::Rgba8UnormSrgb,
+            ));
+            cmds.insert_resource(TypedAtlasDefinition::<MyAtlasTextures>::from(
+                [(
+                    String::from("Pacman"),
+                    AtlasDefinition::from(
+                        GridAtlasDefinition::grid_from_image(image, 2, 1, UVec2::new(20, 20))
+                            .with_nine_patch(NineSliceBorders::new(2, 2, 4, 4))
+                            .with_linear(true),
+                    ),
+                )]
+                .into_iter()
+                .collect::<HashMap<String, AtlasDefinition>>(),
+            ));
+        },
+    );
+
+    // spin Bevy a few times...
+    (0..100).for_each(|_| app.update());
+
+    let resource = app
+        .world
+        .get_resource::<AtlasTextures<MyAtlasTextures>>()
+        .unwrap();
+    assert_eq!(resource.len(MyAtlasTextures::Pacman), 2);
+    assert_eq!(
+        resource.nine_slice(MyAtlasTextures::Pacman, 1),
+        Some(NineSliceBorders::new(2, 2, 4, 4))
+    );
+    assert_eq!(resource.nine_slice(MyAtlasTextures::Pacman, 2), None);
+
+    // the image is now sampled as linear
+    let atlas = app
+        .world
+        .get_resource::<Assets<TextureAtlas>>()
+        .unwrap()
+        .get(resource.handle(MyAtlasTextures::Pacman))
+        .unwrap();
+    let image = app
+        .world
+        .get_resource::<Assets<Image>>()
+        .unwrap()
+        .get(&atlas.texture)
+        .unwrap();
+    assert_eq!(image.texture_descriptor.format, TextureFormat::Rgba8Unorm);
+}
+
+#[test]
+fn image_options_leave_the_source_image_as_is() {
+    let mut app = minimal_app();
+    app.add_plugin(AtlasTexturePlugin::<MyAtlasTextures>::default());
+
+    // the image is generated, rather than loaded
+    let source = Arc::new(Mutex::new(None));
+    app.add_startup_system({
+        let source = source.clone();
+        move |mut cmds: Commands, mut images: ResMut<Assets<Image>>| {
+            let image = images.add(Image::new_fill(
+                Extent3d {
+                    width: 40,
+                    height: 20,
+                    depth_or_array_layers: 1,
+                },
+                TextureDimension::D2,
+                &[200, 100, 50, 128],
+                TextureFormat::Rgba8UnormSrgb,
+            ));
+            *source.lock().unwrap() = Some(image.clone());
+            cmds.insert_resource(TypedAtlasDefinition::<MyAtlasTextures>::from(
+                [(
+                    String::from("Pacman"),
+                    AtlasDefinition::from(
+                        GridAtlasDefinition::grid_from_image(image, 2, 1, UVec2::new(20, 20))
+                            .with_linear(true)
+                            .with_premultiply_alpha(true)
+                            .with_generate_mipmaps(true)
+                            .with_address_mode(AddressMode::Repeat),
+                    ),
+                )]
+                .into_iter()
+                .collect::<HashMap<String, AtlasDefinition>>(),
+            ));
         }
     });
 
     // spin Bevy a few times...
     (0..100).for_each(|_| app.update());
 
-    // unfortunately, bevy asset server does not count non-existant paths as failures :-(
-    assert!(is_failed.load(std::sync::atomic::Ordering::Acquire));
+    let resource = app.world.resource::<AtlasTextures<MyAtlasTextures>>();
+    let atlas = app
+        .world
+        .resource::<Assets<TextureAtlas>>()
+        .get(resource.handle(MyAtlasTextures::Pacman))
+        .unwrap();
+    let source = source.lock().unwrap().clone().unwrap();
+    assert_ne!(atlas.texture, source);
 
-    // resource with the loaded TextureAtlas is NOT  available
-    assert!(!app
+    // the atlas has a processed copy of the image...
+    let images = app.world.resource::<Assets<Image>>();
+    let image = images.get(&atlas.texture).unwrap();
+    assert_eq!(image.texture_descriptor.format, TextureFormat::Rgba8Unorm);
+    assert!(image.texture_descriptor.mip_level_count > 1);
+
+    // ...while the image itself is left for other definitions to use
+    let source = images.get(&source).unwrap();
+    assert_eq!(
+        source.texture_descriptor.format,
+        TextureFormat::Rgba8UnormSrgb
+    );
+    assert_eq!(source.texture_descriptor.mip_level_count, 1);
+    assert_eq!(source.data[..4], [200, 100, 50, 128]);
+    assert_eq!(
+        source.sampler_descriptor.address_mode_u,
+        render_resource::AddressMode::ClampToEdge
+    );
+}
+
+#[test]
+fn generated_images_can_be_packed() {
+    let mut app = minimal_app();
+    app.add_plugin(AtlasTexturePlugin::<MyAtlasTextures>::default());
+
+    // the images are generated, rather than loaded
+    app.add_startup_system(
+        move |mut cmds: Commands, mut images: ResMut<Assets<Image>>| {
+            let sprites = [(8, 8), (16, 8), (4, 12)].map(|(width, height)| {
+                images.add(Image::new_fill(
+                    Extent3d {
+                        width,
+                        height,
+                        depth_or_array_layers: 1,
+                    },
+                    TextureDimension::D2,
+                    &[255, 0, 0, 255],
+                    TextureFormat::Rgba8UnormSrgb,
+                ))
+            });
+            cmds.insert_resource(TypedAtlasDefinition::<MyAtlasTextures>::from(
+                [(
+                    String::from("Pacman"),
+                    AtlasDefinition::from(FolderAtlasDefinition::from_images(sprites)),
+                )]
+                .into_iter()
+                .collect::<HashMap<String, AtlasDefinition>>(),
+            ));
+        },
+    );
+
+    // spin Bevy a few times...
+    (0..100).for_each(|_| app.update());
+
+    let resource = app
         .world
-        .contains_resource::<AtlasTextures<MyAtlasTextures>>());
+        .get_resource::<AtlasTextures<MyAtlasTextures>>()
+        .unwrap();
+    assert_eq!(resource.len(MyAtlasTextures::Pacman), 3);
+}
+
+#[test]
+fn images_can_be_assembled_into_a_texture_array() {
+    let mut app = minimal_app();
+    app.add_plugin(AtlasTexturePlugin::<MyAtlasTextures>::default());
+
+    app.add_startup_system(move |mut cmds: Commands| {
+        cmds.insert_resource(TypedAtlasDefinition::<MyAtlasTextures>::from(
+            [(
+                String::from("Pacman"),
+                AtlasDefinition::from(
+                    ArrayAtlasDefinition::array([Path::new("shapes/square.png")])
+                        .with_layer(Path::new("shapes/square.png")),
+                ),
+            )]
+            .into_iter()
+            .collect::<HashMap<String, AtlasDefinition>>(),
+        ));
+    });
+
+    // spin Bevy a few times...
+    (0..100).for_each(|_| app.update());
+
+    // every layer is a region of the atlas...
+    let resource = app
+        .world
+        .get_resource::<AtlasTextures<MyAtlasTextures>>()
+        .unwrap();
+    assert_eq!(resource.len(MyAtlasTextures::Pacman), 2);
+
+    // ...and a layer of the texture array
+    let array = resource.texture_array(MyAtlasTextures::Pacman).unwrap();
+    let image = app
+        .world
+        .get_resource::<Assets<Image>>()
+        .unwrap()
+        .get(&array)
+        .unwrap();
+    assert_eq!(
+        image.texture_descriptor.size,
+        Extent3d {
+            width: 16,
+            height: 16,
+            depth_or_array_layers: 2,
+        }
+    );
+    assert_eq!(image.texture_descriptor.dimension, TextureDimension::D2);
+}
+
+#[test]
+fn texture_array_layers_must_share_size() {
+    let mut app = minimal_app();
+    app.add_plugin(AtlasTexturePlugin::<MyAtlasTextures>::default());
+
+    app.add_startup_system(move |mut cmds: Commands| {
+        cmds.insert_resource(TypedAtlasDefinition::<MyAtlasTextures>::from(
+            [(
+                String::from("Pacman"),
+                AtlasDefinition::from(ArrayAtlasDefinition::array([
+                    Path::new("shapes/square.png"),
+                    Path::new("shapes/wide.png"),
+                ])),
+            )]
+            .into_iter()
+            .collect::<HashMap<String, AtlasDefinition>>(),
+        ));
+    });
+
+    // add system for capturing event
+    let is_mismatched = Arc::new(AtomicBool::new(false));
+    app.add_system({
+        let is_mismatched = is_mismatched.clone();
+        move |mut events: EventReader<AtlasTexturesEvent<MyAtlasTextures>>| {
+            for e in events.iter() {
+                if e.status().is_failed()
+                    && e.error()
+                        .map_or(false, |error| error.is_mismatched_layer_sizes())
+                {
+                    is_mismatched.store(true, std::sync::atomic::Ordering::Release);
+                }
+            }
+        }
+    });
+
+    // spin Bevy a few times...
+    (0..100).for_each(|_| app.update());
+
+    assert!(is_mismatched.load(std::sync::atomic::Ordering::Acquire));
+    assert!(!app
+        .world
+        .contains_resource::<AtlasTextures<MyAtlasTextures>>());
+}
+
+#[test]
+fn folder_images_are_packed_with_padding() {
+    let mut app = minimal_app();
+    app.add_plugin(AtlasTexturePlugin::<MyAtlasTextures>::default());
+
+    app.add_startup_system(move |mut cmds: Commands| {
+        cmds.insert_resource(TypedAtlasDefinition::<MyAtlasTextures>::from(
+            [(
+                String::from("Pacman"),
+                AtlasDefinition::from(
+                    FolderAtlasDefinition::folder(Path::new("shapes")).with_output_padding(2),
+                ),
+            )]
+            .into_iter()
+            .collect::<HashMap<String, AtlasDefinition>>(),
+        ));
+    });
+
+    // spin Bevy a few times...
+    (0..100).for_each(|_| app.update());
+
+    // the regions keep the size of the images, though with a gap between them
+    let resource = app
+        .world
+        .get_resource::<AtlasTextures<MyAtlasTextures>>()
+        .unwrap();
+    let mut rects = (0..resource.len(MyAtlasTextures::Pacman))
+        .map(|index| resource.tile_rect(MyAtlasTextures::Pacman, index).unwrap())
+        .collect::<Vec<_>>();
+    let mut sizes = rects
+        .iter()
+        .map(|rect| (rect.max - rect.min).as_uvec2().to_array())
+        .collect::<Vec<_>>();
+    sizes.sort_unstable();
+    assert_eq!(sizes, vec![[8, 24], [16, 16], [24, 8]]);
+
+    // the packing is available by the path of the images
+    let layout = resource.folder_layout(MyAtlasTextures::Pacman).unwrap();
+    assert_eq!(
+        layout.iter().map(|(_, rect)| *rect).collect::<Vec<_>>(),
+        rects
+    );
+    let mut paths = layout
+        .iter()
+        .map(|(path, _)| path.clone())
+        .collect::<Vec<_>>();
+    paths.sort();
+    assert_eq!(
+        paths,
+        ["shapes/square.png", "shapes/tall.png", "shapes/wide.png"].map(PathBuf::from)
+    );
+    rects.iter().for_each(|rect| {
+        assert!(rect.min.cmpge(Vec2::splat(2.)).all());
+    });
+    while let Some(rect) = rects.pop() {
+        for other in &rects {
+            let gap = (other.min - rect.max).max(rect.min - other.max);
+            assert!(
+                gap.max_element() >= 4.,
+                "{:?} too close to {:?}",
+                rect,
+                other
+            );
+        }
+    }
+}
+
+#[test]
+fn remapped_indexes_outside_the_atlas_are_left_out() {
+    let mut app = minimal_app();
+    app.add_plugin(AtlasTexturePlugin::<MyAtlasTextures>::default());
+
+    app.add_startup_system(move |mut cmds: Commands| {
+        cmds.insert_resource(TypedAtlasDefinition::<MyAtlasTextures>::from(
+            [(
+                String::from("Pacman"),
+                AtlasDefinition::from(
+                    GridAtlasDefinition::grid(Path::new("Pac-Man.png"), 3, 3, UVec2::new(19, 19))
+                        .with_remap("last", 8)
+                        .with_remap("outside", 999),
+                ),
+            )]
+            .into_iter()
+            .collect::<HashMap<String, AtlasDefinition>>(),
+        ));
+    });
+
+    (0..100).for_each(|_| app.update());
+
+    let resource = app
+        .world
+        .get_resource::<AtlasTextures<MyAtlasTextures>>()
+        .unwrap();
+    assert_eq!(resource.index_of(MyAtlasTextures::Pacman, "last"), Some(8));
+    assert_eq!(resource.index_of(MyAtlasTextures::Pacman, "outside"), None);
+    assert!(resource
+        .sprite_bundle_named(MyAtlasTextures::Pacman, "outside", None)
+        .is_none());
+}
+
+#[test]
+fn pages_are_indexed_continuously() {
+    let mut app = minimal_app();
+    app.add_plugin(AtlasTexturePlugin::<MyAtlasTextures>::default());
+
+    app.add_startup_system(move |mut cmds: Commands| {
+        cmds.insert_resource(TypedAtlasDefinition::<MyAtlasTextures>::from(
+            [(
+                String::from("Pacman"),
+                AtlasDefinition::from(
+                    PagedAtlasDefinition::pages([GridAtlasDefinition::grid(
+                        Path::new("Pac-Man.png"),
+                        3,
+                        3,
+                        UVec2::new(19, 19),
+                    )])
+                    .with_page(GridAtlasDefinition::grid(
+                        Path::new("shapes/square.png"),
+                        1,
+                        1,
+                        UVec2::new(16, 16),
+                    ))
+                    .with_remap("square", 9),
+                ),
+            )]
+            .into_iter()
+            .collect::<HashMap<String, AtlasDefinition>>(),
+        ));
+    });
+
+    (0..100).for_each(|_| app.update());
+
+    let resource = app
+        .world
+        .get_resource::<AtlasTextures<MyAtlasTextures>>()
+        .unwrap();
+    let pages = resource.pages(MyAtlasTextures::Pacman).unwrap();
+    assert_eq!(pages.len(), 2);
+    assert_eq!(pages[0], resource.handle(MyAtlasTextures::Pacman));
+    assert_ne!(pages[0], pages[1]);
+
+    // the tiles of the second page follow those of the first
+    assert_eq!(resource.len(MyAtlasTextures::Pacman), 10);
+    let square = resource
+        .index_of(MyAtlasTextures::Pacman, "square")
+        .unwrap();
+    assert_eq!(
+        resource.page_and_index(MyAtlasTextures::Pacman, square),
+        Some((1, 0))
+    );
+    assert_eq!(
+        resource.page_and_index(MyAtlasTextures::Pacman, 8),
+        Some((0, 8))
+    );
+    assert_eq!(resource.page_and_index(MyAtlasTextures::Pacman, 10), None);
+
+    // sprites show the page holding the index
+    let bundle = resource
+        .sprite_bundle_named(MyAtlasTextures::Pacman, "square", None)
+        .unwrap();
+    assert_eq!(bundle.texture_atlas, pages[1]);
+    assert_eq!(bundle.sprite.index, 0);
+    assert!(resource
+        .sprite_bundle_named(MyAtlasTextures::Pacman, "missing", None)
+        .is_none());
+    assert_eq!(
+        resource
+            .tile_rect(MyAtlasTextures::Pacman, square)
+            .map(|rect| (rect.min, rect.max)),
+        Some((Vec2::ZERO, Vec2::new(16., 16.)))
+    );
+
+    let texture_atlases = app.world.get_resource::<Assets<TextureAtlas>>().unwrap();
+    assert_eq!(texture_atlases.get(&pages[1]).unwrap().len(), 1);
+}
+
+#[test]
+fn grids_can_share_a_combined_atlas() {
+    let mut app = minimal_app();
+    app.add_plugin(AtlasTexturePlugin::<DynamicAtlasKey>::default());
+
+    app.add_startup_system(move |mut cmds: Commands| {
+        cmds.insert_resource(TypedAtlasDefinition::<DynamicAtlasKey>::from(
+            [
+                (
+                    String::from("Pacman"),
+                    AtlasDefinition::from(
+                        GridAtlasDefinition::grid(
+                            Path::new("Pac-Man.png"),
+                            3,
+                            3,
+                            UVec2::new(19, 19),
+                        )
+                        .with_combine("sprites"),
+                    ),
+                ),
+                (
+                    String::from("Square"),
+                    AtlasDefinition::from(
+                        GridAtlasDefinition::grid(
+                            Path::new("shapes/square.png"),
+                            1,
+                            1,
+                            UVec2::new(16, 16),
+                        )
+                        .with_combine("sprites"),
+                    ),
+                ),
+            ]
+            .into_iter()
+            .collect::<HashMap<String, AtlasDefinition>>(),
+        ));
+    });
+
+    (0..100).for_each(|_| app.update());
+
+    let resource = app.world.get_resource::<DynamicAtlasTextures>().unwrap();
+    let (pacman, square) = (
+        DynamicAtlasKey::from("Pacman"),
+        DynamicAtlasKey::from("Square"),
+    );
+    assert_eq!(resource.handle(&pacman), resource.handle(&square));
+
+    // sharing the handle, the grids can't be told apart by it
+    let variant = resource.variant_of(&resource.handle(&square)).unwrap();
+    assert!(*variant == pacman || *variant == square);
+
+    // the regions of each grid are a range of the shared atlas, stacked in the order of the keys
+    assert_eq!(resource.len(&pacman), 9);
+    assert_eq!(resource.len(&square), 1);
+    assert_eq!(resource.index_offset(&pacman), Some(0));
+    assert_eq!(resource.index_offset(&square), Some(9));
+    let bundle = resource
+        .sprite_bundle(&square, 0, Some(Vec2::new(32., 32.)))
+        .unwrap();
+    assert_eq!(bundle.texture_atlas, resource.handle(&pacman));
+    assert_eq!(bundle.sprite.index, 9);
+    assert_eq!(bundle.sprite.custom_size, Some(Vec2::new(32., 32.)));
+    assert_eq!(
+        resource
+            .tile_rect(&square, 0)
+            .map(|rect| (rect.min, rect.max)),
+        Some((Vec2::new(0., 231.), Vec2::new(16., 247.)))
+    );
+
+    let texture_atlases = app.world.get_resource::<Assets<TextureAtlas>>().unwrap();
+    assert_eq!(
+        texture_atlases
+            .get(&resource.handle(&pacman))
+            .unwrap()
+            .len(),
+        10
+    );
+}
+
+#[test]
+fn opaque_areas_are_detected_as_regions() {
+    let mut app = minimal_app();
+    app.add_plugin(AtlasTexturePlugin::<MyAtlasTextures>::default());
+
+    app.add_startup_system(move |mut cmds: Commands| {
+        cmds.insert_resource(TypedAtlasDefinition::<MyAtlasTextures>::from(
+            [(
+                String::from("Pacman"),
+                AtlasDefinition::from(AutoDetectAtlasDefinition::auto_detect(
+                    Path::new("shapes/tall.png"),
+                    0,
+                )),
+            )]
+            .into_iter()
+            .collect::<HashMap<String, AtlasDefinition>>(),
+        ));
+    });
+
+    (0..100).for_each(|_| app.update());
+
+    // the fully opaque image is a single area
+    let resource = app
+        .world
+        .get_resource::<AtlasTextures<MyAtlasTextures>>()
+        .unwrap();
+    assert_eq!(resource.len(MyAtlasTextures::Pacman), 1);
+    assert_eq!(
+        resource
+            .tile_rect(MyAtlasTextures::Pacman, 0)
+            .map(|rect| (rect.min, rect.max)),
+        Some((Vec2::ZERO, Vec2::new(8., 24.)))
+    );
+}
+
+#[test]
+fn images_matching_a_glob_are_packed() {
+    let mut app = minimal_app();
+    app.add_plugin(AtlasTexturePlugin::<MyAtlasTextures>::default());
+
+    // matches square.png and tall.png, but not wide.png
+    app.add_startup_system(move |mut cmds: Commands| {
+        cmds.insert_resource(TypedAtlasDefinition::<MyAtlasTextures>::from(
+            [(
+                String::from("Pacman"),
+                AtlasDefinition::from(GlobAtlasDefinition::glob("shapes/*a*.png")),
+            )]
+            .into_iter()
+            .collect::<HashMap<String, AtlasDefinition>>(),
+        ));
+    });
+
+    (0..100).for_each(|_| app.update());
+
+    let resource = app
+        .world
+        .get_resource::<AtlasTextures<MyAtlasTextures>>()
+        .unwrap();
+    let mut paths = resource
+        .folder_layout(MyAtlasTextures::Pacman)
+        .unwrap()
+        .iter()
+        .map(|(path, _)| path.clone())
+        .collect::<Vec<_>>();
+    paths.sort();
+    assert_eq!(
+        paths,
+        ["shapes/square.png", "shapes/tall.png"].map(PathBuf::from)
+    );
+}
+
+#[test]
+fn unused_space_of_packed_atlases_can_be_filled() {
+    let mut app = minimal_app();
+    app.add_plugin(AtlasTexturePlugin::<MyAtlasTextures>::default());
+
+    app.add_startup_system(move |mut cmds: Commands| {
+        cmds.insert_resource(TypedAtlasDefinition::<MyAtlasTextures>::from(
+            [(
+                String::from("Pacman"),
+                AtlasDefinition::from(
+                    FolderAtlasDefinition::folder(Path::new("shapes"))
+                        .with_output_padding(2)
+                        .with_background(Color::rgba(1., 0., 1., 1.)),
+                ),
+            )]
+            .into_iter()
+            .collect::<HashMap<String, AtlasDefinition>>(),
+        ));
+    });
+
+    (0..100).for_each(|_| app.update());
+
+    // the padding at the top-left corner of the atlas is filled
+    let resource = app
+        .world
+        .get_resource::<AtlasTextures<MyAtlasTextures>>()
+        .unwrap();
+    let texture_atlases = app.world.get_resource::<Assets<TextureAtlas>>().unwrap();
+    let atlas = texture_atlases
+        .get(resource.handle(MyAtlasTextures::Pacman))
+        .unwrap();
+    let images = app.world.get_resource::<Assets<Image>>().unwrap();
+    let image = images.get(&atlas.texture).unwrap();
+    assert_eq!(image.data[..4], [255, 0, 255, 255]);
+}
+
+#[derive(Default)]
+struct WakeFlag(AtomicBool);
+
+impl Wake for WakeFlag {
+    fn wake(self: Arc<Self>) {
+        self.0.store(true, std::sync::atomic::Ordering::Release);
+    }
+}
+
+#[test]
+fn readiness_can_be_awaited() {
+    let mut app = minimal_app();
+    app.add_plugin(AtlasTexturePlugin::<MyAtlasTextures>::default());
+
+    app.add_startup_system(move |mut cmds: Commands| {
+        cmds.insert_resource(TypedAtlasDefinition::<MyAtlasTextures>::from(
+            [(
+                String::from("Pacman"),
+                AtlasDefinition::from(GridAtlasDefinition::grid(
+                    Path::new("Pac-Man.png"),
+                    3,
+                    3,
+                    UVec2::new(19, 19),
+                )),
+            )]
+            .into_iter()
+            .collect::<HashMap<String, AtlasDefinition>>(),
+        ));
+    });
+
+    // the future is pending until the atlases are created
+    let readiness = app
+        .world
+        .get_resource::<AtlasReadiness<MyAtlasTextures>>()
+        .unwrap()
+        .clone();
+    let mut ready = Box::pin(readiness.wait());
+    let woken = Arc::new(WakeFlag::default());
+    let waker = Waker::from(woken.clone());
+    let mut context = Context::from_waker(&waker);
+    assert!(ready.as_mut().poll(&mut context).is_pending());
+    assert_eq!(readiness.outcome(), None);
+
+    // spin Bevy a few times...
+    (0..100).for_each(|_| app.update());
+
+    // the waiting code is woken, and completes
+    assert!(woken.0.load(std::sync::atomic::Ordering::Acquire));
+    assert_eq!(ready.as_mut().poll(&mut context), Poll::Ready(Ok(())));
+    assert_eq!(
+        Box::pin(readiness.wait()).as_mut().poll(&mut context),
+        Poll::Ready(Ok(()))
+    );
+}
+
+#[test]
+fn disabled_definitions_are_skipped() {
+    let mut app = minimal_app();
+    app.add_plugin(AtlasTexturePlugin::<MyAtlasTextures>::default());
+
+    app.add_startup_system(move |mut cmds: Commands| {
+        cmds.insert_resource(TypedAtlasDefinition::<MyAtlasTextures>::from(
+            [(
+                String::from("Pacman"),
+                AtlasDefinition::from(
+                    GridAtlasDefinition::grid(Path::new("Pac-Man.png"), 3, 3, UVec2::new(19, 19))
+                        .with_enabled(false),
+                ),
+            )]
+            .into_iter()
+            .collect::<HashMap<String, AtlasDefinition>>(),
+        ));
+    });
+
+    // spin Bevy a few times...
+    (0..100).for_each(|_| app.update());
+
+    // resource is created, but without the disabled atlas
+    let resource = app
+        .world
+        .get_resource::<AtlasTextures<MyAtlasTextures>>()
+        .unwrap();
+    assert!(!resource.contains(MyAtlasTextures::Pacman));
+    assert_eq!(resource.try_handle(MyAtlasTextures::Pacman), None);
+}
+
+#[test]
+fn undefined_entries_causes_failure() {
+    let mut app = minimal_app();
+    app.add_plugin(AtlasTexturePlugin::<MyAtlasTextures>::default());
+
+    // an empty definition does not cut it!
+    app.add_startup_system(move |mut cmds: Commands| {
+        cmds.insert_resource(TypedAtlasDefinition::<MyAtlasTextures>::from(
+            [].into_iter().collect::<HashMap<String, AtlasDefinition>>(),
+        ));
+    });
+
+    // add system for capturing event
+    let is_failed = Arc::new(AtomicBool::new(false));
+    app.add_system({
+        let is_failed = is_failed.clone();
+        move |mut events: EventReader<AtlasTexturesEvent<MyAtlasTextures>>| {
+            for e in events.iter() {
+                if e.status().is_failed() {
+                    is_failed.store(true, std::sync::atomic::Ordering::Release);
+                }
+            }
+        }
+    });
+
+    // spin Bevy a few times...
+    (0..100).for_each(|_| app.update());
+
+    // event signalling everything OK
+    assert!(is_failed.load(std::sync::atomic::Ordering::Acquire));
+
+    // resource with the loaded TextureAtlas is NOT  available
+    assert!(!app
+        .world
+        .contains_resource::<AtlasTextures<MyAtlasTextures>>());
+}
+
+#[test]
+fn undefined_entries_can_be_allowed() {
+    #[derive(
+        Debug, PartialEq, Eq, Hash, Clone, Copy, strum::EnumVariantNames, strum::EnumString,
+    )]
+    enum SparseAtlasTextures {
+        Pacman,
+        Ghost,
+    }
+
+    let mut app = minimal_app();
+    app.add_plugin(
+        AtlasTexturePlugin::<SparseAtlasTextures>::default().with_require_all_variants(false),
+    );
+
+    // "Ghost" is left undefined
+    app.add_startup_system(move |mut cmds: Commands| {
+        cmds.insert_resource(TypedAtlasDefinition::<SparseAtlasTextures>::from(
+            [(
+                String::from("Pacman"),
+                AtlasDefinition::from(GridAtlasDefinition::grid(
+                    Path::new("Pac-Man.png"),
+                    3,
+                    3,
+                    UVec2::new(19, 19),
+                )),
+            )]
+            .into_iter()
+            .collect::<HashMap<String, AtlasDefinition>>(),
+        ));
+    });
+
+    // spin Bevy a few times...
+    (0..100).for_each(|_| app.update());
+
+    // only the defined atlas is available
+    let resource = app
+        .world
+        .get_resource::<AtlasTextures<SparseAtlasTextures>>()
+        .unwrap();
+    assert!(resource.contains(SparseAtlasTextures::Pacman));
+    assert_eq!(resource.try_handle(SparseAtlasTextures::Ghost), None);
+}
+
+#[test]
+fn atlas_build_failure_causes_failure() {
+    let mut app = minimal_app();
+    app.add_plugin(AtlasTexturePlugin::<MyAtlasTextures>::default());
+
+    // the folder holds a texture too large for the atlas builder
+    app.add_startup_system(move |mut cmds: Commands| {
+        cmds.insert_resource(TypedAtlasDefinition::<MyAtlasTextures>::from(
+            [(
+                String::from("Pacman"),
+                AtlasDefinition::from(FolderAtlasDefinition::folder(Path::new("oversized"))),
+            )]
+            .into_iter()
+            .collect::<HashMap<String, AtlasDefinition>>(),
+        ));
+    });
+
+    // add system for capturing event
+    let is_build_failure = Arc::new(AtomicBool::new(false));
+    app.add_system({
+        let is_build_failure = is_build_failure.clone();
+        move |mut events: EventReader<AtlasTexturesEvent<MyAtlasTextures>>| {
+            for e in events.iter() {
+                if e.status().is_failed()
+                    && e.error()
+                        .map_or(false, |error| error.is_atlas_build_failed())
+                {
+                    is_build_failure.store(true, std::sync::atomic::Ordering::Release);
+                }
+            }
+        }
+    });
+
+    // spin Bevy a few times...
+    (0..100).for_each(|_| app.update());
+
+    // event signalling the builder failed
+    assert!(is_build_failure.load(std::sync::atomic::Ordering::Acquire));
+
+    // resource with the loaded TextureAtlas is NOT  available
+    assert!(!app
+        .world
+        .contains_resource::<AtlasTextures<MyAtlasTextures>>());
+}
+
+#[test]
+fn empty_folder_causes_failure() {
+    let mut app = minimal_app();
+    app.add_plugin(AtlasTexturePlugin::<MyAtlasTextures>::default());
+
+    // the folder holds no images
+    app.add_startup_system(move |mut cmds: Commands| {
+        cmds.insert_resource(TypedAtlasDefinition::<MyAtlasTextures>::from(
+            [(
+                String::from("Pacman"),
+                AtlasDefinition::from(FolderAtlasDefinition::folder(Path::new("empty"))),
+            )]
+            .into_iter()
+            .collect::<HashMap<String, AtlasDefinition>>(),
+        ));
+    });
+
+    // add system for capturing event
+    let is_empty_folder = Arc::new(AtomicBool::new(false));
+    app.add_system({
+        let is_empty_folder = is_empty_folder.clone();
+        move |mut events: EventReader<AtlasTexturesEvent<MyAtlasTextures>>| {
+            for e in events.iter() {
+                if e.status().is_failed()
+                    && e.error().map_or(false, |error| error.is_empty_folder())
+                {
+                    is_empty_folder.store(true, std::sync::atomic::Ordering::Release);
+                }
+            }
+        }
+    });
+
+    // spin Bevy a few times...
+    (0..100).for_each(|_| app.update());
+
+    // event signalling the folder was empty
+    assert!(is_empty_folder.load(std::sync::atomic::Ordering::Acquire));
+    assert!(!app
+        .world
+        .contains_resource::<AtlasTextures<MyAtlasTextures>>());
+}
+
+#[test]
+fn missing_folder_causes_failure() {
+    let mut app = minimal_app();
+    app.add_plugin(AtlasTexturePlugin::<MyAtlasTextures>::default());
+
+    // the folder doesn't exist
+    app.add_startup_system(move |mut cmds: Commands| {
+        cmds.insert_resource(TypedAtlasDefinition::<MyAtlasTextures>::from(
+            [(
+                String::from("Pacman"),
+                AtlasDefinition::from(FolderAtlasDefinition::folder(Path::new("missing"))),
+            )]
+            .into_iter()
+            .collect::<HashMap<String, AtlasDefinition>>(),
+        ));
+    });
+
+    // add system for capturing event
+    let is_load_failed = Arc::new(AtomicBool::new(false));
+    app.add_system({
+        let is_load_failed = is_load_failed.clone();
+        move |mut events: EventReader<AtlasTexturesEvent<MyAtlasTextures>>| {
+            for e in events.iter() {
+                if e.status().is_failed() && e.error().map_or(false, |error| error.is_load_failed())
+                {
+                    is_load_failed.store(true, std::sync::atomic::Ordering::Release);
+                }
+            }
+        }
+    });
+
+    // spin Bevy a few times...
+    (0..100).for_each(|_| app.update());
+
+    // event signalling the folder failed to load
+    assert!(is_load_failed.load(std::sync::atomic::Ordering::Acquire));
+    assert!(!app
+        .world
+        .contains_resource::<AtlasTextures<MyAtlasTextures>>());
+}
+
+#[test]
+fn missing_image_of_manifest_causes_failure() {
+    let mut app = minimal_app();
+    app.add_plugin(AtlasTexturePlugin::<MyAtlasTextures>::default());
+
+    // the manifest lists an image that doesn't exist, next to one that does
+    app.add_startup_system(move |mut cmds: Commands| {
+        cmds.insert_resource(TypedAtlasDefinition::<MyAtlasTextures>::from(
+            [(
+                String::from("Pacman"),
+                AtlasDefinition::from(FolderManifestAtlasDefinition::manifest(Path::new(
+                    "broken.manifest.ron",
+                ))),
+            )]
+            .into_iter()
+            .collect::<HashMap<String, AtlasDefinition>>(),
+        ));
+    });
+
+    // add system for capturing event
+    let error = Arc::new(Mutex::new(None));
+    app.add_system({
+        let error = error.clone();
+        move |mut events: EventReader<AtlasTexturesEvent<MyAtlasTextures>>| {
+            for e in events.iter() {
+                if e.status().is_failed() {
+                    *error.lock().unwrap() = e.error().cloned();
+                }
+            }
+        }
+    });
+
+    // spin Bevy a few times...
+    (0..100).for_each(|_| app.update());
+
+    // failing with the missing image, rather than waiting for it forever
+    assert_eq!(
+        *error.lock().unwrap(),
+        Some(ResourceError::LoadFailed(PathBuf::from(
+            "shapes/missing.png"
+        )))
+    );
+    assert!(!app
+        .world
+        .contains_resource::<AtlasTextures<MyAtlasTextures>>());
+}
+
+#[test]
+fn grid_exceeding_image_causes_failure() {
+    let mut app = minimal_app();
+    app.add_plugin(AtlasTexturePlugin::<MyAtlasTextures>::default());
+
+    // the image is only 380 pixels wide
+    app.add_startup_system(move |mut cmds: Commands| {
+        cmds.insert_resource(TypedAtlasDefinition::<MyAtlasTextures>::from(
+            [(
+                String::from("Pacman"),
+                AtlasDefinition::from(GridAtlasDefinition::grid(
+                    Path::new("Pac-Man.png"),
+                    30,
+                    3,
+                    UVec2::new(19, 19),
+                )),
+            )]
+            .into_iter()
+            .collect::<HashMap<String, AtlasDefinition>>(),
+        ));
+    });
+
+    // add system for capturing event
+    let is_exceeding = Arc::new(AtomicBool::new(false));
+    app.add_system({
+        let is_exceeding = is_exceeding.clone();
+        move |mut events: EventReader<AtlasTexturesEvent<MyAtlasTextures>>| {
+            for e in events.iter() {
+                if e.status().is_failed()
+                    && e.error()
+                        .map_or(false, |error| error.is_grid_exceeds_image())
+                {
+                    is_exceeding.store(true, std::sync::atomic::Ordering::Release);
+                }
+            }
+        }
+    });
+
+    // spin Bevy a few times...
+    (0..100).for_each(|_| app.update());
+
+    // event signalling the grid doesn't fit the image
+    assert!(is_exceeding.load(std::sync::atomic::Ordering::Acquire));
+
+    // ...along with the definition that failed
+    let failures = app
+        .world
+        .get_resource::<TypedAtlasDefinition<MyAtlasTextures>>()
+        .unwrap()
+        .failures();
+    assert_eq!(failures.len(), 1);
+    assert_eq!(failures[0].0, "Pacman");
+    assert!(failures[0].1.is_grid_exceeds_image());
+
+    // resource with the loaded TextureAtlas is NOT  available
+    assert!(!app
+        .world
+        .contains_resource::<AtlasTextures<MyAtlasTextures>>());
+}
+
+#[test]
+fn lenient_mode_leaves_out_failing_definitions() {
+    #[derive(
+        Debug, PartialEq, Eq, Hash, Clone, Copy, strum::EnumVariantNames, strum::EnumString,
+    )]
+    enum TwoAtlasTextures {
+        Good,
+        Bad,
+    }
+
+    let mut app = minimal_app();
+    app.add_plugin(AtlasTexturePlugin::<TwoAtlasTextures>::default().with_lenient(true));
+
+    // the image is only 380 pixels wide
+    app.add_startup_system(move |mut cmds: Commands| {
+        cmds.insert_resource(TypedAtlasDefinition::<TwoAtlasTextures>::from(
+            [
+                (
+                    String::from("Good"),
+                    AtlasDefinition::from(GridAtlasDefinition::grid(
+                        Path::new("Pac-Man.png"),
+                        3,
+                        3,
+                        UVec2::new(19, 19),
+                    )),
+                ),
+                (
+                    String::from("Bad"),
+                    AtlasDefinition::from(GridAtlasDefinition::grid(
+                        Path::new("Pac-Man.png"),
+                        30,
+                        3,
+                        UVec2::new(19, 19),
+                    )),
+                ),
+            ]
+            .into_iter()
+            .collect::<HashMap<String, AtlasDefinition>>(),
+        ));
+    });
+
+    // add system for capturing event
+    let is_partial = Arc::new(AtomicBool::new(false));
+    app.add_system({
+        let is_partial = is_partial.clone();
+        move |mut events: EventReader<AtlasTexturesEvent<TwoAtlasTextures>>| {
+            for e in events.iter() {
+                if e.status().is_partially_created()
+                    && e.failures().len() == 1
+                    && e.failures()[0].0 == "Bad"
+                    && e.failures()[0].1.is_grid_exceeds_image()
+                {
+                    is_partial.store(true, std::sync::atomic::Ordering::Release);
+                }
+            }
+        }
+    });
+
+    // spin Bevy a few times...
+    (0..100).for_each(|_| app.update());
+
+    // event signalling the failing definition was left out
+    assert!(is_partial.load(std::sync::atomic::Ordering::Acquire));
+
+    // resource only has the successful definition
+    let resource = app
+        .world
+        .get_resource::<AtlasTextures<TwoAtlasTextures>>()
+        .unwrap();
+    assert!(resource.contains(TwoAtlasTextures::Good));
+    assert!(!resource.contains(TwoAtlasTextures::Bad));
+
+    // ...and its accessors return `None` for the definition left out
+    assert_eq!(resource.tile_rect(TwoAtlasTextures::Bad, 0), None);
+    assert_eq!(resource.animation(TwoAtlasTextures::Bad, "chomp"), None);
+    assert_eq!(resource.index_of(TwoAtlasTextures::Bad, "first"), None);
+    assert_eq!(resource.nine_slice(TwoAtlasTextures::Bad, 0), None);
+    assert_eq!(resource.source_image(TwoAtlasTextures::Bad), None);
+    assert_eq!(resource.source_images(TwoAtlasTextures::Bad), None);
+    assert_eq!(resource.default_index(TwoAtlasTextures::Bad), None);
+    assert_eq!(resource.pages(TwoAtlasTextures::Bad), None);
+    assert_eq!(resource.index_offset(TwoAtlasTextures::Bad), None);
+    assert!(resource
+        .sprite_bundle(TwoAtlasTextures::Bad, 0, None)
+        .is_none());
+
+    // ...as summarized by the definitions
+    let definition = app
+        .world
+        .get_resource::<TypedAtlasDefinition<TwoAtlasTextures>>()
+        .unwrap();
+    let atlas_definitions = app
+        .world
+        .get_resource::<Assets<GenericAtlasDefinitions>>()
+        .unwrap();
+    assert_eq!(
+        definition.status_summary(atlas_definitions),
+        "1/2 atlases created, 1 failed"
+    );
+}
+
+#[test]
+fn slow_loading_causes_failure() {
+    let mut app = minimal_app();
+    app.add_plugin(
+        AtlasTexturePlugin::<MyAtlasTextures>::default().with_load_timeout(Duration::ZERO),
+    );
+
+    // the image never loads
+    app.add_startup_system(move |mut cmds: Commands| {
+        cmds.insert_resource(TypedAtlasDefinition::<MyAtlasTextures>::from(
+            [(
+                String::from("Pacman"),
+                AtlasDefinition::from(GridAtlasDefinition::grid(
+                    Path::new("missing.png"),
+                    3,
+                    3,
+                    UVec2::new(19, 19),
+                )),
+            )]
+            .into_iter()
+            .collect::<HashMap<String, AtlasDefinition>>(),
+        ));
+    });
+
+    // add system for capturing event
+    let is_timed_out = Arc::new(AtomicBool::new(false));
+    app.add_system({
+        let is_timed_out = is_timed_out.clone();
+        move |mut events: EventReader<AtlasTexturesEvent<MyAtlasTextures>>| {
+            for e in events.iter() {
+                if e.status().is_failed()
+                    && e.error().map_or(false, |error| error.is_load_timeout())
+                {
+                    is_timed_out.store(true, std::sync::atomic::Ordering::Release);
+                }
+            }
+        }
+    });
+
+    // spin Bevy a few times...
+    (0..100).for_each(|_| app.update());
+
+    // event signalling the image didn't load in time
+    assert!(is_timed_out.load(std::sync::atomic::Ordering::Acquire));
+
+    // resource with the loaded TextureAtlas is NOT  available
+    assert!(!app
+        .world
+        .contains_resource::<AtlasTextures<MyAtlasTextures>>());
+
+    // ...and the definition is reported as failed
+    let definition = app
+        .world
+        .get_resource::<TypedAtlasDefinition<MyAtlasTextures>>()
+        .unwrap();
+    let atlas_definitions = app
+        .world
+        .get_resource::<Assets<GenericAtlasDefinitions>>()
+        .unwrap();
+    assert_eq!(
+        definition
+            .definitions_status(atlas_definitions)
+            .collect::<Vec<_>>(),
+        vec![("Pacman", DefinitionStatus::Failed)]
+    );
+}
+
+#[test]
+fn loading_definitions_are_reported() {
+    let mut app = minimal_app();
+    app.add_plugin(AtlasTexturePlugin::<MyAtlasTextures>::default());
+
+    // the image never loads
+    app.add_startup_system(move |mut cmds: Commands| {
+        cmds.insert_resource(TypedAtlasDefinition::<MyAtlasTextures>::from(
+            [(
+                String::from("Pacman"),
+                AtlasDefinition::from(GridAtlasDefinition::grid(
+                    Path::new("missing.png"),
+                    3,
+                    3,
+                    UVec2::new(19, 19),
+                )),
+            )]
+            .into_iter()
+            .collect::<HashMap<String, AtlasDefinition>>(),
+        ));
+    });
+
+    // spin Bevy a few times...
+    (0..100).for_each(|_| app.update());
+
+    let definition = app
+        .world
+        .get_resource::<TypedAtlasDefinition<MyAtlasTextures>>()
+        .unwrap();
+    let atlas_definitions = app
+        .world
+        .get_resource::<Assets<GenericAtlasDefinitions>>()
+        .unwrap();
+    assert_eq!(
+        definition.currently_loading(atlas_definitions),
+        vec![String::from("Pacman")]
+    );
+    assert_eq!(
+        definition
+            .definitions_status(atlas_definitions)
+            .collect::<Vec<_>>(),
+        vec![("Pacman", DefinitionStatus::Loading)]
+    );
+}
+
+#[ignore = "Bevy Asset Server does not see invalid paths as failures, thus we can not either!"]
+#[test]
+fn unloadable_paths_causes_failure() {
+    let mut app = minimal_app();
+    app.add_plugin(AtlasTexturePlugin::<MyAtlasTextures>::default());
+
+    app.add_startup_system(move |mut cmds: Commands| {
+        cmds.insert_resource(TypedAtlasDefinition::<MyAtlasTextures>::from(
+            [(
+                String::from("Pacman"),
+                AtlasDefinition::from(GridAtlasDefinition::grid(
+                    Path::new("invalid-path.png"),
+                    3,
+                    3,
+                    UVec2::new(19, 19),
+                )),
+            )]
+            .into_iter()
+            .collect::<HashMap<String, AtlasDefinition>>(),
+        ));
+    });
+
+    // add system for capturing event
+    let is_failed = Arc::new(AtomicBool::new(false));
+    app.add_system({
+        let is_failed = is_failed.clone();
+        move |mut events: EventReader<AtlasTexturesEvent<MyAtlasTextures>>| {
+            for e in events.iter() {
+                if e.status().is_failed() {
+                    is_failed.store(true, std::sync::atomic::Ordering::Release);
+                }
+            }
+        }
+    });
+
+    // spin Bevy a few times...
+    (0..100).for_each(|_| app.update());
+
+    // unfortunately, bevy asset server does not count non-existant paths as failures :-(
+    assert!(is_failed.load(std::sync::atomic::Ordering::Acquire));
+
+    // resource with the loaded TextureAtlas is NOT  available
+    assert!(!app
+        .world
+        .contains_resource::<AtlasTextures<MyAtlasTextures>>());
+}
+
+#[test]
+fn higher_priorities_are_loaded_first() {
+    let mut app = minimal_app();
+    app.add_plugin(AtlasTexturePlugin::<DynamicAtlasKey>::default());
+
+    // neither image loads, so only the first definition processed starts loading
+    app.add_startup_system(move |mut cmds: Commands| {
+        cmds.insert_resource(TypedAtlasDefinition::<DynamicAtlasKey>::from(
+            [
+                (
+                    String::from("Decoration"),
+                    AtlasDefinition::from(GridAtlasDefinition::grid(
+                        Path::new("missing.png"),
+                        3,
+                        3,
+                        UVec2::new(19, 19),
+                    )),
+                ),
+                (
+                    String::from("Player"),
+                    AtlasDefinition::from(
+                        GridAtlasDefinition::grid(
+                            Path::new("missing_player.png"),
+                            3,
+                            3,
+                            UVec2::new(19, 19),
+                        )
+                        .with_priority(10),
+                    ),
+                ),
+            ]
+            .into_iter()
+            .collect::<HashMap<String, AtlasDefinition>>(),
+        ));
+    });
+
+    (0..100).for_each(|_| app.update());
+
+    let definition = app
+        .world
+        .get_resource::<TypedAtlasDefinition<DynamicAtlasKey>>()
+        .unwrap();
+    let atlas_definitions = app
+        .world
+        .get_resource::<Assets<GenericAtlasDefinitions>>()
+        .unwrap();
+    let mut status = definition
+        .definitions_status(atlas_definitions)
+        .collect::<Vec<_>>();
+    status.sort_by_key(|(key, _)| *key);
+    assert_eq!(
+        status,
+        vec![
+            ("Decoration", DefinitionStatus::None),
+            ("Player", DefinitionStatus::Loading)
+        ]
+    );
+}
+
+#[test]
+fn definitions_can_be_replaced_from_ron() {
+    let mut app = minimal_app();
+    app.add_plugin(AtlasTexturePlugin::<MyAtlasTextures>::default());
+
+    app.add_startup_system(move |mut cmds: Commands| {
+        cmds.insert_resource(TypedAtlasDefinition::<MyAtlasTextures>::from(
+            [(
+                String::from("Pacman"),
+                AtlasDefinition::from(GridAtlasDefinition::grid(
+                    Path::new("Pac-Man.png"),
+                    3,
+                    3,
+                    UVec2::new(19, 19),
+                )),
+            )]
+            .into_iter()
+            .collect::<HashMap<String, AtlasDefinition>>(),
+        ));
+    });
+
+    // add system for counting the reloads
+    let reloading = Arc::new(std::sync::atomic::AtomicUsize::new(0));
+    app.add_system({
+        let reloading = reloading.clone();
+        move |mut events: EventReader<AtlasTexturesEvent<MyAtlasTextures>>| {
+            for e in events.iter() {
+                if e.status().is_reloading() {
+                    reloading.fetch_add(1, std::sync::atomic::Ordering::AcqRel);
+                }
+            }
+        }
+    });
+
+    (0..100).for_each(|_| app.update());
+
+    let len = |app: &App| {
+        app.world
+            .get_resource::<AtlasTextures<MyAtlasTextures>>()
+            .unwrap()
+            .len(MyAtlasTextures::Pacman)
+    };
+    assert_eq!(len(&app), 9);
+
+    // a parse error leaves the atlases intact
+    let mut definition = app
+        .world
+        .get_resource_mut::<TypedAtlasDefinition<MyAtlasTextures>>()
+        .unwrap();
+    assert!(definition.replace_from_ron("({ \"Pacman\": (").is_err());
+    (0..100).for_each(|_| app.update());
+    assert_eq!(len(&app), 9);
+    assert_eq!(reloading.load(std::sync::atomic::Ordering::Acquire), 0);
+
+    let mut definition = app
+        .world
+        .get_resource_mut::<TypedAtlasDefinition<MyAtlasTextures>>()
+        .unwrap();
+    definition
+        .replace_from_ron(
+            r#"({
+                "Pacman": (
+                    texture: "Pac-Man.png",
+                    columns: 2,
+                    rows: 1,
+                    tile_size: (19, 19),
+                ),
+            })"#,
+        )
+        .unwrap();
+    (0..100).for_each(|_| app.update());
+    assert_eq!(len(&app), 2);
+
+    // listeners are told about the reload, as for a reloaded asset
+    assert_eq!(reloading.load(std::sync::atomic::Ordering::Acquire), 1);
+}
+
+#[test]
+fn atlases_can_be_rebuilt_right_away() {
+    let mut app = minimal_app();
+    app.add_plugin(AtlasTexturePlugin::<MyAtlasTextures>::default());
+
+    app.add_startup_system(move |mut cmds: Commands| {
+        cmds.insert_resource(TypedAtlasDefinition::<MyAtlasTextures>::from(
+            [(
+                String::from("Pacman"),
+                AtlasDefinition::from(GridAtlasDefinition::grid(
+                    Path::new("Pac-Man.png"),
+                    3,
+                    3,
+                    UVec2::new(19, 19),
+                )),
+            )]
+            .into_iter()
+            .collect::<HashMap<String, AtlasDefinition>>(),
+        ));
+    });
+
+    (0..100).for_each(|_| app.update());
+
+    app.world
+        .get_resource_mut::<TypedAtlasDefinition<MyAtlasTextures>>()
+        .unwrap()
+        .replace_from_ron(
+            r#"({
+                "Pacman": (
+                    texture: "Pac-Man.png",
+                    columns: 2,
+                    rows: 1,
+                    tile_size: (19, 19),
+                ),
+            })"#,
+        )
+        .unwrap();
+
+    // the image is loaded already, so no frames are needed
+    assert!(rebuild_now::<MyAtlasTextures>(&mut app.world));
+    assert_eq!(
+        app.world
+            .get_resource::<AtlasTextures<MyAtlasTextures>>()
+            .unwrap()
+            .len(MyAtlasTextures::Pacman),
+        2
+    );
+}
+
+#[test]
+fn sprites_show_the_default_index() {
+    let mut app = minimal_app();
+    app.add_plugin(AtlasTexturePlugin::<MyAtlasTextures>::default());
+
+    app.add_startup_system(move |mut cmds: Commands| {
+        cmds.insert_resource(TypedAtlasDefinition::<MyAtlasTextures>::from(
+            [(
+                String::from("Pacman"),
+                AtlasDefinition::from(
+                    GridAtlasDefinition::grid(Path::new("Pac-Man.png"), 3, 3, UVec2::new(19, 19))
+                        .with_default_index(4),
+                ),
+            )]
+            .into_iter()
+            .collect::<HashMap<String, AtlasDefinition>>(),
+        ));
+    });
+
+    (0..100).for_each(|_| app.update());
+
+    let resource = app
+        .world
+        .get_resource::<AtlasTextures<MyAtlasTextures>>()
+        .unwrap();
+    assert_eq!(resource.default_index(MyAtlasTextures::Pacman), Some(4));
+    let bundle = resource
+        .default_sprite_bundle(MyAtlasTextures::Pacman, None)
+        .unwrap();
+    assert_eq!(bundle.sprite.index, 4);
+    assert_eq!(
+        bundle.texture_atlas,
+        resource.handle(MyAtlasTextures::Pacman)
+    );
+}
+
+#[test]
+fn mipmaps_can_be_generated() {
+    let mut app = minimal_app();
+    app.add_plugin(AtlasTexturePlugin::<MyAtlasTextures>::default());
+
+    app.add_startup_system(move |mut cmds: Commands| {
+        cmds.insert_resource(TypedAtlasDefinition::<MyAtlasTextures>::from(
+            [(
+                String::from("Pacman"),
+                AtlasDefinition::from(
+                    GridAtlasDefinition::grid(Path::new("Pac-Man.png"), 3, 3, UVec2::new(19, 19))
+                        .with_generate_mipmaps(true),
+                ),
+            )]
+            .into_iter()
+            .collect::<HashMap<String, AtlasDefinition>>(),
+        ));
+    });
+
+    (0..100).for_each(|_| app.update());
+
+    let resource = app
+        .world
+        .get_resource::<AtlasTextures<MyAtlasTextures>>()
+        .unwrap();
+    let texture_atlases = app.world.get_resource::<Assets<TextureAtlas>>().unwrap();
+    let images = app.world.get_resource::<Assets<Image>>().unwrap();
+    let texture = &texture_atlases
+        .get(&resource.handle(MyAtlasTextures::Pacman))
+        .unwrap()
+        .texture;
+
+    // 380x231 halved down to 1x1
+    assert_eq!(
+        images
+            .get(texture)
+            .unwrap()
+            .texture_descriptor
+            .mip_level_count,
+        9
+    );
+}
+
+#[test]
+fn images_can_be_placed_into_the_cells_of_a_mosaic() {
+    let mut app = minimal_app();
+    app.add_plugin(AtlasTexturePlugin::<MyAtlasTextures>::default());
+
+    app.add_startup_system(move |mut cmds: Commands| {
+        cmds.insert_resource(TypedAtlasDefinition::<MyAtlasTextures>::from(
+            [(
+                String::from("Pacman"),
+                AtlasDefinition::from(
+                    MosaicAtlasDefinition::mosaic([
+                        (UVec2::new(0, 0), Path::new("shapes/square.png")),
+                        (UVec2::new(1, 0), Path::new("shapes/wide.png")),
+                    ])
+                    .with_cell(UVec2::new(1, 1), Path::new("shapes/tall.png")),
+                ),
+            )]
+            .into_iter()
+            .collect::<HashMap<String, AtlasDefinition>>(),
+        ));
+    });
+
+    // spin Bevy a few times...
+    (0..100).for_each(|_| app.update());
+
+    let resource = app
+        .world
+        .get_resource::<AtlasTextures<MyAtlasTextures>>()
+        .unwrap();
+    let atlas = app
+        .world
+        .get_resource::<Assets<TextureAtlas>>()
+        .unwrap()
+        .get(resource.handle(MyAtlasTextures::Pacman))
+        .unwrap();
+
+    // cells are as large as the widest and tallest images
+    assert_eq!(atlas.size, Vec2::new(48., 48.));
+    assert_eq!(atlas.len(), 3);
+    assert_eq!(atlas.textures[1].min, Vec2::new(24., 0.));
+    assert_eq!(atlas.textures[1].max, Vec2::new(48., 8.));
+    assert_eq!(atlas.textures[2].min, Vec2::new(24., 24.));
+    assert_eq!(atlas.textures[2].max, Vec2::new(32., 48.));
+}
+
+#[test]
+fn single_region_atlases_can_repeat() {
+    let mut app = minimal_app();
+    app.add_plugin(AtlasTexturePlugin::<MyAtlasTextures>::default());
+
+    app.add_startup_system(move |mut cmds: Commands| {
+        cmds.insert_resource(TypedAtlasDefinition::<MyAtlasTextures>::from(
+            [(
+                String::from("Pacman"),
+                AtlasDefinition::from(
+                    GridAtlasDefinition::grid(
+                        Path::new("shapes/square.png"),
+                        1,
+                        1,
+                        UVec2::new(16, 16),
+                    )
+                    .with_address_mode(AddressMode::Repeat),
+                ),
+            )]
+            .into_iter()
+            .collect::<HashMap<String, AtlasDefinition>>(),
+        ));
+    });
+
+    // spin Bevy a few times...
+    (0..100).for_each(|_| app.update());
+
+    let resource = app
+        .world
+        .get_resource::<AtlasTextures<MyAtlasTextures>>()
+        .unwrap();
+    let texture_atlases = app.world.get_resource::<Assets<TextureAtlas>>().unwrap();
+    let images = app.world.get_resource::<Assets<Image>>().unwrap();
+    let texture = &texture_atlases
+        .get(resource.handle(MyAtlasTextures::Pacman))
+        .unwrap()
+        .texture;
+    let sampler = &images.get(texture).unwrap().sampler_descriptor;
+    assert_eq!(sampler.address_mode_u, render_resource::AddressMode::Repeat);
+    assert_eq!(sampler.address_mode_v, render_resource::AddressMode::Repeat);
+}
+
+#[test]
+fn systems_can_run_on_atlas_events() {
+    let mut app = minimal_app();
+    app.add_plugin(AtlasTexturePlugin::<MyAtlasTextures>::default());
+
+    app.add_startup_system(move |mut cmds: Commands| {
+        cmds.insert_resource(TypedAtlasDefinition::<MyAtlasTextures>::from(
+            [(
+                String::from("Pacman"),
+                AtlasDefinition::from(GridAtlasDefinition::grid(
+                    Path::new("Pac-Man.png"),
+                    3,
+                    3,
+                    UVec2::new(19, 19),
+                )),
+            )]
+            .into_iter()
+            .collect::<HashMap<String, AtlasDefinition>>(),
+        ));
+    });
+
+    // add systems for counting the frames they run on
+    let created = Arc::new(std::sync::atomic::AtomicUsize::new(0));
+    let failed = Arc::new(std::sync::atomic::AtomicUsize::new(0));
+    app.add_system(
+        {
+            let created = created.clone();
+            move || {
+                created.fetch_add(1, std::sync::atomic::Ordering::AcqRel);
+            }
+        }
+        .run_if(on_atlas_created::<MyAtlasTextures>),
+    );
+    app.add_system(
+        {
+            let failed = failed.clone();
+            move || {
+                failed.fetch_add(1, std::sync::atomic::Ordering::AcqRel);
+            }
+        }
+        .run_if(on_atlas_failed::<MyAtlasTextures>),
+    );
+
+    // spin Bevy a few times...
+    (0..100).for_each(|_| app.update());
+
+    assert_eq!(created.load(std::sync::atomic::Ordering::Acquire), 1);
+    assert_eq!(failed.load(std::sync::atomic::Ordering::Acquire), 0);
+}
+
+#[test]
+fn variants_requested_after_failure_are_created() {
+    #[derive(
+        Debug, PartialEq, Eq, Hash, Clone, Copy, strum::EnumVariantNames, strum::EnumString,
+    )]
+    enum TwoAtlasTextures {
+        Pacman,
+        Ghost,
+    }
+
+    let mut app = minimal_app();
+    app.add_plugin(AtlasTexturePlugin::<TwoAtlasTextures>::default());
+
+    // the requested Ghost has no definition
+    app.add_startup_system(move |mut cmds: Commands| {
+        cmds.insert_resource(
+            TypedAtlasDefinition::<TwoAtlasTextures>::from(
+                [(
+                    String::from("Pacman"),
+                    AtlasDefinition::from(GridAtlasDefinition::grid(
+                        Path::new("Pac-Man.png"),
+                        3,
+                        3,
+                        UVec2::new(19, 19),
+                    )),
+                )]
+                .into_iter()
+                .collect::<HashMap<String, AtlasDefinition>>(),
+            )
+            .with_variants(&[TwoAtlasTextures::Ghost]),
+        );
+    });
+
+    // add system for counting the frames failing
+    let failed = Arc::new(std::sync::atomic::AtomicUsize::new(0));
+    app.add_system(
+        {
+            let failed = failed.clone();
+            move || {
+                failed.fetch_add(1, std::sync::atomic::Ordering::AcqRel);
+            }
+        }
+        .run_if(on_atlas_failed::<TwoAtlasTextures>),
+    );
+
+    // spin Bevy a few times...
+    (0..100).for_each(|_| app.update());
+    assert_eq!(failed.load(std::sync::atomic::Ordering::Acquire), 1);
+
+    // requesting another variant tries again, without keeping the earlier failures
+    app.world
+        .get_resource_mut::<TypedAtlasDefinition<TwoAtlasTextures>>()
+        .unwrap()
+        .request_variants(&[TwoAtlasTextures::Pacman]);
+    (0..100).for_each(|_| app.update());
+    assert_eq!(failed.load(std::sync::atomic::Ordering::Acquire), 2);
+    let failures = app
+        .world
+        .get_resource::<TypedAtlasDefinition<TwoAtlasTextures>>()
+        .unwrap()
+        .failures();
+    assert_eq!(failures.len(), 1);
+    assert_eq!(failures[0].0, "Ghost");
+}
+
+#[test]
+fn profile_selected_after_failure_is_created() {
+    let mut app = minimal_app();
+    app.add_plugin(AtlasTexturePlugin::<MyAtlasTextures>::default());
+
+    // the image is only 380 pixels wide, so only the profile fits it
+    app.add_startup_system(move |mut cmds: Commands| {
+        cmds.insert_resource(TypedAtlasDefinition::<MyAtlasTextures>::from(
+            [
+                (
+                    String::from("Pacman"),
+                    AtlasDefinition::from(GridAtlasDefinition::grid(
+                        Path::new("Pac-Man.png"),
+                        30,
+                        3,
+                        UVec2::new(19, 19),
+                    )),
+                ),
+                (
+                    String::from("Pacman@fixed"),
+                    AtlasDefinition::from(GridAtlasDefinition::grid(
+                        Path::new("Pac-Man.png"),
+                        3,
+                        3,
+                        UVec2::new(19, 19),
+                    )),
+                ),
+            ]
+            .into_iter()
+            .collect::<HashMap<String, AtlasDefinition>>(),
+        ));
+    });
+
+    // spin Bevy a few times...
+    (0..100).for_each(|_| app.update());
+    assert!(!app
+        .world
+        .contains_resource::<AtlasTextures<MyAtlasTextures>>());
+
+    app.world
+        .get_resource_mut::<TypedAtlasDefinition<MyAtlasTextures>>()
+        .unwrap()
+        .set_profile(Some("fixed"));
+    (0..100).for_each(|_| app.update());
+
+    let resource = app
+        .world
+        .get_resource::<AtlasTextures<MyAtlasTextures>>()
+        .unwrap();
+    assert_eq!(resource.len(MyAtlasTextures::Pacman), 9);
+    assert!(app
+        .world
+        .get_resource::<TypedAtlasDefinition<MyAtlasTextures>>()
+        .unwrap()
+        .failures()
+        .is_empty());
+}
+
+#[test]
+fn textures_of_all_definitions_can_be_prefetched() {
+    #[derive(
+        Debug, PartialEq, Eq, Hash, Clone, Copy, strum::EnumVariantNames, strum::EnumString,
+    )]
+    enum TwoAtlasTextures {
+        Pacman,
+        Square,
+    }
+
+    let mut app = minimal_app();
+    app.add_plugin(AtlasTexturePlugin::<TwoAtlasTextures>::default().with_prefetch(true));
+
+    app.add_startup_system(move |mut cmds: Commands| {
+        cmds.insert_resource(TypedAtlasDefinition::<TwoAtlasTextures>::from(
+            [
+                (
+                    String::from("Pacman"),
+                    AtlasDefinition::from(GridAtlasDefinition::grid(
+                        Path::new("Pac-Man.png"),
+                        3,
+                        3,
+                        UVec2::new(19, 19),
+                    )),
+                ),
+                (
+                    String::from("Square"),
+                    AtlasDefinition::from(GridAtlasDefinition::grid(
+                        Path::new("shapes/square.png"),
+                        1,
+                        1,
+                        UVec2::new(16, 16),
+                    )),
+                ),
+            ]
+            .into_iter()
+            .collect::<HashMap<String, AtlasDefinition>>(),
+        ));
+    });
+
+    // spin Bevy until the first texture is requested...
+    let requested = |app: &App, path: &str| {
+        let asset_server = app.world.resource::<AssetServer>();
+        asset_server.get_load_state(path) != bevy::asset::LoadState::NotLoaded
+    };
+    for _ in 0..100 {
+        app.update();
+        if requested(&app, "Pac-Man.png") || requested(&app, "shapes/square.png") {
+            break;
+        }
+    }
+
+    // ...at which point the other is requested as well
+    assert!(requested(&app, "Pac-Man.png"));
+    assert!(requested(&app, "shapes/square.png"));
+}
+
+#[test]
+fn optional_variants_can_be_missing() {
+    #[derive(
+        Debug, PartialEq, Eq, Hash, Clone, Copy, strum::EnumVariantNames, strum::EnumString,
+    )]
+    enum PackAtlasTextures {
+        Pacman,
+        Bonus,
+    }
+
+    let mut app = minimal_app();
+    app.add_plugin(
+        AtlasTexturePlugin::<PackAtlasTextures>::default()
+            .with_optional_variants([PackAtlasTextures::Bonus]),
+    );
+
+    app.add_startup_system(move |mut cmds: Commands| {
+        cmds.insert_resource(TypedAtlasDefinition::<PackAtlasTextures>::from(
+            [(
+                String::from("Pacman"),
+                AtlasDefinition::from(GridAtlasDefinition::grid(
+                    Path::new("Pac-Man.png"),
+                    3,
+                    3,
+                    UVec2::new(19, 19),
+                )),
+            )]
+            .into_iter()
+            .collect::<HashMap<String, AtlasDefinition>>(),
+        ));
+    });
+
+    // spin Bevy a few times...
+    (0..100).for_each(|_| app.update());
+
+    // the optional variant is simply left out
+    let atlas_textures = app.world.resource::<AtlasTextures<PackAtlasTextures>>();
+    assert!(atlas_textures.contains(PackAtlasTextures::Pacman));
+    assert!(!atlas_textures.contains(PackAtlasTextures::Bonus));
 }
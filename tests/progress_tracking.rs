@@ -0,0 +1,58 @@
+use bevy::{prelude::*, utils::HashMap};
+use bevy_atlas_loader::{
+    atlas_textures_progress, test_support::minimal_app, AtlasDefinition, AtlasTexturePlugin,
+    GridAtlasDefinition, TypedAtlasDefinition,
+};
+use iyes_progress::Progress;
+use std::{
+    path::Path,
+    sync::{Arc, Mutex},
+};
+
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy, strum::EnumVariantNames, strum::EnumString)]
+enum MyAtlasTextures {
+    Pacman,
+}
+
+#[test]
+fn progress_completes_once_the_atlases_are_created() {
+    let mut app = minimal_app();
+    app.add_plugin(AtlasTexturePlugin::<MyAtlasTextures>::default());
+
+    app.add_startup_system(move |mut cmds: Commands| {
+        cmds.insert_resource(TypedAtlasDefinition::<MyAtlasTextures>::from(
+            [(
+                String::from("Pacman"),
+                AtlasDefinition::from(GridAtlasDefinition::grid(
+                    Path::new("Pac-Man.png"),
+                    3,
+                    3,
+                    UVec2::new(19, 19),
+                )),
+            )]
+            .into_iter()
+            .collect::<HashMap<String, AtlasDefinition>>(),
+        ));
+    });
+
+    // add system for capturing the progress
+    let progress = Arc::new(Mutex::new(Vec::new()));
+    app.add_system(atlas_textures_progress::<MyAtlasTextures>.chain({
+        let progress = progress.clone();
+        move |In(reported): In<Progress>| {
+            progress
+                .lock()
+                .unwrap()
+                .push((reported.done, reported.total));
+        }
+    }));
+
+    // spin Bevy a few times...
+    (0..100).for_each(|_| app.update());
+
+    let progress = progress.lock().unwrap();
+    assert!(progress.iter().any(|(done, total)| done < total));
+    let (done, total) = progress.last().copied().unwrap();
+    assert!(total > 0);
+    assert_eq!(done, total);
+}
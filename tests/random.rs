@@ -1,7 +1,7 @@
-use crate::common::minimal_bevy_app;
-use bevy_atlas_loader::AtlasTexturePlugin;
-
-mod common;
+use bevy_atlas_loader::{
+    define_atlas_textures, test_support::minimal_app, AtlasKey, AtlasTexturePlugin,
+};
+use std::borrow::Cow;
 
 #[derive(Debug, PartialEq, Eq, Hash, Clone, Copy, strum::EnumVariantNames, strum::EnumString)]
 enum MyAtlasTextures {
@@ -10,8 +10,54 @@ enum MyAtlasTextures {
 
 #[test]
 fn plugin_alone_wont_cause_panic() {
-    let mut app = minimal_bevy_app();
+    let mut app = minimal_app();
     app.add_plugin(AtlasTexturePlugin::<MyAtlasTextures>::default());
     // spin Bevy a few times...
     (0..100).for_each(|_| app.update());
 }
+
+#[derive(Debug, PartialEq, Eq, Hash, Clone)]
+struct DuplicateKey(String);
+
+impl AtlasKey for DuplicateKey {
+    fn variants() -> &'static [&'static str] {
+        &["Pacman", "Ghost", "Pacman"]
+    }
+
+    fn from_key(key: &str) -> Option<Self> {
+        Some(Self(key.to_string()))
+    }
+
+    fn to_key(&self) -> Cow<'_, str> {
+        Cow::Borrowed(&self.0)
+    }
+}
+
+#[test]
+#[should_panic(expected = "several variants named \"Pacman\"")]
+fn duplicate_variants_cause_panic() {
+    let mut app = minimal_app();
+    app.add_plugin(AtlasTexturePlugin::<DuplicateKey>::default());
+}
+
+define_atlas_textures! {
+    enum MacroAtlasTextures {
+        Pacman,
+        Ghost,
+    }
+}
+
+#[test]
+fn macro_defined_keys_map_to_variant_names() {
+    assert_eq!(MacroAtlasTextures::variants(), &["Pacman", "Ghost"]);
+    assert_eq!(
+        MacroAtlasTextures::from_key("Pacman"),
+        Some(MacroAtlasTextures::Pacman)
+    );
+    assert_eq!(MacroAtlasTextures::from_key("Blinky"), None);
+    assert_eq!(MacroAtlasTextures::Ghost.to_key(), "Ghost");
+
+    let mut app = minimal_app();
+    app.add_plugin(AtlasTexturePlugin::<MacroAtlasTextures>::default());
+    (0..100).for_each(|_| app.update());
+}
@@ -0,0 +1,55 @@
+use bevy::{prelude::*, utils::HashMap};
+use bevy_atlas_loader::{
+    test_support::minimal_app, AtlasDefinition, AtlasTexturePlugin, AtlasTextures,
+    TexturePackerAtlasDefinition, TypedAtlasDefinition,
+};
+use std::path::Path;
+
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy, strum::EnumVariantNames, strum::EnumString)]
+enum MyAtlasTextures {
+    Pacman,
+}
+
+#[test]
+fn definition_can_be_a_texture_packer_sheet() {
+    let mut app = minimal_app();
+    app.add_plugin(AtlasTexturePlugin::<MyAtlasTextures>::default());
+
+    app.add_startup_system(move |mut cmds: Commands| {
+        cmds.insert_resource(TypedAtlasDefinition::<MyAtlasTextures>::from(
+            [(
+                String::from("Pacman"),
+                AtlasDefinition::from(TexturePackerAtlasDefinition::texture_packer(Path::new(
+                    "pacman.tpsheet.json",
+                ))),
+            )]
+            .into_iter()
+            .collect::<HashMap<String, AtlasDefinition>>(),
+        ));
+    });
+
+    // spin Bevy a few times...
+    (0..100).for_each(|_| app.update());
+
+    // the frames are available by their names
+    let resource = app
+        .world
+        .get_resource::<AtlasTextures<MyAtlasTextures>>()
+        .unwrap();
+    assert_eq!(resource.len(MyAtlasTextures::Pacman), 3);
+    assert_eq!(
+        resource.index_of(MyAtlasTextures::Pacman, "pacman-closed.png"),
+        Some(2)
+    );
+
+    // ...along with their regions
+    assert_eq!(
+        resource
+            .lookup(MyAtlasTextures::Pacman, "pacman-closed.png")
+            .map(|(index, rect)| (index, rect.min, rect.max)),
+        Some((2, Vec2::new(107., 86.), Vec2::new(126., 105.)))
+    );
+    assert!(resource
+        .lookup(MyAtlasTextures::Pacman, "pacman-dead.png")
+        .is_none());
+}
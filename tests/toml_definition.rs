@@ -0,0 +1,48 @@
+use bevy::prelude::*;
+use bevy_atlas_loader::{
+    test_support::minimal_app, AtlasTexturePlugin, AtlasTextures, AtlasTexturesEvent,
+    TypedAtlasDefinition,
+};
+use std::sync::{atomic::AtomicBool, Arc};
+
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy, strum::EnumVariantNames, strum::EnumString)]
+enum MyAtlasTextures {
+    Pacman,
+}
+
+#[test]
+fn definition_can_be_loaded_from_toml() {
+    let mut app = minimal_app();
+    app.add_plugin(AtlasTexturePlugin::<MyAtlasTextures>::default());
+
+    // no 3'rd party plugin needed, as the `toml` feature adds a loader for `.atlas.toml`
+    app.add_startup_system(move |mut cmds: Commands, assets: Res<AssetServer>| {
+        cmds.insert_resource(TypedAtlasDefinition::<MyAtlasTextures>::from(
+            assets.load("sprite_sheets.atlas.toml"),
+        ));
+    });
+
+    // add system for capturing event
+    let is_created = Arc::new(AtomicBool::new(false));
+    app.add_system({
+        let is_created = is_created.clone();
+        move |mut events: EventReader<AtlasTexturesEvent<MyAtlasTextures>>| {
+            for e in events.iter() {
+                if e.status().is_created() {
+                    is_created.store(true, std::sync::atomic::Ordering::Release);
+                }
+            }
+        }
+    });
+
+    // spin Bevy a few times...
+    (0..100).for_each(|_| app.update());
+
+    // event signalling everything OK
+    assert!(is_created.load(std::sync::atomic::Ordering::Acquire));
+
+    let atlas_textures = app.world.resource::<AtlasTextures<MyAtlasTextures>>();
+    assert!(atlas_textures
+        .animation(MyAtlasTextures::Pacman, "chomp")
+        .is_some());
+}